@@ -0,0 +1,29 @@
+//benches/generation.rs
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use shiftropolis::app::generation::ArenaGenerator;
+use shiftropolis::app::monitoring::AnomalyMonitor;
+
+const SIZES: &[u32] = &[8, 12, 16, 24, 32];
+const RULE_COUNT: u32 = 3;
+
+fn generate_with_monitoring(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generate_with_monitoring");
+
+    for &size in SIZES {
+        group.bench_with_input(BenchmarkId::from_parameter(format!("{}x{}", size, size)), &size, |b, &size| {
+            let mut seed = 0u64;
+            b.iter(|| {
+                // New seed per iteration so a lucky layout does not skew the timing
+                seed += 1;
+                let mut monitor = AnomalyMonitor::new();
+                let result = ArenaGenerator::new(Some(seed)).generate_with_monitoring(size, RULE_COUNT, &mut monitor);
+                black_box(result.is_ok())
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, generate_with_monitoring);
+criterion_main!(benches);
@@ -36,7 +36,7 @@ impl Arena {
 
         // Minimum walkable area
         let walkable_count = self.get_walkable_cells().len();
-        let min_walkable = (self.width * self.height / 4) as usize; // At least 25%
+        let min_walkable = self.playable_cell_count() / 4; // At least 25%
 
         if walkable_count < min_walkable {
             result.add_error(&format!(
@@ -72,6 +72,18 @@ impl Arena {
                 ));
             }
 
+            // Fragile floors break under the player: orbs behind them may be lost for a while
+            let permanent = self.get_permanently_reachable_positions(start);
+            let temporary_orbs = orb_positions.iter()
+                .filter(|cell| reachable.contains(&(cell.x, cell.y)) && !permanent.contains(&(cell.x, cell.y)))
+                .count();
+            if temporary_orbs > 0 {
+                result.add_warning(&format!(
+                    "{} energy orbs are only reachable across fragile floors",
+                    temporary_orbs
+                ));
+            }
+
             // Check if interactive elements are reachable
             let interactive_positions = self.get_interactive_elements();
             let unreachable_interactive: Vec<_> = interactive_positions.iter()
@@ -174,6 +186,10 @@ impl Arena {
                     }
                 }
 
+                RuleId::CrumblingFloor if self.count_modules_by_type(&ModuleId::FloorFragile) == 0 => {
+                    result.add_error("Crumbling Floor rule active but no fragile floors found");
+                }
+
                 RuleId::OrbCollection => {
                     let orb_count = self.count_modules_by_type(&ModuleId::OrbEnergy);
                     let expected_min = 5; // Based on rule parameters
@@ -258,7 +274,7 @@ impl Arena {
         let empty_zones = self.find_large_empty_zones();
 
         for zone in empty_zones {
-            if zone.size > (self.width * self.height / 8) as usize {
+            if zone.size > self.playable_cell_count() / 8 {
                 result.add_warning(&format!(
                     "Large empty zone detected at ({}, {}) with {} cells",
                     zone.center.0, zone.center.1, zone.size
@@ -269,7 +285,7 @@ impl Arena {
 
     // Helper methods
 
-    fn get_player_position(&self) -> Option<(i32, i32)> {
+    pub fn get_player_position(&self) -> Option<(i32, i32)> {
         self.modules.iter()
             .find(|cell| matches!(cell.module_id, ModuleId::Player))
             .map(|cell| (cell.x, cell.y))
@@ -295,7 +311,7 @@ impl Arena {
             .collect()
     }
 
-    fn get_hazard_positions(&self) -> Vec<(i32, i32)> {
+    pub fn get_hazard_positions(&self) -> Vec<(i32, i32)> {
         self.modules.iter()
             .filter(|cell| matches!(cell.module_id,
                 ModuleId::HazardLavaPit |
@@ -306,28 +322,44 @@ impl Arena {
             .collect()
     }
 
-    fn get_reachable_positions(&self, start: (i32, i32)) -> HashSet<(i32, i32)> {
-        let mut visited = HashSet::new();
+    /// Flood fill over walkable and collectible cells, starting from `start`
+    pub fn get_reachable_positions(&self, start: (i32, i32)) -> HashSet<(i32, i32)> {
+        self.get_path_distances(start).into_keys().collect()
+    }
+
+    /// Same flood fill with every fragile floor broken: what stays reachable at all times
+    pub fn get_permanently_reachable_positions(&self, start: (i32, i32)) -> HashSet<(i32, i32)> {
+        self.flood_fill(start, false).into_keys().collect()
+    }
+
+    /// Shortest walking distance (in cells) from `start` to every reachable position.
+    /// Fragile floors are crossed while intact.
+    pub fn get_path_distances(&self, start: (i32, i32)) -> HashMap<(i32, i32), u32> {
+        self.flood_fill(start, true)
+    }
+
+    fn flood_fill(&self, start: (i32, i32), cross_fragile: bool) -> HashMap<(i32, i32), u32> {
+        let mut distances = HashMap::new();
         let mut queue = VecDeque::new();
+        distances.insert(start, 0);
         queue.push_back(start);
 
         while let Some((x, y)) = queue.pop_front() {
-            if visited.contains(&(x, y)) {
-                continue;
-            }
-
-            visited.insert((x, y));
+            let distance = distances[&(x, y)];
 
             // Check adjacent cells
             for (nx, ny) in self.get_adjacent_positions(x, y) {
-                if !visited.contains(&(nx, ny)) {
+                if !distances.contains_key(&(nx, ny)) {
                     if let Some(cell) = self.get_cell(nx, ny) {
                         // Consider walkable or collectible surfaces as reachable
-                        if matches!(cell.module_id,
+                        let traversable = matches!(cell.module_id,
                             ModuleId::FloorStd | ModuleId::FloorLarge |
                             ModuleId::RampSteep | ModuleId::RampLow |
                             ModuleId::OrbEnergy |
-                            ModuleId::InteractButtonFloor) {
+                            ModuleId::InteractButtonFloor)
+                            || (cross_fragile && cell.module_id == ModuleId::FloorFragile);
+                        if traversable {
+                            distances.insert((nx, ny), distance + 1);
                             queue.push_back((nx, ny));
                         }
                     }
@@ -335,7 +367,7 @@ impl Arena {
             }
         }
 
-        visited
+        distances
     }
     
     fn count_adjacent_walkable(&self, x: i32, y: i32) -> usize {
@@ -0,0 +1,371 @@
+//core/config.rs
+use crate::app::core::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Every knob of a generation run. Serializable so presets can live in files and
+/// so each arena records exactly how it was made.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GenerationConfig {
+    /// Preset this config came from, or "custom"
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub shape: ArenaShape,
+    /// Total number of active rules, forced ones included
+    pub rule_count: u32,
+    /// Required: always active, in this order, before any random pick
+    #[serde(alias = "required_rules")]
+    pub forced_rules: Vec<RuleId>,
+    /// Never picked at random
+    pub forbidden_rules: Vec<RuleId>,
+    /// Weighted up in the random pick, but not guaranteed
+    pub preferred_rules: Vec<RuleId>,
+    /// Bounds on how many active rules carry a given tag
+    pub rule_quotas: Vec<RuleQuota>,
+    /// Env values applied after rule effects and variance (clamped to the variable's range)
+    pub forced_env: HashMap<EnvVarId, f64>,
+    /// 0.0 (easiest) to 1.0 (hardest); biases rule selection when set
+    pub target_difficulty: Option<f32>,
+    pub densities: DensityConfig,
+    /// Repair passes before giving up on a broken arena (0 disables repair)
+    pub max_repair_attempts: u32,
+    /// Wall-clock limit for one generation, checked between stages and inside the layout pass
+    pub time_budget_ms: Option<u64>,
+}
+
+/// Between `min` and `max` active rules must carry `tag` (e.g. at most one "hazard" rule)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleQuota {
+    pub tag: String,
+    #[serde(default)]
+    pub min: u32,
+    #[serde(default)]
+    pub max: Option<u32>,
+}
+
+impl RuleQuota {
+    pub fn new(tag: &str, min: u32, max: Option<u32>) -> Self {
+        Self { tag: tag.to_string(), min, max }
+    }
+
+    pub fn applies_to(&self, rule: &Rule) -> bool {
+        rule.tags.iter().any(|t| t == &self.tag)
+    }
+
+    /// Rules among `rules` that count towards this quota
+    pub fn usage(&self, rules: &[Rule]) -> u32 {
+        rules.iter().filter(|rule| self.applies_to(rule)).count() as u32
+    }
+
+    pub fn is_full(&self, rules: &[Rule]) -> bool {
+        self.max.is_some_and(|max| self.usage(rules) >= max)
+    }
+
+    /// Picks still needed to reach `min`
+    pub fn deficit(&self, rules: &[Rule]) -> u32 {
+        self.min.saturating_sub(self.usage(rules))
+    }
+}
+
+/// Default for `GenerationConfig::time_budget_ms`
+pub const DEFAULT_TIME_BUDGET_MS: u64 = 2000;
+
+/// Upper bound for `DensityConfig::hazards`
+pub const MAX_HAZARD_WEIGHT: f32 = 4.0;
+
+/// Layout ratios, all relative to the number of playable cells
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DensityConfig {
+    /// Share of cells the WFC pass fills
+    pub layout: f32,
+    /// Multiplier on the WFC weight of hazard modules
+    pub hazards: f32,
+    pub orbs: f64,
+    pub min_orbs: usize,
+    pub interactive: f64,
+    pub min_interactive: usize,
+    /// Walkable share below which the balance stage pads with floor
+    pub min_walkable: f64,
+    /// Chance for each lava pit to spread one cell under LAVA_FLOOR
+    pub lava_expansion_chance: f32,
+}
+
+impl Default for DensityConfig {
+    fn default() -> Self {
+        Self {
+            layout: 0.6,
+            hazards: 1.0,
+            orbs: 1.0 / 20.0,
+            min_orbs: 3,
+            interactive: 1.0 / 40.0,
+            min_interactive: 1,
+            min_walkable: 1.0 / 3.0,
+            lava_expansion_chance: 0.5,
+        }
+    }
+}
+
+impl DensityConfig {
+    /// `ratio` of `cells`, rounded down; the epsilon absorbs float error on exact multiples
+    pub fn scaled(cells: usize, ratio: f64) -> usize {
+        (cells as f64 * ratio + 1e-9).floor() as usize
+    }
+}
+
+impl Default for GenerationConfig {
+    fn default() -> Self {
+        Self::normal()
+    }
+}
+
+impl GenerationConfig {
+    pub fn easy() -> Self {
+        Self {
+            name: "easy".to_string(),
+            width: 10,
+            height: 10,
+            rule_count: 1,
+            forbidden_rules: vec![RuleId::ProjectileRain, RuleId::NoJump],
+            target_difficulty: Some(0.2),
+            densities: DensityConfig {
+                orbs: 0.07,
+                min_walkable: 0.45,
+                lava_expansion_chance: 0.25,
+                ..Default::default()
+            },
+            ..Self::normal()
+        }
+    }
+
+    /// The values generation used before configs existed
+    pub fn normal() -> Self {
+        Self {
+            name: "normal".to_string(),
+            width: 12,
+            height: 12,
+            shape: ArenaShape::Rectangle,
+            rule_count: 2,
+            forced_rules: Vec::new(),
+            forbidden_rules: Vec::new(),
+            preferred_rules: Vec::new(),
+            rule_quotas: Vec::new(),
+            forced_env: HashMap::new(),
+            target_difficulty: None,
+            densities: DensityConfig::default(),
+            max_repair_attempts: 3,
+            time_budget_ms: Some(DEFAULT_TIME_BUDGET_MS),
+        }
+    }
+
+    pub fn hard() -> Self {
+        Self {
+            name: "hard".to_string(),
+            width: 16,
+            height: 16,
+            rule_count: 3,
+            target_difficulty: Some(0.75),
+            densities: DensityConfig {
+                orbs: 1.0 / 25.0,
+                min_walkable: 0.3,
+                lava_expansion_chance: 0.65,
+                ..Default::default()
+            },
+            ..Self::normal()
+        }
+    }
+
+    pub fn chaos() -> Self {
+        Self {
+            name: "chaos".to_string(),
+            width: 20,
+            height: 20,
+            rule_count: 4,
+            target_difficulty: Some(1.0),
+            densities: DensityConfig {
+                layout: 0.7,
+                interactive: 1.0 / 25.0,
+                min_walkable: 0.3,
+                lava_expansion_chance: 0.85,
+                ..Default::default()
+            },
+            ..Self::normal()
+        }
+    }
+
+    pub fn preset(name: &str) -> Result<Self, ArenaGenerationError> {
+        match name {
+            "easy" => Ok(Self::easy()),
+            "normal" => Ok(Self::normal()),
+            "hard" => Ok(Self::hard()),
+            "chaos" => Ok(Self::chaos()),
+            other => Err(invalid(format!("unknown preset '{}' (expected easy, normal, hard or chaos)", other))),
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ArenaGenerationError> {
+        let config: Self = serde_json::from_str(json)
+            .map_err(|e| invalid(format!("malformed config: {}", e)))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ArenaGenerationError> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| invalid(format!("cannot read {}: {}", path.display(), e)))?;
+        Self::from_json(&json)
+    }
+
+    /// `<dir>/<name>.json` if present, otherwise the built-in preset of that name
+    pub fn load_preset(dir: impl AsRef<Path>, name: &str) -> Result<Self, ArenaGenerationError> {
+        let path = dir.as_ref().join(format!("{}.json", name));
+        if path.exists() {
+            Self::from_file(path)
+        } else {
+            Self::preset(name)
+        }
+    }
+
+    pub fn to_json_string(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn with_shape(mut self, shape: ArenaShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    pub fn with_rule_count(mut self, rule_count: u32) -> Self {
+        self.rule_count = rule_count;
+        self
+    }
+
+    /// Exactly these rules and no random pick
+    pub fn with_fixed_rules(mut self, rules: Vec<RuleId>) -> Self {
+        self.rule_count = rules.len() as u32;
+        self.forced_rules = rules;
+        self
+    }
+
+    /// Always active, on top of the current forced rules; the rule count grows if it
+    /// no longer covers them
+    pub fn require_rule(mut self, rule: RuleId) -> Self {
+        if !self.forced_rules.contains(&rule) {
+            self.forced_rules.push(rule);
+        }
+        self.rule_count = self.rule_count.max(self.forced_rules.len() as u32);
+        self
+    }
+
+    pub fn forbid_rule(mut self, rule: RuleId) -> Self {
+        if !self.forbidden_rules.contains(&rule) {
+            self.forbidden_rules.push(rule);
+        }
+        self
+    }
+
+    pub fn prefer_rule(mut self, rule: RuleId) -> Self {
+        if !self.preferred_rules.contains(&rule) {
+            self.preferred_rules.push(rule);
+        }
+        self
+    }
+
+    pub fn with_rule_quota(mut self, tag: &str, min: u32, max: Option<u32>) -> Self {
+        self.rule_quotas.push(RuleQuota::new(tag, min, max));
+        self
+    }
+
+    pub fn with_forced_env(mut self, id: EnvVarId, value: f64) -> Self {
+        self.forced_env.insert(id, value);
+        self
+    }
+
+    /// None lifts the limit
+    pub fn with_time_budget(mut self, budget_ms: Option<u64>) -> Self {
+        self.time_budget_ms = budget_ms;
+        self
+    }
+
+    pub fn with_target_difficulty(mut self, difficulty: f32) -> Self {
+        self.target_difficulty = Some(difficulty);
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), ArenaGenerationError> {
+        if self.width == 0 || self.height == 0 {
+            return Err(invalid(format!("arena size must be positive, got {}x{}", self.width, self.height)));
+        }
+
+        if let ArenaShape::Mask(mask) = &self.shape {
+            if !mask.is_well_formed() {
+                return Err(invalid(format!("mask of {}x{} cells has {} entries", mask.width, mask.height, mask.cells.len())));
+            }
+        }
+
+        if let Some(rule) = self.forced_rules.iter().find(|r| self.forbidden_rules.contains(r)) {
+            return Err(invalid(format!("rule {} is both forced and forbidden", rule)));
+        }
+
+        if self.forced_rules.len() > self.rule_count as usize {
+            return Err(invalid(format!("{} forced rules but rule_count is {}", self.forced_rules.len(), self.rule_count)));
+        }
+
+        if let Some(rule) = self.preferred_rules.iter().find(|r| self.forbidden_rules.contains(r)) {
+            return Err(invalid(format!("rule {} is both preferred and forbidden", rule)));
+        }
+
+        for quota in &self.rule_quotas {
+            if quota.max.is_some_and(|max| quota.min > max) {
+                return Err(invalid(format!("quota for tag '{}' has min {} above max {:?}", quota.tag, quota.min, quota.max)));
+            }
+            if quota.min > self.rule_count {
+                return Err(invalid(format!("quota for tag '{}' needs {} rules but rule_count is {}", quota.tag, quota.min, self.rule_count)));
+            }
+        }
+
+        if self.time_budget_ms == Some(0) {
+            return Err(invalid("time budget must be positive (use None for no limit)".to_string()));
+        }
+
+        if let Some(difficulty) = self.target_difficulty {
+            if !(0.0..=1.0).contains(&difficulty) {
+                return Err(invalid(format!("target difficulty must be within 0.0..=1.0, got {}", difficulty)));
+            }
+        }
+
+        let d = &self.densities;
+        let ratios = [
+            ("layout", d.layout as f64),
+            ("orbs", d.orbs),
+            ("interactive", d.interactive),
+            ("min_walkable", d.min_walkable),
+            ("lava_expansion_chance", d.lava_expansion_chance as f64),
+        ];
+        for (name, value) in ratios {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(invalid(format!("density '{}' must be within 0.0..=1.0, got {}", name, value)));
+            }
+        }
+
+        if !(0.0..=MAX_HAZARD_WEIGHT).contains(&d.hazards) {
+            return Err(invalid(format!("density 'hazards' must be within 0.0..={}, got {}", MAX_HAZARD_WEIGHT, d.hazards)));
+        }
+
+        Ok(())
+    }
+}
+
+fn invalid(details: String) -> ArenaGenerationError {
+    ArenaGenerationError::InvalidConfiguration { details }
+}
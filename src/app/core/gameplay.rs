@@ -278,8 +278,8 @@ impl MutationEngine {
             let x = self.rng.gen_range(0..arena.width as i32);
             let y = self.rng.gen_range(0..arena.height as i32);
 
-            // Vérifier si la position est libre
-            if arena.get_cell(x, y).is_none() {
+            // Vérifier si la position est libre et dans l'empreinte de l'arène
+            if arena.is_valid_position(x, y) && arena.get_cell(x, y).is_none() {
                 arena.modules.push(ArenaCell {
                     x,
                     y,
@@ -369,7 +369,7 @@ impl MutationEngine {
 }
 
 // 3. SYSTÈME DE DIFFICULTÉ PROGRESSIVE
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Resource)]
 pub struct DifficultySystem {
     pub current_level: f32,
     pub base_level: f32,
@@ -378,6 +378,12 @@ pub struct DifficultySystem {
     pub shift_survival_bonus: f32,
 }
 
+impl Default for DifficultySystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DifficultySystem {
     pub fn new() -> Self {
         Self {
@@ -422,10 +428,16 @@ impl DifficultySystem {
     pub fn get_spawn_multiplier(&self) -> f32 {
         1.0 + (self.current_level - 1.0) * 0.3
     }
+
+    /// Difficulté d'arène visée (0.0..=1.0) pour `ArenaGenerator::generate_for_difficulty`.
+    /// Même une arène "facile" garde un minimum de dangers, d'où le plancher.
+    pub fn get_arena_difficulty_target(&self) -> f32 {
+        0.15 + 0.75 * self.get_mutation_intensity()
+    }
 }
 
 // 4. SYSTÈME DE PRESSION DES DANGERS ACCRUS
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Resource)]
 pub struct DangerPressureSystem {
     pub player_position: (f32, f32),
     pub last_movement_time: Instant,
@@ -453,6 +465,12 @@ pub enum ThreatType {
     EnergyDrain,
 }
 
+impl Default for DangerPressureSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DangerPressureSystem {
     pub fn new() -> Self {
         Self {
@@ -0,0 +1,188 @@
+//core/lava.rs
+use crate::app::core::*;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+/// Salt mixed into the arena seed, so lava doesn't mirror the generator's own draws
+const LAVA_SEED_SALT: u64 = 0x4C41_5641_464C_4F57;
+
+/// Seconds between spread attempts at intensity 1.0
+const BASE_SPREAD_INTERVAL: f32 = 4.0;
+/// Chance for each lava cell to overflow on a spread attempt
+const SPREAD_CHANCE: f64 = 0.5;
+/// Seconds a spread cell stays molten before cooling back to what it was
+pub const LAVA_COOL_TIME: f32 = 12.0;
+/// Spread cells allowed per original pit at intensity 1.0
+const MAX_FLOWS_PER_SOURCE: f32 = 2.0;
+
+/// A cell that turned to lava or cooled back to what it was; the arena is already updated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LavaChange {
+    Spread(i32, i32),
+    Cooled(i32, i32),
+}
+
+impl LavaChange {
+    pub fn cell(&self) -> (i32, i32) {
+        match *self {
+            LavaChange::Spread(x, y) | LavaChange::Cooled(x, y) => (x, y),
+        }
+    }
+}
+
+/// A spread cell, with what lava replaced there
+#[derive(Debug, Clone)]
+struct Flow {
+    cell: (i32, i32),
+    /// Remaining heat in seconds
+    heat: f32,
+    /// Modules the lava took over; empty for an empty cell
+    covered: Vec<ArenaCell>,
+}
+
+/// Lava dynamics of an arena: the original pits overflow onto neighbouring floor or empty
+/// cells, and the overflow cools back to what it covered. Lava never cuts the spawn off
+/// from an orb it could reach. Every change is written into the `Arena`, so validation
+/// and the minimap always see the current layout.
+#[derive(Debug, Clone)]
+pub struct LavaFlow {
+    rng: StdRng,
+    /// Pits placed by the generator; they never cool
+    sources: Vec<(i32, i32)>,
+    /// Spread cells, oldest first
+    flows: Vec<Flow>,
+    until_spread: f32,
+}
+
+impl LavaFlow {
+    pub fn new(arena: &Arena) -> Self {
+        let mut sources: Vec<(i32, i32)> = arena.get_modules_by_type(&ModuleId::HazardLavaPit).iter()
+            .map(|cell| (cell.x, cell.y))
+            .collect();
+        sources.sort_unstable();
+        sources.dedup();
+
+        Self {
+            rng: StdRng::seed_from_u64(arena.generation_metadata.seed ^ LAVA_SEED_SALT),
+            sources,
+            flows: Vec::new(),
+            until_spread: BASE_SPREAD_INTERVAL,
+        }
+    }
+
+    /// Spread intensity from the `LavaFloor` rule (its `intensity` parameter, 1.0 by default);
+    /// 0.0 when the rule is inactive
+    pub fn rule_intensity(arena: &Arena) -> f32 {
+        arena.active_rules.iter()
+            .find(|rule| rule.id == RuleId::LavaFloor)
+            .map_or(0.0, |rule| rule.parameters.as_ref()
+                .and_then(|p| p.get("intensity"))
+                .and_then(|v| v.as_f64())
+                .unwrap_or(1.0) as f32)
+    }
+
+    pub fn sources(&self) -> &[(i32, i32)] {
+        &self.sources
+    }
+
+    /// Spread cells still molten, oldest first
+    pub fn flows(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.flows.iter().map(|flow| flow.cell)
+    }
+
+    /// Most spread cells at once for `intensity`
+    pub fn max_flows(&self, intensity: f32) -> usize {
+        (self.sources.len() as f32 * MAX_FLOWS_PER_SOURCE * intensity.max(0.0)).round() as usize
+    }
+
+    /// Lava only takes over empty cells and plain floor
+    pub fn can_spread_into(arena: &Arena, x: i32, y: i32) -> bool {
+        arena.is_valid_position(x, y) && arena.modules.iter()
+            .filter(|cell| cell.x == x && cell.y == y)
+            .all(|cell| matches!(cell.module_id, ModuleId::FloorStd | ModuleId::FloorLarge))
+    }
+
+    /// Advance by `dt`: cool old flows, then spread at a pace scaled by `intensity`.
+    /// Returns the cells changed in `arena`, in order.
+    pub fn tick(&mut self, arena: &mut Arena, intensity: f32, dt: f32) -> Vec<LavaChange> {
+        let mut changes = Vec::new();
+
+        for flow in self.flows.iter_mut() {
+            flow.heat -= dt;
+            let (x, y) = flow.cell;
+            // A cell rebuilt by a mutation in the meantime is left alone
+            let still_lava = arena.get_cell(x, y).is_some_and(|c| c.module_id == ModuleId::HazardLavaPit);
+            if flow.heat <= 0.0 && still_lava {
+                arena.remove_modules_at(x, y);
+                arena.modules.append(&mut flow.covered);
+                changes.push(LavaChange::Cooled(x, y));
+            }
+        }
+        self.flows.retain(|flow| flow.heat > 0.0);
+
+        if intensity <= 0.0 {
+            return changes;
+        }
+
+        self.until_spread -= dt * intensity;
+        while self.until_spread <= 0.0 {
+            self.until_spread += BASE_SPREAD_INTERVAL;
+            self.spread(arena, intensity, &mut changes);
+        }
+        changes
+    }
+
+    /// Whether lava at (x, y) leaves the spawn a way to every orb in `reachable_orbs`
+    fn keeps_orbs_reachable(arena: &Arena, x: i32, y: i32, reachable_orbs: &[(i32, i32)]) -> bool {
+        let Some(spawn) = arena.get_player_position() else {
+            return true;
+        };
+        let mut after = arena.clone();
+        after.set_module(x, y, ModuleId::HazardLavaPit, None);
+        let reachable = after.get_reachable_positions(spawn);
+        reachable_orbs.iter().all(|orb| reachable.contains(orb))
+    }
+
+    /// Orbs the player can walk to from the spawn right now
+    fn reachable_orbs(arena: &Arena) -> Vec<(i32, i32)> {
+        let Some(spawn) = arena.get_player_position() else {
+            return Vec::new();
+        };
+        let reachable = arena.get_reachable_positions(spawn);
+        arena.get_modules_by_type(&ModuleId::OrbEnergy).iter()
+            .map(|cell| (cell.x, cell.y))
+            .filter(|orb| reachable.contains(orb))
+            .collect()
+    }
+
+    fn spread(&mut self, arena: &mut Arena, intensity: f32, changes: &mut Vec<LavaChange>) {
+        let max_flows = self.max_flows(intensity);
+        let lava: Vec<(i32, i32)> = self.sources.iter().copied().chain(self.flows()).collect();
+        let reachable_orbs = Self::reachable_orbs(arena);
+
+        for (x, y) in lava {
+            if self.flows.len() >= max_flows {
+                break;
+            }
+            // A source replaced by a mutation no longer overflows
+            if arena.get_cell(x, y).is_none_or(|cell| cell.module_id != ModuleId::HazardLavaPit) {
+                continue;
+            }
+            if !self.rng.gen_bool(SPREAD_CHANCE) {
+                continue;
+            }
+
+            let targets: Vec<(i32, i32)> = arena.get_adjacent_positions(x, y).into_iter()
+                .filter(|&(nx, ny)| Self::can_spread_into(arena, nx, ny))
+                .filter(|&(nx, ny)| Self::keeps_orbs_reachable(arena, nx, ny, &reachable_orbs))
+                .collect();
+            if let Some(&(nx, ny)) = targets.choose(&mut self.rng) {
+                let covered = arena.remove_modules_at(nx, ny);
+                arena.add_module(nx, ny, ModuleId::HazardLavaPit, None);
+                self.flows.push(Flow { cell: (nx, ny), heat: LAVA_COOL_TIME, covered });
+                changes.push(LavaChange::Spread(nx, ny));
+            }
+        }
+    }
+}
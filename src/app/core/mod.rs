@@ -1,9 +1,19 @@
 pub mod types;
 pub mod arena;
+pub mod shape;
+pub mod config;
 pub mod gameplay;
+pub mod theme;
+pub mod projectiles;
+pub mod lava;
 
 pub use types::*;
 pub use arena::*;
+pub use shape::*;
+pub use config::*;
+pub use theme::*;
+pub use projectiles::*;
+pub use lava::*;
 pub use gameplay::ShiftManager;
 
 use serde::{Deserialize, Serialize};
@@ -21,6 +31,7 @@ pub enum RuleId {
     ProjectileRain,
     OrbCollection,
     MoonGravity,
+    CrumblingFloor,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -35,6 +46,7 @@ pub enum ModuleId {
     OrbEnergy,
     FloorStd,
     FloorLarge,
+    FloorFragile,
     WallLow,
     WallHigh,
     PanelGlass,
@@ -83,7 +95,7 @@ pub struct ModuleDefinition {
     pub wfc_weight: Option<u32>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArenaCell {
     pub x: i32,
     pub y: i32,
@@ -92,12 +104,12 @@ pub struct ArenaCell {
     pub connections: Vec<Direction>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Direction {
     North, South, East, West, Up, Down
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Arena {
     pub id: Uuid,
     pub width: u32,
@@ -106,14 +118,20 @@ pub struct Arena {
     pub active_rules: Vec<Rule>,
     pub env_variables: HashMap<EnvVarId, f64>,
     pub generation_metadata: GenerationMetadata,
+    /// Playable footprint; None means the whole width x height box
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mask: Option<ArenaMask>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GenerationMetadata {
     pub seed: u64,
     pub generation_time_ms: u64,
     pub algorithm_version: String,
     pub constraints_applied: Vec<String>,
+    /// Config the arena was generated from
+    #[serde(default)]
+    pub config: Option<GenerationConfig>,
 }
 
 impl Arena {
@@ -130,7 +148,9 @@ impl Arena {
                 generation_time_ms: 0,
                 algorithm_version: "1.0.0".to_string(),
                 constraints_applied: Vec::new(),
+                config: None,
             },
+            mask: None,
         }
     }
     
@@ -154,29 +174,59 @@ impl Arena {
     }
     
     pub fn validate_structural_integrity(&self) -> Vec<String> {
+        self.structural_issues().iter().map(ToString::to_string).collect()
+    }
+
+    /// Structural problems that make the arena unplayable, in a fixed order
+    pub fn structural_issues(&self) -> Vec<StructuralIssue> {
         let mut issues = Vec::new();
         
         // Check if player exists
         if !self.modules.iter().any(|cell| matches!(cell.module_id, ModuleId::Player)) {
-            issues.push("No player spawn point found".to_string());
+            issues.push(StructuralIssue::MissingPlayerSpawn);
         }
         
         // Check for minimum walkable surfaces
-        let walkable_count = self.modules.iter()
+        let walkable: Vec<(i32, i32)> = self.modules.iter()
             .filter(|cell| matches!(cell.module_id, 
                 ModuleId::FloorStd | ModuleId::FloorLarge | ModuleId::RampSteep))
-            .count();
+            .map(|cell| (cell.x, cell.y))
+            .collect();
             
-        if walkable_count < 3 {
-            issues.push(format!("Insufficient walkable surfaces: {} (minimum 3)", walkable_count));
+        if walkable.len() < MIN_WALKABLE_SURFACES {
+            issues.push(StructuralIssue::InsufficientWalkableSurfaces(walkable));
         }
         
         // Check for energy orbs
         let orb_count = self.count_modules_by_type(&ModuleId::OrbEnergy);
         if orb_count == 0 {
-            issues.push("No energy orbs found".to_string());
+            issues.push(StructuralIssue::MissingEnergyOrbs);
         }
         
         issues
     }
 }
+
+/// Fewest walkable cells a playable arena may have
+pub const MIN_WALKABLE_SURFACES: usize = 3;
+
+/// A structural check failed by `Arena::structural_issues`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StructuralIssue {
+    MissingPlayerSpawn,
+    /// The walkable cells that were found
+    InsufficientWalkableSurfaces(Vec<(i32, i32)>),
+    MissingEnergyOrbs,
+}
+
+impl std::fmt::Display for StructuralIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StructuralIssue::MissingPlayerSpawn => write!(f, "No player spawn point found"),
+            StructuralIssue::InsufficientWalkableSurfaces(walkable) => write!(
+                f, "Insufficient walkable surfaces: {} (minimum {})", walkable.len(), MIN_WALKABLE_SURFACES
+            ),
+            StructuralIssue::MissingEnergyOrbs => write!(f, "No energy orbs found"),
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//core/projectiles.rs
+use crate::app::core::*;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+/// Salt mixed into the arena seed, so volleys don't mirror the generator's own draws
+const PROJECTILE_SEED_SALT: u64 = 0x5052_4F4A_5241_494E;
+
+/// Seconds between volleys at pace 1.0
+const BASE_VOLLEY_INTERVAL: f32 = 6.0;
+const MIN_VOLLEY_INTERVAL: f32 = 0.75;
+/// Warning time before impact at GameSpeed 1.0; never shorter than the floor below
+const BASE_TELEGRAPH: f32 = 1.6;
+const MIN_TELEGRAPH: f32 = 0.6;
+const MAX_VOLLEY_SIZE: usize = 16;
+const BASE_PROJECTILE_DAMAGE: f32 = 15.0;
+
+/// Layout of the impact points of one volley
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolleyPattern {
+    /// Random cells over the whole arena
+    Scatter,
+    /// A row or column through one cell
+    Line,
+    /// The cells around one cell, which stays safe
+    Ring,
+    /// A plus sign centred on one cell
+    Cross,
+}
+
+impl VolleyPattern {
+    /// Patterns unlock as difficulty rises
+    pub fn available(difficulty: f32) -> Vec<VolleyPattern> {
+        [
+            (VolleyPattern::Scatter, 0.0),
+            (VolleyPattern::Line, 1.0),
+            (VolleyPattern::Ring, 1.5),
+            (VolleyPattern::Cross, 2.0),
+        ].into_iter()
+            .filter(|(_, threshold)| difficulty >= *threshold)
+            .map(|(pattern, _)| pattern)
+            .collect()
+    }
+}
+
+/// Projectiles falling together, each announced on its cell for `telegraph` seconds
+#[derive(Debug, Clone, PartialEq)]
+pub struct Volley {
+    pub pattern: VolleyPattern,
+    pub targets: Vec<(i32, i32)>,
+    pub telegraph: f32,
+    pub damage: f32,
+}
+
+/// Deterministic projectile timeline of an arena: the same seed, difficulty and
+/// env variables give the same volleys at the same times.
+#[derive(Debug, Clone)]
+pub struct ProjectileSchedule {
+    rng: StdRng,
+    /// Cells the player can stand on, sorted
+    targets: Vec<(i32, i32)>,
+    pub interval: f32,
+    pub telegraph: f32,
+    pub volley_size: usize,
+    pub damage: f32,
+    pub difficulty: f32,
+    until_next: f32,
+}
+
+impl ProjectileSchedule {
+    /// Schedule for `arena` when `ProjectileRain` is active, scaled by the rule's intensity and
+    /// frequency, by `difficulty` (1.0 = base) and by the arena's `GameSpeed`
+    pub fn for_arena(arena: &Arena, difficulty: f32) -> Option<Self> {
+        let rule = arena.active_rules.iter().find(|rule| rule.id == RuleId::ProjectileRain)?;
+        let rule_param = |key: &str| rule.parameters.as_ref()
+            .and_then(|p| p.get(key))
+            .and_then(|v| v.as_f64())
+            .unwrap_or(1.0) as f32;
+        let game_speed = arena.env_variables.get(&EnvVarId::GameSpeed).copied().unwrap_or(1.0).max(0.1) as f32;
+
+        let targets = Self::target_cells(arena);
+        if targets.is_empty() {
+            return None;
+        }
+
+        let difficulty = difficulty.max(0.0);
+        let intensity = rule_param("intensity");
+        let pace = rule_param("frequency") * game_speed * (0.75 + 0.25 * difficulty);
+        let volley_size = (2.0 + intensity * difficulty * 1.5).round().clamp(1.0, MAX_VOLLEY_SIZE as f32) as usize;
+        let interval = (BASE_VOLLEY_INTERVAL / pace.max(0.01)).max(MIN_VOLLEY_INTERVAL);
+
+        Some(Self {
+            rng: StdRng::seed_from_u64(arena.generation_metadata.seed ^ PROJECTILE_SEED_SALT),
+            targets,
+            interval,
+            // Faster games warn later, but always leave time to step aside
+            telegraph: (BASE_TELEGRAPH / game_speed).max(MIN_TELEGRAPH),
+            volley_size,
+            damage: BASE_PROJECTILE_DAMAGE * intensity,
+            difficulty,
+            // A first breather before the opening volley
+            until_next: interval,
+        })
+    }
+
+    /// Where the player can be: reachable from the spawn, or every walkable cell without one
+    fn target_cells(arena: &Arena) -> Vec<(i32, i32)> {
+        let mut cells: Vec<(i32, i32)> = match arena.get_player_position() {
+            Some(spawn) => arena.get_reachable_positions(spawn).into_iter().collect(),
+            None => arena.modules.iter()
+                .filter(|cell| matches!(cell.module_id, ModuleId::FloorStd | ModuleId::FloorLarge | ModuleId::RampLow | ModuleId::RampSteep))
+                .map(|cell| (cell.x, cell.y))
+                .collect(),
+        };
+        cells.sort_unstable();
+        cells
+    }
+
+    /// Half-width of the Ring and Cross shapes
+    fn reach(&self) -> i32 {
+        (self.volley_size as i32 / 4).max(1)
+    }
+
+    /// Most cells a single volley can target: the largest shape among the unlocked patterns.
+    /// Shapes don't follow `volley_size` exactly; a Ring of reach r covers 8r cells.
+    pub fn max_volley_targets(&self) -> usize {
+        let reach = self.reach() as usize;
+        VolleyPattern::available(self.difficulty).into_iter()
+            .map(|pattern| match pattern {
+                VolleyPattern::Scatter => self.volley_size,
+                VolleyPattern::Line => self.volley_size / 2 * 2 + 1,
+                VolleyPattern::Ring => 8 * reach,
+                VolleyPattern::Cross => 1 + 4 * reach,
+            })
+            // A shape that fell off the walkable cells is replaced by a scatter
+            .fold(self.volley_size, usize::max)
+    }
+
+    /// Most projectiles a pool must hold at once: the volleys still in the air when the next fires
+    pub fn max_projectiles_in_flight(&self) -> usize {
+        let overlapping = (self.telegraph / self.interval).ceil() as usize + 1;
+        self.max_volley_targets() * overlapping
+    }
+
+    /// Advance the timeline by `dt`; volleys due in that time, in order
+    pub fn tick(&mut self, dt: f32) -> Vec<Volley> {
+        let mut due = Vec::new();
+        self.until_next -= dt;
+        while self.until_next <= 0.0 {
+            due.push(self.next_volley());
+            self.until_next += self.interval;
+        }
+        due
+    }
+
+    pub fn next_volley(&mut self) -> Volley {
+        let pattern = *VolleyPattern::available(self.difficulty).choose(&mut self.rng).unwrap_or(&VolleyPattern::Scatter);
+        let anchor = self.targets[self.rng.gen_range(0..self.targets.len())];
+        let reach = self.reach();
+
+        let candidates: Vec<(i32, i32)> = match pattern {
+            VolleyPattern::Scatter => Vec::new(),
+            VolleyPattern::Line => {
+                let horizontal = self.rng.gen_bool(0.5);
+                let half = self.volley_size as i32 / 2;
+                (-half..=half)
+                    .map(|i| if horizontal { (anchor.0 + i, anchor.1) } else { (anchor.0, anchor.1 + i) })
+                    .collect()
+            }
+            VolleyPattern::Ring => (-reach..=reach)
+                .flat_map(|dx| (-reach..=reach).map(move |dy| (dx, dy)))
+                .filter(|&(dx, dy)| dx.abs().max(dy.abs()) == reach)
+                .map(|(dx, dy)| (anchor.0 + dx, anchor.1 + dy))
+                .collect(),
+            VolleyPattern::Cross => std::iter::once(anchor)
+                .chain((1..=reach).flat_map(|i| [(i, 0), (-i, 0), (0, i), (0, -i)].map(|(dx, dy)| (anchor.0 + dx, anchor.1 + dy))))
+                .collect(),
+        };
+
+        let mut targets: Vec<(i32, i32)> = candidates.into_iter()
+            .filter(|cell| self.targets.binary_search(cell).is_ok())
+            .collect();
+        // Scatter, or a shape that fell off the walkable cells
+        if targets.is_empty() {
+            targets = self.targets.choose_multiple(&mut self.rng, self.volley_size).copied().collect();
+        }
+
+        Volley { pattern, targets, telegraph: self.telegraph, damage: self.damage }
+    }
+}
@@ -0,0 +1,180 @@
+//core/shape.rs
+use serde::{Deserialize, Serialize};
+use crate::app::core::Arena;
+
+/// Footprint of an arena inside its width x height bounding box
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum ArenaShape {
+    #[default]
+    Rectangle,
+    /// Ellipse inscribed in the bounding box
+    Circle,
+    /// Plus sign whose arms are a third of the box wide
+    Cross,
+    /// Elliptical band `thickness` cells wide
+    Ring { thickness: u32 },
+    /// Bounding box minus its top-right quadrant
+    LShape,
+    /// Explicit footprint; the arena takes the mask's dimensions
+    Mask(ArenaMask),
+}
+
+impl ArenaShape {
+    /// Mask for a `width` x `height` arena, or None when every cell is playable
+    pub fn build_mask(&self, width: u32, height: u32) -> Option<ArenaMask> {
+        let (w, h) = (width as f32, height as f32);
+
+        let inside: Box<dyn Fn(u32, u32) -> bool> = match self {
+            ArenaShape::Rectangle => return None,
+            ArenaShape::Mask(mask) => return Some(mask.clone()),
+            ArenaShape::Circle => Box::new(move |x, y| ellipse_distance(x, y, w / 2.0, h / 2.0, w, h) <= 1.0),
+            ArenaShape::Ring { thickness } => {
+                let t = *thickness as f32;
+                Box::new(move |x, y| {
+                    let outer = ellipse_distance(x, y, w / 2.0, h / 2.0, w, h) <= 1.0;
+                    let inner_rx = w / 2.0 - t;
+                    let inner_ry = h / 2.0 - t;
+                    let inner = inner_rx > 0.0 && inner_ry > 0.0
+                        && ellipse_distance(x, y, inner_rx, inner_ry, w, h) < 1.0;
+                    outer && !inner
+                })
+            }
+            ArenaShape::Cross => {
+                let arm_w = (width / 3).max(1);
+                let arm_h = (height / 3).max(1);
+                let x0 = (width - arm_w) / 2;
+                let y0 = (height - arm_h) / 2;
+                Box::new(move |x, y| (x >= x0 && x < x0 + arm_w) || (y >= y0 && y < y0 + arm_h))
+            }
+            ArenaShape::LShape => {
+                let (half_w, half_h) = (width.div_ceil(2), height.div_ceil(2));
+                Box::new(move |x, y| x < half_w || y < half_h)
+            }
+        };
+
+        let mut mask = ArenaMask::empty(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                mask.set(x as i32, y as i32, inside(x, y));
+            }
+        }
+        Some(mask)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ArenaShape::Rectangle => "rectangle",
+            ArenaShape::Circle => "circle",
+            ArenaShape::Cross => "cross",
+            ArenaShape::Ring { .. } => "ring",
+            ArenaShape::LShape => "l_shape",
+            ArenaShape::Mask(_) => "mask",
+        }
+    }
+
+    /// Bounding box the arena is generated in; a custom mask overrides the requested size
+    pub fn dimensions(&self, width: u32, height: u32) -> (u32, u32) {
+        match self {
+            ArenaShape::Mask(mask) => (mask.width, mask.height),
+            _ => (width, height),
+        }
+    }
+}
+
+/// Normalized distance of a cell center from the box center, 1.0 on the ellipse
+fn ellipse_distance(x: u32, y: u32, rx: f32, ry: f32, w: f32, h: f32) -> f32 {
+    let dx = (x as f32 + 0.5 - w / 2.0) / rx;
+    let dy = (y as f32 + 0.5 - h / 2.0) / ry;
+    dx * dx + dy * dy
+}
+
+/// Row-major bitmap of playable cells
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArenaMask {
+    pub width: u32,
+    pub height: u32,
+    pub cells: Vec<bool>,
+}
+
+impl ArenaMask {
+    pub fn empty(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![false; (width * height) as usize],
+        }
+    }
+
+    /// Build a mask from text rows, `#` (or `1`) marking a playable cell.
+    /// Row 0 is y = 0; short rows are padded with blocked cells.
+    pub fn from_bitmap(rows: &[&str]) -> Self {
+        let width = rows.iter().map(|row| row.chars().count()).max().unwrap_or(0) as u32;
+        let mut mask = Self::empty(width, rows.len() as u32);
+
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                mask.set(x as i32, y as i32, matches!(c, '#' | '1'));
+            }
+        }
+
+        mask
+    }
+
+    /// Whether `cells` holds exactly one entry per cell; a hand-written mask may not
+    pub fn is_well_formed(&self) -> bool {
+        self.cells.len() == self.width as usize * self.height as usize
+    }
+
+    /// Cells missing from a malformed mask count as blocked
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        self.index(x, y).and_then(|i| self.cells.get(i)).copied().unwrap_or(false)
+    }
+
+    pub fn set(&mut self, x: i32, y: i32, playable: bool) {
+        if let Some(cell) = self.index(x, y).and_then(|i| self.cells.get_mut(i)) {
+            *cell = playable;
+        }
+    }
+
+    pub fn playable_count(&self) -> usize {
+        self.cells.iter().filter(|&&c| c).count()
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return None;
+        }
+        Some((y as u32 * self.width + x as u32) as usize)
+    }
+}
+
+impl Arena {
+    /// Arena whose playable cells follow `shape`
+    pub fn with_shape(width: u32, height: u32, seed: u64, shape: &ArenaShape) -> Self {
+        let (width, height) = shape.dimensions(width, height);
+        let mut arena = Arena::new(width, height, seed);
+        arena.mask = shape.build_mask(width, height);
+        arena
+    }
+
+    /// Number of cells inside the footprint; densities and quotas are relative to this
+    pub fn playable_cell_count(&self) -> usize {
+        match &self.mask {
+            Some(mask) => mask.playable_count(),
+            None => (self.width * self.height) as usize,
+        }
+    }
+
+    /// Every cell inside the footprint, row by row
+    pub fn playable_positions(&self) -> Vec<(i32, i32)> {
+        let mut positions = Vec::with_capacity(self.playable_cell_count());
+        for y in 0..self.height as i32 {
+            for x in 0..self.width as i32 {
+                if self.is_valid_position(x, y) {
+                    positions.push((x, y));
+                }
+            }
+        }
+        positions
+    }
+}
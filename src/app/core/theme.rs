@@ -0,0 +1,276 @@
+//core/theme.rs
+use crate::app::core::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Visual theme of an arena, picked from its rules and environment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ThemeId {
+    #[default]
+    Pastel,
+    Industrial,
+    Neon,
+    Volcanic,
+    Moon,
+}
+
+impl ThemeId {
+    pub const ALL: [ThemeId; 5] = [ThemeId::Pastel, ThemeId::Industrial, ThemeId::Neon, ThemeId::Volcanic, ThemeId::Moon];
+
+    /// File stem of the theme in `assets/themes`
+    pub fn name(&self) -> &'static str {
+        match self {
+            ThemeId::Pastel => "pastel",
+            ThemeId::Industrial => "industrial",
+            ThemeId::Neon => "neon",
+            ThemeId::Volcanic => "volcanic",
+            ThemeId::Moon => "moon",
+        }
+    }
+
+    /// Theme file, compiled in: no platform needs file access to get its palettes
+    fn builtin_json(&self) -> &'static str {
+        match self {
+            ThemeId::Pastel => include_str!("../../../assets/themes/pastel.json"),
+            ThemeId::Industrial => include_str!("../../../assets/themes/industrial.json"),
+            ThemeId::Neon => include_str!("../../../assets/themes/neon.json"),
+            ThemeId::Volcanic => include_str!("../../../assets/themes/volcanic.json"),
+            ThemeId::Moon => include_str!("../../../assets/themes/moon.json"),
+        }
+    }
+
+    /// Theme matching the arena's active rules and env variables, strongest signal first
+    pub fn for_arena(arena: &Arena) -> Self {
+        let has_rule = |id: RuleId| arena.active_rules.iter().any(|rule| rule.id == id);
+        let env = |id: EnvVarId| arena.env_variables.get(&id).copied();
+
+        if has_rule(RuleId::MoonGravity) || env(EnvVarId::Gravity).is_some_and(|g| g < 0.5) {
+            ThemeId::Moon
+        } else if has_rule(RuleId::LavaFloor) {
+            ThemeId::Volcanic
+        } else if has_rule(RuleId::SpeedUp) || env(EnvVarId::GameSpeed).is_some_and(|s| s >= 1.3) {
+            ThemeId::Neon
+        } else if has_rule(RuleId::ProjectileRain) || has_rule(RuleId::CrumblingFloor) || has_rule(RuleId::NoAttack) {
+            ThemeId::Industrial
+        } else {
+            ThemeId::Pastel
+        }
+    }
+}
+
+impl fmt::Display for ThemeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// One shared material of the arena; every module kind draws from these
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum MaterialSlot {
+    FloorStd,
+    FloorLarge,
+    FloorFragile,
+    FloorFragileCracked,
+    WallLow,
+    WallHigh,
+    PanelGlass,
+    Ramp,
+    OrbEnergy,
+    HazardLava,
+    HazardLaser,
+    /// The beam itself, drawn from the emitter to the first obstacle
+    LaserBeam,
+    /// Dark bars laid over hazards in colour-blind mode
+    HazardStripes,
+    /// Burn marks around laser hazards; the texture supplies the shape, the alpha caps its opacity
+    ScorchDecal,
+    /// Falling projectile of the `ProjectileRain` rule
+    Projectile,
+    /// Ground marker announcing where a projectile will land
+    ImpactIndicator,
+    DecorMetallic,
+    DecorSteel,
+    DecorBrass,
+    DecorCobalt,
+    DecorCopper,
+    PlayerSpawn,
+    TeleporterIn,
+    TeleporterOut,
+    ClimbSurface,
+    Button,
+    ButtonPressed,
+    Lever,
+    LeverHandle,
+    EnemySpawner,
+    BarrierEnergy,
+}
+
+impl MaterialSlot {
+    pub const ALL: [MaterialSlot; 31] = [
+        MaterialSlot::FloorStd, MaterialSlot::FloorLarge, MaterialSlot::FloorFragile, MaterialSlot::FloorFragileCracked,
+        MaterialSlot::WallLow, MaterialSlot::WallHigh, MaterialSlot::PanelGlass, MaterialSlot::Ramp,
+        MaterialSlot::OrbEnergy, MaterialSlot::HazardLava, MaterialSlot::HazardLaser, MaterialSlot::LaserBeam,
+        MaterialSlot::HazardStripes, MaterialSlot::ScorchDecal, MaterialSlot::Projectile, MaterialSlot::ImpactIndicator,
+        MaterialSlot::DecorMetallic, MaterialSlot::DecorSteel,
+        MaterialSlot::DecorBrass, MaterialSlot::DecorCobalt, MaterialSlot::DecorCopper, MaterialSlot::PlayerSpawn,
+        MaterialSlot::TeleporterIn, MaterialSlot::TeleporterOut, MaterialSlot::ClimbSurface, MaterialSlot::Button,
+        MaterialSlot::ButtonPressed, MaterialSlot::Lever, MaterialSlot::LeverHandle, MaterialSlot::EnemySpawner,
+        MaterialSlot::BarrierEnergy,
+    ];
+
+    /// Arch tints, indexed by the arch's `colorVariant` parameter
+    pub const DECOR_VARIANTS: [MaterialSlot; 4] =
+        [MaterialSlot::DecorSteel, MaterialSlot::DecorBrass, MaterialSlot::DecorCobalt, MaterialSlot::DecorCopper];
+
+    pub fn is_hazard(&self) -> bool {
+        matches!(self, MaterialSlot::HazardLava | MaterialSlot::HazardLaser | MaterialSlot::LaserBeam
+            | MaterialSlot::Projectile | MaterialSlot::ImpactIndicator)
+    }
+
+    pub fn is_collectible(&self) -> bool {
+        matches!(self, MaterialSlot::OrbEnergy)
+    }
+}
+
+/// Surface parameters of a material; colours are sRGB
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MaterialSpec {
+    /// An alpha below 1.0 makes the material blended
+    pub base_color: [f32; 4],
+    #[serde(default)]
+    pub emissive: [f32; 3],
+    pub roughness: f32,
+    pub metallic: f32,
+}
+
+impl MaterialSpec {
+    pub const fn solid(r: f32, g: f32, b: f32, roughness: f32, metallic: f32) -> Self {
+        Self { base_color: [r, g, b, 1.0], emissive: [0.0; 3], roughness, metallic }
+    }
+
+    pub const fn glowing(mut self, r: f32, g: f32, b: f32) -> Self {
+        self.emissive = [r, g, b];
+        self
+    }
+
+    pub const fn with_alpha(mut self, alpha: f32) -> Self {
+        self.base_color[3] = alpha;
+        self
+    }
+
+    pub fn is_transparent(&self) -> bool {
+        self.base_color[3] < 1.0
+    }
+
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        // Weighted sum rather than a + (b - a) * t: exact at both ends of a fade
+        let mix = |a: f32, b: f32| a * (1.0 - t) + b * t;
+        Self {
+            base_color: std::array::from_fn(|i| mix(self.base_color[i], other.base_color[i])),
+            emissive: std::array::from_fn(|i| mix(self.emissive[i], other.emissive[i])),
+            roughness: mix(self.roughness, other.roughness),
+            metallic: mix(self.metallic, other.metallic),
+        }
+    }
+
+    /// WCAG relative luminance of the base colour
+    pub fn luminance(&self) -> f32 {
+        let linear = |c: f32| if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) };
+        let [r, g, b, _] = self.base_color;
+        0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+    }
+}
+
+/// Every material of a theme. Built-in files list them all; a palette read with
+/// `from_json` may list only the slots it changes, the others keep the built-in values.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThemePalette {
+    pub theme: ThemeId,
+    /// Clear colour behind the arena (sRGB)
+    pub background: [f32; 3],
+    pub materials: BTreeMap<MaterialSlot, MaterialSpec>,
+}
+
+#[derive(Deserialize)]
+struct ThemeFile {
+    theme: ThemeId,
+    background: Option<[f32; 3]>,
+    #[serde(default)]
+    materials: BTreeMap<MaterialSlot, MaterialSpec>,
+}
+
+impl ThemePalette {
+    /// Built-in palette of `theme`, complete. The files of `assets/themes` are the only
+    /// source; they are embedded at compile time and parsed once.
+    pub fn builtin(theme: ThemeId) -> Self {
+        static BUILTIN: OnceLock<Vec<ThemePalette>> = OnceLock::new();
+        let palettes = BUILTIN.get_or_init(|| ThemeId::ALL.iter()
+            .map(|theme| serde_json::from_str(theme.builtin_json())
+                .unwrap_or_else(|e| panic!("assets/themes/{}.json is malformed: {}", theme, e)))
+            .collect());
+        palettes.iter()
+            .find(|palette| palette.theme == theme)
+            .cloned()
+            .unwrap_or_else(|| panic!("assets/themes/{}.json declares another theme", theme))
+    }
+
+    /// Material of `slot`; slots missing from a hand-written palette fall back to the pastel one
+    pub fn spec(&self, slot: MaterialSlot) -> MaterialSpec {
+        self.materials.get(&slot).copied()
+            .unwrap_or_else(|| Self::builtin(ThemeId::Pastel).materials[&slot])
+    }
+
+    /// Colour-blind-safe variant (Okabe-Ito hues): collectibles light sky blue, hazards dark
+    /// vermillion, far apart in luminance so they never rely on hue alone
+    pub fn colour_blind(&self) -> Self {
+        let mut palette = self.clone();
+        palette.materials.extend([
+            (MaterialSlot::OrbEnergy, MaterialSpec::solid(0.6, 0.85, 1.0, 0.2, 0.0).glowing(0.2, 0.35, 0.45)),
+            (MaterialSlot::HazardLava, MaterialSpec::solid(0.8, 0.3, 0.0, 0.8, 0.0).glowing(0.25, 0.08, 0.0)),
+            (MaterialSlot::HazardLaser, MaterialSpec::solid(0.75, 0.25, 0.0, 0.3, 0.6).glowing(0.25, 0.06, 0.0)),
+            (MaterialSlot::LaserBeam, MaterialSpec::solid(0.85, 0.3, 0.0, 1.0, 0.0).glowing(0.6, 0.15, 0.0).with_alpha(0.85)),
+            (MaterialSlot::Projectile, MaterialSpec::solid(0.8, 0.3, 0.0, 0.4, 0.2).glowing(0.3, 0.08, 0.0)),
+            (MaterialSlot::ImpactIndicator, MaterialSpec::solid(0.85, 0.3, 0.0, 1.0, 0.0).glowing(0.3, 0.08, 0.0).with_alpha(0.6)),
+            (MaterialSlot::HazardStripes, MaterialSpec::solid(0.02, 0.02, 0.02, 0.9, 0.0)),
+            (MaterialSlot::FloorFragileCracked, MaterialSpec::solid(0.9, 0.6, 0.0, 1.0, 0.0).glowing(0.3, 0.2, 0.0)),
+            (MaterialSlot::Button, MaterialSpec::solid(0.94, 0.89, 0.26, 0.4, 0.3).glowing(0.2, 0.18, 0.0)),
+            (MaterialSlot::ButtonPressed, MaterialSpec::solid(0.0, 0.45, 0.7, 0.4, 0.3).glowing(0.0, 0.15, 0.3)),
+            (MaterialSlot::TeleporterIn, MaterialSpec::solid(0.8, 0.47, 0.65, 0.3, 0.2).glowing(0.3, 0.15, 0.25)),
+            (MaterialSlot::TeleporterOut, MaterialSpec::solid(0.0, 0.62, 0.45, 0.3, 0.2).glowing(0.0, 0.25, 0.18)),
+        ]);
+        palette
+    }
+
+    /// Palette `t` of the way from `self` to `other` (0.0 = self, 1.0 = other)
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let materials = MaterialSlot::ALL.iter()
+            .map(|&slot| (slot, self.spec(slot).lerp(&other.spec(slot), t)))
+            .collect();
+
+        Self {
+            theme: if t < 0.5 { self.theme } else { other.theme },
+            background: std::array::from_fn(|i| self.background[i] * (1.0 - t) + other.background[i] * t),
+            materials,
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ArenaGenerationError> {
+        let file: ThemeFile = serde_json::from_str(json).map_err(|e| ArenaGenerationError::InvalidConfiguration {
+            details: format!("malformed theme: {}", e),
+        })?;
+
+        let mut palette = Self::builtin(file.theme);
+        if let Some(background) = file.background {
+            palette.background = background;
+        }
+        palette.materials.extend(file.materials);
+        Ok(palette)
+    }
+
+    pub fn to_json_string(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
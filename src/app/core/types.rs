@@ -4,6 +4,7 @@ use std::fmt;
 use crate::app::core::*;
 use crate::app::core::Arena;
 use crate::app::core::ArenaCell;
+use crate::app::monitoring::Anomaly;
 
 impl fmt::Display for RuleId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -17,6 +18,7 @@ impl fmt::Display for RuleId {
             RuleId::ProjectileRain => "PROJECTILE_RAIN",
             RuleId::OrbCollection => "ORB_COLLECTION",
             RuleId::MoonGravity => "MOON_GRAVITY",
+            RuleId::CrumblingFloor => "CRUMBLING_FLOOR",
         };
         write!(f, "{}", name)
     }
@@ -29,6 +31,7 @@ impl fmt::Display for ModuleId {
             ModuleId::OrbEnergy => "ORB_ENERGY",
             ModuleId::FloorStd => "FLOOR_STD",
             ModuleId::FloorLarge => "FLOOR_LARGE",
+            ModuleId::FloorFragile => "FLOOR_FRAGILE",
             ModuleId::WallLow => "WALL_LOW",
             ModuleId::WallHigh => "WALL_HIGH",
             ModuleId::PanelGlass => "PANEL_GLASS",
@@ -70,9 +73,10 @@ impl Arena {
             .collect()
     }
 
-    /// Check if a position is within arena bounds
+    /// Check if a position is within arena bounds and inside the footprint
     pub fn is_valid_position(&self, x: i32, y: i32) -> bool {
-        x >= 0 && y >= 0 && x < self.width as i32 && y < self.height as i32
+        let in_bounds = x >= 0 && y >= 0 && x < self.width as i32 && y < self.height as i32;
+        in_bounds && self.mask.as_ref().is_none_or(|mask| mask.contains(x, y))
     }
 
     /// Get all adjacent positions (4-directional)
@@ -83,16 +87,30 @@ impl Arena {
             .collect()
     }
 
+    /// Remove every module stored at a position, returning the removed cells
+    pub fn remove_modules_at(&mut self, x: i32, y: i32) -> Vec<ArenaCell> {
+        let (removed, kept): (Vec<_>, Vec<_>) = self.modules.drain(..)
+            .partition(|cell| cell.x == x && cell.y == y);
+        self.modules = kept;
+        removed
+    }
+
+    /// Replace whatever occupies a position with a single module
+    pub fn set_module(&mut self, x: i32, y: i32, module_id: ModuleId, params: Option<serde_json::Value>) {
+        self.remove_modules_at(x, y);
+        self.add_module(x, y, module_id, params);
+    }
+
     /// Calculate density of a specific module type
     pub fn calculate_module_density(&self, module_type: &ModuleId) -> f64 {
         let count = self.count_modules_by_type(module_type) as f64;
-        let total_area = (self.width * self.height) as f64;
+        let total_area = self.playable_cell_count() as f64;
         count / total_area
     }
 
     /// Get arena statistics
     pub fn get_statistics(&self) -> ArenaStatistics {
-        let total_cells = (self.width * self.height) as usize;
+        let total_cells = self.playable_cell_count();
         let filled_cells = self.modules.len();
         let empty_cells = total_cells - filled_cells;
 
@@ -166,7 +184,7 @@ impl fmt::Display for ArenaStatistics {
 }
 
 // Error types for better error handling
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum ArenaGenerationError {
     #[error("Arena generation failed: {message}")]
     GenerationFailed { message: String },
@@ -174,14 +192,32 @@ pub enum ArenaGenerationError {
     #[error("Invalid configuration: {details}")]
     InvalidConfiguration { details: String },
 
-    #[error("Constraint violation: {constraint}")]
-    ConstraintViolation { constraint: String },
+    #[error("Constraint violation: {constraint} ({details})")]
+    ConstraintViolation { constraint: String, details: String },
+
+    #[error("Critical anomaly detected: {}", describe_anomalies(.anomalies))]
+    CriticalAnomaly { anomalies: Vec<Anomaly> },
 
-    #[error("Critical anomaly detected: {anomaly}")]
-    CriticalAnomaly { anomaly: String },
+    #[error("Timeout during generation (exceeded {max_time_ms}ms in stage '{stage}')")]
+    Timeout { max_time_ms: u64, stage: String },
+
+    #[error("Generation cancelled before stage '{stage}'")]
+    Cancelled { stage: String },
+}
+
+impl ArenaGenerationError {
+    /// Whether another seed may succeed; a bad configuration fails the same way every time,
+    /// and a cancelled generation must not be restarted
+    pub fn is_retryable(&self) -> bool {
+        !matches!(self, ArenaGenerationError::InvalidConfiguration { .. } | ArenaGenerationError::Cancelled { .. })
+    }
+}
 
-    #[error("Timeout during generation (exceeded {max_time_ms}ms)")]
-    Timeout { max_time_ms: u64 },
+fn describe_anomalies(anomalies: &[Anomaly]) -> String {
+    anomalies.iter()
+        .map(|a| format!("[{}] {}", a.code, a.message))
+        .collect::<Vec<_>>()
+        .join("; ")
 }
 
 // Performance monitoring structures
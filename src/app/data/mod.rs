@@ -1,20 +1,41 @@
 //data/mod.rs..
 use crate::app::core::*;
-use std::collections::HashMap;
+use indexmap::IndexMap;
+use std::sync::OnceLock;
+
+mod relations;
+
+pub use relations::*;
 
 pub struct RulesDatabase {
-    rules: HashMap<RuleId, Rule>,
+    rules: IndexMap<RuleId, Rule>,
+    relations: RuleGraph,
 }
 
 impl RulesDatabase {
     pub fn new() -> Self {
         let mut db = Self {
-            rules: HashMap::new(),
+            rules: IndexMap::new(),
+            relations: RuleGraph::default(),
         };
         db.initialize();
+        db.initialize_relations();
         db
     }
 
+    /// The built-in rules, shared by everything that doesn't bring its own database.
+    /// Inconsistencies in their relations are logged once, on first use.
+    pub fn builtin() -> &'static RulesDatabase {
+        static BUILTIN: OnceLock<RulesDatabase> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            let db = RulesDatabase::new();
+            for issue in db.relations.issues() {
+                log::warn!("Rule relations: {}", issue);
+            }
+            db
+        })
+    }
+
     fn initialize(&mut self) {
         let rules = vec![
             Rule {
@@ -89,6 +110,14 @@ impl RulesDatabase {
                 parameters: Some(serde_json::json!({"gravityMultiplier": 0.3})),
                 incompatible_with: vec![RuleId::NoJump],
             },
+            Rule {
+                id: RuleId::CrumblingFloor,
+                name: "Crumbling Floor".to_string(),
+                description: "Fragile floors are everywhere and give way faster.".to_string(),
+                tags: vec!["hazard".to_string(), "environment".to_string(), "difficulty_medium".to_string()],
+                parameters: Some(serde_json::json!({"breakDelayMultiplier": 0.6})),
+                incompatible_with: vec![],
+            },
         ];
 
         for rule in rules {
@@ -96,6 +125,38 @@ impl RulesDatabase {
         }
     }
 
+    /// Conflicts come from `incompatible_with`; synergies and implications are declared here
+    fn initialize_relations(&mut self) {
+        let synergies = [
+            // Sauter par-dessus la lave
+            (RuleId::LavaFloor, RuleId::HighJump, 1.5),
+            // Course aux orbes
+            (RuleId::OrbCollection, RuleId::SpeedUp, 1.4),
+            // Esquive en apesanteur
+            (RuleId::MoonGravity, RuleId::ProjectileRain, 1.3),
+            // Traverser avant que le sol cède
+            (RuleId::CrumblingFloor, RuleId::SpeedUp, 1.3),
+        ];
+        // Aucune règle intégrée n'en exige une autre pour l'instant ; `with_relations` permet d'en déclarer
+        let implications = [];
+
+        self.set_relations(&synergies, &implications);
+    }
+
+    /// Replace the declared synergies and implications (conflicts still come from the rules)
+    pub fn with_relations(mut self, synergies: &[(RuleId, RuleId, f32)], implications: &[(RuleId, RuleId)]) -> Self {
+        self.set_relations(synergies, implications);
+        self
+    }
+
+    fn set_relations(&mut self, synergies: &[(RuleId, RuleId, f32)], implications: &[(RuleId, RuleId)]) {
+        self.relations = RuleGraph::build(&self.get_all_rules(), synergies, implications);
+    }
+
+    pub fn relations(&self) -> &RuleGraph {
+        &self.relations
+    }
+
     pub fn get_rule(&self, id: &RuleId) -> Option<&Rule> {
         self.rules.get(id)
     }
@@ -106,13 +167,13 @@ impl RulesDatabase {
 }
 
 pub struct EnvVarsDatabase {
-    variables: HashMap<EnvVarId, EnvVariable>,
+    variables: IndexMap<EnvVarId, EnvVariable>,
 }
 
 impl EnvVarsDatabase {
     pub fn new() -> Self {
         let mut db = Self {
-            variables: HashMap::new(),
+            variables: IndexMap::new(),
         };
         db.initialize();
         db
@@ -151,13 +212,13 @@ impl EnvVarsDatabase {
 }
 
 pub struct ModulesDatabase {
-    modules: HashMap<ModuleId, ModuleDefinition>,
+    modules: IndexMap<ModuleId, ModuleDefinition>,
 }
 
 impl ModulesDatabase {
     pub fn new() -> Self {
         let mut db = Self {
-            modules: HashMap::new(),
+            modules: IndexMap::new(),
         };
         db.initialize();
         db
@@ -201,6 +262,17 @@ impl ModulesDatabase {
                 parameters: Some(serde_json::json!({"sizeX": 2, "sizeZ": 2})),
                 wfc_weight: Some(20)
             },
+            ModuleDefinition {
+                id: ModuleId::FloorFragile,
+                name: "Fragile Floor".to_string(),
+                description: "Breaks shortly after being stepped on, then reforms.".to_string(),
+                tags: vec!["structure".to_string(), "walkable".to_string(), "fragile".to_string(), "temporary".to_string()],
+                parameters: Some(serde_json::json!({
+                    "breakDelay": 0.5,
+                    "respawnDelay": 5.0
+                })),
+                wfc_weight: Some(4),
+            },
             ModuleDefinition {
                 id: ModuleId::WallLow,
                 name: "Wall low".to_string(),
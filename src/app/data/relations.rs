@@ -0,0 +1,190 @@
+//data/relations.rs
+use crate::app::core::*;
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::EdgeRef;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+/// Edge of the rule graph. Conflicts and synergies are stored in both directions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RuleRelation {
+    /// The two rules are never active together
+    Conflict,
+    /// Multiplies the weight of one rule once the other is selected
+    Synergy(f32),
+    /// The source rule is only selected together with the target
+    Requires,
+}
+
+/// Inconsistency found while building the graph from rule declarations
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleGraphIssue {
+    /// `declared_by` lists `other` as incompatible but not the other way round
+    AsymmetricConflict { declared_by: RuleId, other: RuleId },
+    /// A relation points at a rule that is not in the database
+    UnknownRule { declared_by: RuleId, target: RuleId },
+    /// A rule declared in relation with itself
+    SelfRelation { rule: RuleId },
+    /// `rule` needs `required` (possibly through other rules) but conflicts with it, so it can never be active
+    RequiresConflicting { rule: RuleId, required: RuleId },
+}
+
+impl fmt::Display for RuleGraphIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleGraphIssue::AsymmetricConflict { declared_by, other } =>
+                write!(f, "{} is incompatible with {} but {} does not declare it back", declared_by, other, other),
+            RuleGraphIssue::UnknownRule { declared_by, target } =>
+                write!(f, "{} refers to unknown rule {}", declared_by, target),
+            RuleGraphIssue::SelfRelation { rule } =>
+                write!(f, "{} is declared in relation with itself", rule),
+            RuleGraphIssue::RequiresConflicting { rule, required } =>
+                write!(f, "{} requires {} but conflicts with it", rule, required),
+        }
+    }
+}
+
+/// Conflicts, synergies and implications between rules
+#[derive(Debug, Clone, Default)]
+pub struct RuleGraph {
+    graph: DiGraph<RuleId, RuleRelation>,
+    nodes: HashMap<RuleId, NodeIndex>,
+    issues: Vec<RuleGraphIssue>,
+}
+
+impl RuleGraph {
+    /// Build from each rule's `incompatible_with` plus the declared synergies `(a, b, factor)`
+    /// and implications `(rule, required)`. Asymmetric conflicts are made symmetric and reported.
+    pub fn build(rules: &[&Rule], synergies: &[(RuleId, RuleId, f32)], implications: &[(RuleId, RuleId)]) -> Self {
+        let mut graph = Self::default();
+        for rule in rules {
+            let node = graph.graph.add_node(rule.id.clone());
+            graph.nodes.insert(rule.id.clone(), node);
+        }
+
+        for rule in rules {
+            for other in &rule.incompatible_with {
+                if !graph.check_pair(&rule.id, other) {
+                    continue;
+                }
+                let declared_back = rules.iter()
+                    .find(|r| &r.id == other)
+                    .is_some_and(|r| r.incompatible_with.contains(&rule.id));
+                if !declared_back {
+                    graph.issues.push(RuleGraphIssue::AsymmetricConflict { declared_by: rule.id.clone(), other: other.clone() });
+                }
+                graph.link_both(&rule.id, other, RuleRelation::Conflict);
+            }
+        }
+
+        for (a, b, factor) in synergies {
+            if graph.check_pair(a, b) {
+                graph.link_both(a, b, RuleRelation::Synergy(*factor));
+            }
+        }
+
+        for (rule, required) in implications {
+            if graph.check_pair(rule, required) {
+                graph.link(rule, required, RuleRelation::Requires);
+            }
+        }
+
+        for rule in rules {
+            for required in graph.requirements(&rule.id) {
+                if graph.conflicts(&rule.id, &required) {
+                    graph.issues.push(RuleGraphIssue::RequiresConflicting { rule: rule.id.clone(), required });
+                }
+            }
+        }
+
+        graph
+    }
+
+    /// Problems found while building, in declaration order
+    pub fn issues(&self) -> &[RuleGraphIssue] {
+        &self.issues
+    }
+
+    pub fn conflicts(&self, a: &RuleId, b: &RuleId) -> bool {
+        self.relations(a, b).any(|r| r == RuleRelation::Conflict)
+    }
+
+    /// Every rule that can't be active alongside `rule`
+    pub fn conflicts_of(&self, rule: &RuleId) -> Vec<RuleId> {
+        self.neighbours(rule, |r| r == RuleRelation::Conflict)
+    }
+
+    /// Weight multiplier for `candidate` once `selected` is active (1.0 without synergy)
+    pub fn synergy(&self, selected: &RuleId, candidate: &RuleId) -> f32 {
+        self.relations(selected, candidate)
+            .filter_map(|r| match r {
+                RuleRelation::Synergy(factor) => Some(factor),
+                _ => None,
+            })
+            .product()
+    }
+
+    /// Everything `rule` needs, directly or through other requirements, nearest first
+    pub fn requirements(&self, rule: &RuleId) -> Vec<RuleId> {
+        let mut found = Vec::new();
+        let mut seen = HashSet::from([rule.clone()]);
+        let mut queue = VecDeque::from([rule.clone()]);
+
+        while let Some(current) = queue.pop_front() {
+            for required in self.neighbours(&current, |r| r == RuleRelation::Requires) {
+                if seen.insert(required.clone()) {
+                    found.push(required.clone());
+                    queue.push_back(required);
+                }
+            }
+        }
+
+        found
+    }
+
+    fn relations<'a>(&'a self, a: &RuleId, b: &RuleId) -> impl Iterator<Item = RuleRelation> + 'a {
+        let pair = self.nodes.get(a).zip(self.nodes.get(b));
+        pair.into_iter()
+            .flat_map(move |(&a, &b)| self.graph.edges_connecting(a, b))
+            .map(|edge| *edge.weight())
+    }
+
+    fn neighbours(&self, rule: &RuleId, keep: impl Fn(RuleRelation) -> bool) -> Vec<RuleId> {
+        let Some(&node) = self.nodes.get(rule) else {
+            return Vec::new();
+        };
+        // petgraph walks the newest edge first; reverse to keep declaration order
+        let mut found: Vec<RuleId> = self.graph.edges(node)
+            .filter(|edge| keep(*edge.weight()))
+            .map(|edge| self.graph[edge.target()].clone())
+            .collect();
+        found.reverse();
+        found
+    }
+
+    /// Record an issue and return false when the pair can't be linked
+    fn check_pair(&mut self, a: &RuleId, b: &RuleId) -> bool {
+        if a == b {
+            self.issues.push(RuleGraphIssue::SelfRelation { rule: a.clone() });
+            return false;
+        }
+        if let Some(missing) = [a, b].into_iter().find(|id| !self.nodes.contains_key(id)) {
+            self.issues.push(RuleGraphIssue::UnknownRule { declared_by: a.clone(), target: missing.clone() });
+            return false;
+        }
+        true
+    }
+
+    fn link(&mut self, from: &RuleId, to: &RuleId, relation: RuleRelation) {
+        if self.relations(from, to).any(|r| r == relation) {
+            return;
+        }
+        let (from, to) = (self.nodes[from], self.nodes[to]);
+        self.graph.add_edge(from, to, relation);
+    }
+
+    fn link_both(&mut self, a: &RuleId, b: &RuleId, relation: RuleRelation) {
+        self.link(a, b, relation);
+        self.link(b, a, relation);
+    }
+}
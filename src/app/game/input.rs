@@ -71,6 +71,13 @@ pub fn handle_keyboard_input(
             }
         },
 
+        GameState::Loading => {
+            if keyboard_input.just_pressed(KeyCode::Escape) {
+                // Annuler la génération (la tâche est abandonnée en quittant l'écran)
+                next_state.set(GameState::MainMenu);
+            }
+        },
+
         GameState::Playing => {
             if keyboard_input.just_pressed(KeyCode::Escape) {
                 next_state.set(GameState::Paused);
@@ -0,0 +1,361 @@
+use bevy::prelude::*;
+use bevy::tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task};
+use crate::app::game::*;
+use crate::app::generation::*;
+use crate::app::monitoring::*;
+use crate::app::mesh_generation::{spawn_arena_visuals, prepare_arena_textures, apply_arena_theme};
+use crate::app::core::gameplay::ShiftManager;
+use crate::app::core::gameplay::DifficultySystem;
+use std::sync::Arc;
+use log::{info, warn, error};
+
+// ============================================================================
+// TÂCHES DE GÉNÉRATION EN ARRIÈRE-PLAN
+// ============================================================================
+
+/// Génération d'arène lancée sur l'`AsyncComputeTaskPool`.
+/// Abandonner la tâche (la retirer de `ArenaGenerationTasks`) annule la génération.
+pub struct ArenaGenerationTask {
+    pub master_seed: u64,
+    pub difficulty_target: f32,
+    pub progress: Arc<GenerationProgress>,
+    task: Task<GenerationResult<RetryOutcome>>,
+}
+
+impl ArenaGenerationTask {
+    /// Lance la génération avec le preset "normal" et la difficulté visée
+    pub fn spawn(difficulty_target: f32) -> Self {
+        // Preset "normal" (fichier s'il existe, sinon intégré)
+        let config = GenerationConfig::load_preset(PRESETS_DIR, "normal").unwrap_or_else(|e| {
+            warn!("⚠️ Preset illisible, preset intégré utilisé: {}", e);
+            GenerationConfig::normal()
+        });
+
+        // Graines dérivées d'une graine maîtresse, contraintes relâchées au fil des échecs,
+        // et arène de secours en dernier recours : le joueur ne retourne jamais au menu
+        let master_seed = rand::random::<u64>();
+        let policy = RetryPolicy::default().with_difficulty(difficulty_target, DEFAULT_DIFFICULTY_TOLERANCE);
+        let progress = GenerationProgress::new();
+
+        let task_progress = progress.clone();
+        let task = AsyncComputeTaskPool::get().spawn(async move {
+            let mut generator = ArenaGenerator::new(Some(master_seed)).with_progress(task_progress);
+            let mut monitor = AnomalyMonitor::new();
+            generator.generate_until_valid(master_seed, &config, &policy, &mut monitor)
+        });
+
+        Self { master_seed, difficulty_target, progress, task }
+    }
+
+    /// La difficulté visée est toujours celle attendue, à la tolérance près
+    pub fn fits(&self, difficulty_target: f32) -> bool {
+        difficulty_fits(self.difficulty_target, difficulty_target)
+    }
+
+    /// Résultat de la génération si elle est terminée, sans bloquer la frame
+    pub fn poll(&mut self) -> Option<GenerationResult<RetryOutcome>> {
+        block_on(future::poll_once(&mut self.task))
+    }
+}
+
+impl Drop for ArenaGenerationTask {
+    fn drop(&mut self) {
+        // La tâche tourne sur un autre thread : on lui demande de s'arrêter à la prochaine étape
+        self.progress.cancel();
+    }
+}
+
+/// Arène générée d'avance, avec la difficulté qu'elle visait
+pub struct PregeneratedArena {
+    pub outcome: RetryOutcome,
+    pub difficulty_target: f32,
+}
+
+impl PregeneratedArena {
+    pub fn fits(&self, difficulty_target: f32) -> bool {
+        difficulty_fits(self.difficulty_target, difficulty_target)
+    }
+}
+
+fn difficulty_fits(generated_for: f32, expected: f32) -> bool {
+    (generated_for - expected).abs() <= DEFAULT_DIFFICULTY_TOLERANCE
+}
+
+#[derive(Resource, Default)]
+pub struct ArenaGenerationTasks {
+    /// Arène attendue par l'écran de chargement
+    pub current: Option<ArenaGenerationTask>,
+    /// Arène du shift suivant, générée pendant que le shift courant se joue
+    pub next_shift: Option<ArenaGenerationTask>,
+    /// Arène du shift suivant, prête à être utilisée
+    pub pregenerated: Option<PregeneratedArena>,
+}
+
+impl ArenaGenerationTasks {
+    /// Annule la génération de l'écran de chargement
+    pub fn cancel_current(&mut self) {
+        if self.current.take().is_some() {
+            info!("🛑 Génération de l'arène annulée");
+        }
+    }
+
+    /// Jette ce qui a été (ou est en train d'être) généré pour une autre difficulté
+    pub fn discard_stale(&mut self, difficulty_target: f32) {
+        if self.next_shift.as_ref().is_some_and(|task| !task.fits(difficulty_target)) {
+            info!("🗑️ Pré-génération abandonnée : la difficulté visée a changé");
+            self.next_shift = None;
+        }
+        if self.pregenerated.as_ref().is_some_and(|next| !next.fits(difficulty_target)) {
+            info!("🗑️ Arène pré-générée jetée : la difficulté visée a changé");
+            self.pregenerated = None;
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct LoadingProgressText;
+
+#[derive(Component)]
+pub struct LoadingProgressFill;
+
+// ============================================================================
+// ÉCRAN DE CHARGEMENT
+// ============================================================================
+
+pub fn setup_loading(mut commands: Commands) {
+    info!("Chargement en cours...");
+    commands.spawn((
+        LoadingUI,
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.05, 0.05, 0.1)),
+    )).with_children(|parent| {
+        parent.spawn((
+            Text::new("Génération de l'arène..."),
+            TextFont {
+                font_size: 32.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+        ));
+
+        // Barre de progression, remplie étape par étape
+        parent.spawn((
+            Node {
+                width: Val::Percent(50.0),
+                height: Val::Px(12.0),
+                margin: UiRect::top(Val::Px(24.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.2, 0.2, 0.2, 0.8)),
+        )).with_children(|parent| {
+            parent.spawn((
+                LoadingProgressFill,
+                Node {
+                    width: Val::Percent(0.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.0, 0.9, 1.0)),
+            ));
+        });
+
+        parent.spawn((
+            LoadingProgressText,
+            Text::new(""),
+            TextFont {
+                font_size: 18.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.7, 0.7, 0.7)),
+        ));
+    });
+}
+
+/// Lance la génération, ou reprend celle du shift suivant si elle vise encore la bonne difficulté
+pub fn start_arena_generation(
+    mut tasks: ResMut<ArenaGenerationTasks>,
+    difficulty: Res<DifficultySystem>,
+) {
+    let difficulty_target = difficulty.get_arena_difficulty_target();
+    tasks.discard_stale(difficulty_target);
+
+    if tasks.pregenerated.is_some() {
+        info!("♻️ Arène pré-générée utilisée");
+        return;
+    }
+
+    if let Some(next) = tasks.next_shift.take() {
+        info!("♻️ Pré-génération en cours reprise (graine maîtresse {})", next.master_seed);
+        tasks.current = Some(next);
+        return;
+    }
+
+    // Ajuster l'arène au niveau de difficulté courant
+    tasks.current = Some(ArenaGenerationTask::spawn(difficulty_target));
+}
+
+pub fn loading_system(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut arena_manager: ResMut<ArenaManager>,
+    mut shift_manager: ResMut<ShiftManager>,
+    mut tasks: ResMut<ArenaGenerationTasks>,
+    mut progress_text: Query<&mut Text, With<LoadingProgressText>>,
+    mut progress_fill: Query<&mut Node, With<LoadingProgressFill>>,
+) {
+    let (result, master_seed) = match tasks.pregenerated.take() {
+        Some(next) => (Ok(next.outcome), None),
+        None => {
+            let Some(task) = tasks.current.as_mut() else {
+                return;
+            };
+
+            match task.poll() {
+                Some(result) => (result, Some(task.master_seed)),
+                None => {
+                    // Progression alimentée par les étapes terminées du pipeline
+                    let snapshot = task.progress.snapshot();
+                    for mut node in progress_fill.iter_mut() {
+                        node.width = Val::Percent(snapshot.fraction() * 100.0);
+                    }
+                    for mut text in progress_text.iter_mut() {
+                        **text = format!("Essai {} — étape {}/{} {}",
+                                         snapshot.attempt.max(1), snapshot.stages_done, snapshot.stages_total, snapshot.stage);
+                    }
+                    return;
+                }
+            }
+        }
+    };
+    tasks.current = None;
+
+    match result {
+        Ok(outcome) => {
+            if outcome.fallback {
+                warn!("⚠️ Arène de secours utilisée après {} essais", outcome.attempts);
+            } else {
+                info!("✅ Arène générée avec succès (graine {}, maîtresse {:?}, {} essais, relâchement {})",
+                      outcome.seed, master_seed, outcome.attempts, outcome.relaxation_level);
+            }
+            for failure in &outcome.failures {
+                warn!("⚠️ Graine {} rejetée: {}", failure.seed, failure.error);
+            }
+
+            let arena = outcome.arena;
+            arena_manager.arena_bounds = Vec3::new(arena.width as f32, 5.0, arena.height as f32);
+            arena_manager.current_arena = Some(arena);
+
+            // Préparer le premier shift
+            shift_manager.start_shift();
+
+            next_state.set(GameState::Playing);
+        }
+        Err(e) => {
+            error!("❌ Erreur lors de la génération de l'arène: {}", e);
+            next_state.set(GameState::MainMenu);
+        }
+    }
+}
+
+pub fn cleanup_loading(
+    mut commands: Commands,
+    mut tasks: ResMut<ArenaGenerationTasks>,
+    query: Query<Entity, With<LoadingUI>>,
+) {
+    // Quitter l'écran avant la fin (Échap) annule la génération
+    tasks.cancel_current();
+
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+// ============================================================================
+// PRÉ-GÉNÉRATION DU SHIFT SUIVANT
+// ============================================================================
+
+/// Lance la génération de l'arène suivante dès que la partie commence
+pub fn start_next_shift_pregeneration(
+    mut tasks: ResMut<ArenaGenerationTasks>,
+    difficulty: Res<DifficultySystem>,
+) {
+    // Retour de pause : la pré-génération est déjà lancée ou prête
+    if tasks.next_shift.is_some() || tasks.pregenerated.is_some() {
+        return;
+    }
+
+    let task = ArenaGenerationTask::spawn(difficulty.get_arena_difficulty_target());
+    info!("🔮 Pré-génération de l'arène suivante (graine maîtresse {})", task.master_seed);
+    tasks.next_shift = Some(task);
+}
+
+pub fn poll_next_shift_pregeneration(mut tasks: ResMut<ArenaGenerationTasks>) {
+    let Some(result) = tasks.next_shift.as_mut().and_then(|task| task.poll()) else {
+        return;
+    };
+    let Some(task) = tasks.next_shift.take() else {
+        return;
+    };
+
+    match result {
+        Ok(outcome) => {
+            info!("🔮 Arène suivante prête ({} essais)", outcome.attempts);
+            tasks.pregenerated = Some(PregeneratedArena { outcome, difficulty_target: task.difficulty_target });
+        }
+        // Le prochain chargement générera l'arène lui-même
+        Err(e) => warn!("⚠️ Pré-génération échouée: {}", e),
+    }
+}
+
+/// En fin de shift, l'arène pré-générée prend la place de l'arène courante si elle vise encore
+/// la difficulté du moment ; sinon elle est jetée. Dans tous les cas, l'arène d'après est
+/// relancée à la difficulté courante.
+pub fn swap_arena_on_shift_end(
+    mut commands: Commands,
+    mut shift_end_events: EventReader<ShiftEndEvent>,
+    difficulty: Res<DifficultySystem>,
+    mut tasks: ResMut<ArenaGenerationTasks>,
+    mut arena_manager: ResMut<ArenaManager>,
+    mut player_query: Query<&mut Transform, With<Player>>,
+) {
+    let Some(event) = shift_end_events.read().last() else {
+        return;
+    };
+
+    let difficulty_target = difficulty.get_arena_difficulty_target();
+    tasks.discard_stale(difficulty_target);
+
+    if let Some(next) = tasks.pregenerated.take() {
+        for entity in arena_manager.spawned_modules.drain(..) {
+            commands.entity(entity).despawn();
+        }
+
+        let arena = next.outcome.arena;
+        if let Some((x, y)) = arena.get_player_position() {
+            for mut transform in player_query.iter_mut() {
+                transform.translation = Vec3::new(x as f32, 2.0, y as f32);
+            }
+        }
+        arena_manager.arena_bounds = Vec3::new(arena.width as f32, 5.0, arena.height as f32);
+        arena_manager.current_arena = Some(arena);
+
+        // Après les despawns ci-dessus, comme à l'entrée en partie
+        commands.run_system_cached(spawn_arena_visuals);
+        commands.run_system_cached(prepare_arena_textures);
+        commands.run_system_cached(apply_arena_theme);
+        info!("🔄 Fin du shift {} : arène pré-générée en place (difficulté {:.2})",
+              event.shift_number, next.difficulty_target);
+    }
+
+    if tasks.next_shift.is_none() && tasks.pregenerated.is_none() {
+        let task = ArenaGenerationTask::spawn(difficulty_target);
+        info!("🔮 Pré-génération de l'arène suivante (graine maîtresse {})", task.master_seed);
+        tasks.next_shift = Some(task);
+    }
+}
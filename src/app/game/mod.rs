@@ -2,15 +2,18 @@ use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 use log::info;
 use log::error;
+use log::warn;
 use colored::Style;
 
 mod states;
 mod player;
 mod input;
+mod loading;
 
 pub use states::*;
 pub use player::*;
 pub use input::*;
+pub use loading::*;
 
 use crate::app::core::*;
 use crate::app::generation::*;
@@ -18,12 +21,12 @@ use crate::app::monitoring::*;
 use crate::app::data::*;
 use crate::app::mesh_generation::*;
 use crate::app::camera::*;
-use crate::app::game::gameplay::ShiftManager;
-use crate::app::game::gameplay::DifficultySystem;
-use crate::app::game::gameplay::DangerPressureSystem;
+use crate::app::core::gameplay::ShiftManager;
+use crate::app::core::gameplay::DifficultySystem;
+use crate::app::core::gameplay::DangerPressureSystem;
 use crate::app::ui::update_survival_ui;
 use crate::app::ui::update_game_info_ui;
-use crate::app::game::gameplay::MutationEngine;
+use crate::app::core::gameplay::MutationEngine;
 use crate::app::ui::setup_survival_ui;
 
 pub struct GamePlugin;
@@ -43,6 +46,7 @@ impl Plugin for GamePlugin {
             .init_resource::<ArenaManager>()
             .init_resource::<CameraContext>()
             .init_resource::<TouchInputState>()
+            .init_resource::<ArenaGenerationTasks>()
 
             // Événements
             .add_event::<ShiftStartEvent>()
@@ -56,14 +60,19 @@ impl Plugin for GamePlugin {
             .add_systems(OnEnter(GameState::MainMenu), setup_main_menu)
             .add_systems(OnExit(GameState::MainMenu), cleanup_main_menu)
 
-            .add_systems(OnEnter(GameState::Loading), setup_loading)
-            .add_systems(Update, loading_system.run_if(in_state(GameState::Loading)))
+            .add_systems(OnEnter(GameState::Loading), (setup_loading, start_arena_generation))
+            .add_systems(Update, (
+                handle_keyboard_input,
+                loading_system,
+            ).run_if(in_state(GameState::Loading)))
             .add_systems(OnExit(GameState::Loading), cleanup_loading)
 
             .add_systems(OnEnter(GameState::Playing), (
                 setup_game_session,
+                spawn_arena_visuals,
                 spawn_player,
                 setup_camera_for_gameplay,
+                start_next_shift_pregeneration,
             ))
             .add_systems(Update, (
                 // Systèmes d'entrée
@@ -78,6 +87,10 @@ impl Plugin for GamePlugin {
                 // Systèmes de caméra
                 update_camera_context_system,
 
+                // Génération de l'arène suivante en arrière-plan, relève de l'arène en fin de shift
+                poll_next_shift_pregeneration,
+                swap_arena_on_shift_end,
+
                 // Systèmes UI
                 update_survival_ui,
                 update_game_info_ui,
@@ -99,6 +112,9 @@ impl Plugin for GamePlugin {
 // RESSOURCES ET COMPOSANTS
 // ============================================================================
 
+/// Dossier des presets de génération (`easy.json`, `normal.json`, ...)
+pub const PRESETS_DIR: &str = "assets/presets";
+
 #[derive(Resource, Default)]
 pub struct GameSession {
     pub current_shift: u32,
@@ -117,6 +133,28 @@ pub struct ArenaManager {
     pub spawned_modules: Vec<Entity>,
 }
 
+impl ArenaManager {
+    /// Cellule de l'arène sous une position monde (une cellule = une unité)
+    pub fn world_to_cell(position: Vec3) -> (i32, i32) {
+        (position.x.round() as i32, position.z.round() as i32)
+    }
+
+    /// Vrai si la position est au-dessus d'une cellule jouable (forme de l'arène comprise)
+    pub fn is_inside_arena(&self, position: Vec3) -> bool {
+        let (x, y) = Self::world_to_cell(position);
+        self.current_arena.as_ref().is_some_and(|arena| arena.is_valid_position(x, y))
+    }
+
+    /// Point de réapparition : le spawn du joueur, sinon le centre de l'arène
+    pub fn respawn_position(&self) -> Vec3 {
+        let spawn = self.current_arena.as_ref().and_then(|arena| arena.get_player_position());
+        match spawn {
+            Some((x, y)) => Vec3::new(x as f32, 2.0, y as f32),
+            None => Vec3::new(self.arena_bounds.x * 0.5, 2.0, self.arena_bounds.z * 0.5),
+        }
+    }
+}
+
 #[derive(Resource)]
 pub struct TouchInputState {
     pub movement_touch: Option<(Vec2, u64)>, // Position et ID du touch
@@ -188,6 +226,61 @@ pub struct DynamicHazard {
     pub max_lifetime: f32,
 }
 
+/// Lave : brûle en continu tant que le joueur est en contact
+#[derive(Component)]
+pub struct LavaPit {
+    pub damage_per_second: f32,
+    pub touching_player: bool,
+}
+
+/// Émetteur de rayon : un lancer de rayon par image, arrêté au premier obstacle
+#[derive(Component)]
+pub struct LaserEmitter {
+    pub beam_length: f32,
+    pub damage_per_second: f32,
+    /// Vitesse de balayage en radians par seconde (0.0 : rayon fixe)
+    pub rotation_speed: f32,
+    /// Amplitude totale du balayage en radians, centrée sur `direction`
+    pub arc: f32,
+    /// Cap du rayon au repos, en radians autour de Y (0.0 : vers +X)
+    pub direction: f32,
+    /// Écart actuel au cap de repos
+    pub sweep_offset: f32,
+    /// Sens du balayage : 1.0 ou -1.0
+    pub sweep_sign: f32,
+    /// Longueur visible, coupée au premier obstacle
+    pub current_length: f32,
+    pub hitting_player: bool,
+}
+
+/// Rayon visible, enfant de l'émetteur
+#[derive(Component)]
+pub struct LaserBeam;
+
+/// Les rayons laser traversent ce module (vitres)
+#[derive(Component)]
+pub struct BeamPassThrough;
+
+/// Projectile de la pluie, recyclé par un pool : inactif, il est caché et sans collisions
+#[derive(Component)]
+pub struct Projectile {
+    pub damage: f32,
+    /// Point d'impact, au niveau du sol
+    pub target: Vec3,
+    /// Temps restant avant l'impact
+    pub time_to_impact: f32,
+    /// Durée totale de la chute, égale à celle de l'annonce au sol
+    pub telegraph: f32,
+    /// En vol ; repasse à `false` à l'impact ou au contact du joueur
+    pub active: bool,
+    /// Marque au sol associée
+    pub indicator: Entity,
+}
+
+/// Marque au sol annonçant l'impact d'un projectile
+#[derive(Component)]
+pub struct ImpactIndicator;
+
 #[derive(Component)]
 pub struct FragileSurface {
     pub break_delay: f32,
@@ -198,6 +291,64 @@ pub struct FragileSurface {
     pub respawn_timer: f32,
 }
 
+/// Dalle d'apparition du joueur
+#[derive(Component)]
+pub struct PlayerSpawnPad;
+
+#[derive(Component)]
+pub struct Teleporter {
+    /// Relie une entrée à la sortie du même identifiant
+    pub link_id: Option<String>,
+    pub is_entry: bool,
+}
+
+#[derive(Component)]
+pub struct ClimbSurface {
+    pub climb_speed: f32,
+}
+
+#[derive(Component)]
+pub struct TriggerButton {
+    pub trigger_id: Option<String>,
+    pub wall_mounted: bool,
+    pub one_time: bool,
+    pub reset_delay: f32,
+    pub is_pressed: bool,
+    pub reset_timer: f32,
+}
+
+#[derive(Component)]
+pub struct Lever {
+    pub trigger_id: Option<String>,
+    pub is_on: bool,
+}
+
+/// Poignée d'un levier, enfant de l'entité `Lever`
+#[derive(Component)]
+pub struct LeverHandle;
+
+/// Bandes posées sur un danger en mode daltonien, enfant du module
+#[derive(Component)]
+pub struct HazardPattern;
+
+#[derive(Component)]
+pub struct EnemySpawner {
+    pub enemy_type: String,
+    pub spawn_limit: u32,
+    pub spawn_radius: f32,
+    pub activation_delay: f32,
+    pub trigger_id: Option<String>,
+}
+
+#[derive(Component)]
+pub struct EnergyBarrier {
+    pub health: f32,
+    pub disable_on_trigger_id: Option<String>,
+    pub disable_duration: f32,
+    pub is_active: bool,
+    pub disabled_timer: f32,
+}
+
 // ============================================================================
 // ÉVÉNEMENTS
 // ============================================================================
@@ -294,83 +445,15 @@ fn cleanup_main_menu(mut commands: Commands, query: Query<Entity, With<MainMenuU
     }
 }
 
-fn setup_loading(mut commands: Commands) {
-    info!("Chargement en cours...");
-    commands.spawn((
-        LoadingUI,
-        Node::default(),
-        Style {
-            width: Val::Percent(100.0),
-            height: Val::Percent(100.0),
-            justify_content: JustifyContent::Center,
-            align_items: AlignItems::Center,
-            ..default()
-        },
-        BackgroundColor(Color::srgb(0.05, 0.05, 0.1)),
-    )).with_children(|parent| {
-        parent.spawn((
-            Text::new("Génération de l'arène..."),
-            TextFont {
-                font_size: 32.0,
-                ..default()
-            },
-            TextColor(Color::WHITE),
-        ));
-    });
-}
-
-fn loading_system(
-    mut next_state: ResMut<NextState<GameState>>,
-    mut arena_manager: ResMut<ArenaManager>,
-    mut shift_manager: ResMut<ShiftManager>,
-    mut commands: Commands,
-) {
-    // Générer la première arène
-    let mut generator = ArenaGenerator::new(Some(42));
-    let mut monitor = AnomalyMonitor::new();
-
-    match generator.generate_with_monitoring(12, 3, &mut monitor) {
-        Ok(arena) => {
-            info!("✅ Arène générée avec succès");
-            arena_manager.current_arena = Some(arena);
-            arena_manager.arena_bounds = Vec3::new(12.0, 5.0, 12.0);
-
-            // Préparer le premier shift
-            shift_manager.start_shift();
-
-            next_state.set(GameState::Playing);
-        }
-        Err(e) => {
-            error!("❌ Erreur lors de la génération de l'arène: {}", e);
-            // En cas d'erreur, retour au menu
-            next_state.set(GameState::MainMenu);
-        }
-    }
-}
-
-fn cleanup_loading(mut commands: Commands, query: Query<Entity, With<LoadingUI>>) {
-    for entity in query.iter() {
-        commands.entity(entity).despawn_recursive();
-    }
-}
-
 fn setup_game_session(
     mut commands: Commands,
     mut game_session: ResMut<GameSession>,
-    mut arena_manager: ResMut<ArenaManager>,
-    meshes: ResMut<Assets<Mesh>>,
-    materials: ResMut<Assets<StandardMaterial>>,
 ) {
     info!("🎮 Démarrage de la session de jeu");
 
     // Réinitialiser la session
     *game_session = GameSession::default();
 
-    // Générer l'arène visuellement
-    if let Some(ref arena) = arena_manager.current_arena {
-        spawn_arena_visuals(commands, arena_manager, meshes, materials);
-    }
-
     // Ajouter l'UI de survie
     setup_survival_ui(&mut commands);
 }
@@ -3,7 +3,7 @@ use bevy_rapier3d::prelude::*;
 use crate::app::game::*;
 use crate::app::camera::*;
 use log::info;
-use crate::app::game::gameplay::ShiftManager;
+use crate::app::core::gameplay::ShiftManager;
 
 pub fn spawn_player(mut commands: Commands) {
     info!("👤 Spawn du joueur");
@@ -113,17 +113,15 @@ pub fn player_movement_system(
             velocity.linvel.z *= 0.8;
         }
 
-        // Vérifier les limites de l'arène
-        let bounds = arena_manager.arena_bounds;
+        // Vérifier les limites de l'arène (les cellules hors de sa forme comptent comme du vide)
         let pos = transform.translation;
 
-        if pos.x < -bounds.x * 0.5 || pos.x > bounds.x * 0.5 ||
-           pos.z < -bounds.z * 0.5 || pos.z > bounds.z * 0.5 {
+        if !arena_manager.is_inside_arena(pos) {
             // Joueur sort de l'arène - téléporter ou appliquer des dégâts
             if pos.y < -5.0 {
                 // Chute mortelle
                 // TODO: Déclencher événement de mort
-                transform.translation = Vec3::new(6.0, 2.0, 6.0); // Respawn au centre
+                transform.translation = arena_manager.respawn_position();
             }
         }
 
@@ -153,8 +151,10 @@ pub fn player_collision_system(
     mut collision_events: EventReader<CollisionEvent>,
     mut player_query: Query<(Entity, &mut Player), With<Player>>,
     orb_query: Query<&EnergyOrb, With<EnergyOrb>>,
-    hazard_query: Query<&DynamicHazard, With<DynamicHazard>>,
+    mut projectile_query: Query<&mut Projectile>,
+    mut lava_query: Query<&mut LavaPit>,
     mut fragile_query: Query<&mut FragileSurface, With<FragileSurface>>,
+    mut button_query: Query<&mut TriggerButton>,
     mut orb_collected_events: EventWriter<OrbCollectedEvent>,
     mut player_death_events: EventWriter<PlayerDeathEvent>,
     mut commands: Commands,
@@ -185,26 +185,22 @@ pub fn player_collision_system(
                         }
                     }
 
-                    // Collision avec danger
-                    if let Ok(hazard) = hazard_query.get(other_entity) {
-                        match hazard.hazard_type {
-                            HazardType::LavaPit => {
-                                player.health -= 25.0 * hazard.intensity;
-                                if player.health <= 0.0 {
-                                    player_death_events.send(PlayerDeathEvent {
-                                        cause: DeathCause::Hazard("Lava Pit".to_string()),
-                                    });
-                                }
-                            },
-                            HazardType::LaserBeam => {
-                                player.health -= 30.0 * hazard.intensity;
-                                if player.health <= 0.0 {
-                                    player_death_events.send(PlayerDeathEvent {
-                                        cause: DeathCause::Hazard("Laser Beam".to_string()),
-                                    });
-                                }
-                            },
-                            _ => {},
+                    // Lave : les dégâts courent jusqu'à la fin du contact (`lava_damage_system`)
+                    if let Ok(mut lava) = lava_query.get_mut(other_entity) {
+                        lava.touching_player = true;
+                    }
+
+                    // Projectile : touché une seule fois, puis rendu au pool
+                    if let Ok(mut projectile) = projectile_query.get_mut(other_entity) {
+                        if projectile.active {
+                            projectile.active = false;
+                            let was_alive = player.health > 0.0;
+                            player.health -= projectile.damage;
+                            if was_alive && player.health <= 0.0 {
+                                player_death_events.write(PlayerDeathEvent {
+                                    cause: DeathCause::Hazard("Projectile".to_string()),
+                                });
+                            }
                         }
                     }
 
@@ -216,12 +212,21 @@ pub fn player_collision_system(
                         }
                     }
 
+                    // Bouton au sol : pressé en marchant dessus
+                    if let Ok(mut button) = button_query.get_mut(other_entity) {
+                        if !button.wall_mounted && !button.is_pressed {
+                            button.is_pressed = true;
+                            button.reset_timer = 0.0;
+                            info!("🔘 Bouton pressé ({:?})", button.trigger_id);
+                        }
+                    }
+
                     // Détection du sol pour le saut
                     // TODO: Améliorer la détection du sol avec les normales de collision
                     player.is_grounded = true;
                 },
                 CollisionEvent::Stopped(entity1, entity2, _) => {
-                    let (_player_entity, _other_entity) = if *entity1 == player_entity {
+                    let (_player_entity, other_entity) = if *entity1 == player_entity {
                         (*entity1, *entity2)
                     } else if *entity2 == player_entity {
                         (*entity2, *entity1)
@@ -229,6 +234,10 @@ pub fn player_collision_system(
                         continue;
                     };
 
+                    if let Ok(mut lava) = lava_query.get_mut(other_entity) {
+                        lava.touching_player = false;
+                    }
+
                     // Le joueur quitte le sol
                     // Note: Cette logique est simplifiée, il faudrait vérifier
                     // si on quitte vraiment le sol ou juste un autre objet
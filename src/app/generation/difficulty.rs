@@ -0,0 +1,257 @@
+//generation/difficulty.rs
+use super::*;
+use serde::{Deserialize, Serialize};
+
+/// Default distance between estimate and target accepted by `generate_for_difficulty`
+pub const DEFAULT_DIFFICULTY_TOLERANCE: f32 = 0.1;
+
+/// Generations `generate_for_difficulty` runs before settling for the closest arena
+pub const MAX_DIFFICULTY_ATTEMPTS: u32 = 12;
+
+/// Hazard share of the playable cells that counts as fully dangerous
+const HAZARD_DENSITY_CAP: f32 = 0.2;
+
+/// Summed rule hardness that counts as the hardest rule set
+const RULE_HARDNESS_CAP: f32 = 2.0;
+
+/// Error beyond which tuning adds or drops a rule instead of only nudging densities
+const RULE_COUNT_STEP: f32 = 0.15;
+
+/// Most rules the tuner will ask for
+const MAX_TUNED_RULES: u32 = 4;
+
+/// Share of each component in the final score
+const HAZARD_WEIGHT: f32 = 0.35;
+const PATH_WEIGHT: f32 = 0.2;
+const RULE_WEIGHT: f32 = 0.3;
+const ENV_WEIGHT: f32 = 0.15;
+
+/// How hard an arena is, every field within 0.0 (trivial) ..= 1.0 (brutal)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DifficultyEstimate {
+    /// Weighted blend of the components below
+    pub score: f32,
+    /// Hazard cells relative to the playable area
+    pub hazard_density: f32,
+    /// Mean walking distance from the spawn to each orb, relative to width + height.
+    /// Unreachable orbs count as the full length.
+    pub orb_path_length: f32,
+    /// `difficulty_*` tags of the active rules, summed
+    pub rule_hardness: f32,
+    /// Game speed above its default and gravity away from its default
+    pub environment: f32,
+}
+
+impl DifficultyEstimate {
+    pub fn distance_to(&self, target: f32) -> f32 {
+        (self.score - target).abs()
+    }
+}
+
+/// Score `arena` against the ranges of `env_vars_db`
+pub fn estimate_difficulty(arena: &Arena, env_vars_db: &EnvVarsDatabase) -> DifficultyEstimate {
+    let hazard_density = hazard_density(arena);
+    let orb_path_length = orb_path_length(arena);
+    let rule_hardness = rule_hardness(&arena.active_rules);
+    let environment = environment_pressure(arena, env_vars_db);
+
+    let score = HAZARD_WEIGHT * hazard_density
+        + PATH_WEIGHT * orb_path_length
+        + RULE_WEIGHT * rule_hardness
+        + ENV_WEIGHT * environment;
+
+    DifficultyEstimate {
+        score: score.clamp(0.0, 1.0),
+        hazard_density,
+        orb_path_length,
+        rule_hardness,
+        environment,
+    }
+}
+
+fn hazard_density(arena: &Arena) -> f32 {
+    let playable = arena.playable_cell_count().max(1) as f32;
+    let density = arena.get_hazard_positions().len() as f32 / playable;
+    (density / HAZARD_DENSITY_CAP).min(1.0)
+}
+
+fn orb_path_length(arena: &Arena) -> f32 {
+    let orbs: Vec<(i32, i32)> = arena.modules.iter()
+        .filter(|cell| matches!(cell.module_id, ModuleId::OrbEnergy))
+        .map(|cell| (cell.x, cell.y))
+        .collect();
+    let Some(spawn) = arena.get_player_position() else {
+        return 1.0;
+    };
+    if orbs.is_empty() {
+        return 0.0;
+    }
+
+    let distances = arena.get_path_distances(spawn);
+    let span = (arena.width + arena.height) as f32;
+    let total: f32 = orbs.iter()
+        .map(|orb| distances.get(orb).map_or(1.0, |&d| (d as f32 / span).min(1.0)))
+        .sum();
+
+    total / orbs.len() as f32
+}
+
+fn rule_hardness(rules: &[Rule]) -> f32 {
+    let total: f32 = rules.iter()
+        .map(|rule| {
+            if rule.tags.iter().any(|t| t == "difficulty_hard") {
+                1.0
+            } else if rule.tags.iter().any(|t| t == "difficulty_medium") {
+                0.5
+            } else {
+                0.2
+            }
+        })
+        .sum();
+
+    (total / RULE_HARDNESS_CAP).min(1.0)
+}
+
+fn environment_pressure(arena: &Arena, env_vars_db: &EnvVarsDatabase) -> f32 {
+    // Share of the way from the default value to the end of the range it moved towards
+    let deviation = |id: EnvVarId, slower_is_easier: bool| -> f32 {
+        let (Some(var), Some(&value)) = (env_vars_db.get_variable(&id), arena.env_variables.get(&id)) else {
+            return 0.0;
+        };
+        let span = if value >= var.default_value {
+            var.range.1 - var.default_value
+        } else if slower_is_easier {
+            return 0.0;
+        } else {
+            var.default_value - var.range.0
+        };
+        if span <= 0.0 {
+            return 0.0;
+        }
+        ((value - var.default_value).abs() / span).min(1.0) as f32
+    };
+
+    (deviation(EnvVarId::GameSpeed, true) + deviation(EnvVarId::Gravity, false)) / 2.0
+}
+
+/// Arena produced for a requested difficulty, with the estimate it reached
+#[derive(Debug, Clone)]
+pub struct DifficultyMatch {
+    pub arena: Arena,
+    pub estimate: DifficultyEstimate,
+    pub target: f32,
+    /// Config the arena was generated from, after tuning
+    pub config: GenerationConfig,
+    /// Generations run, failed ones included
+    pub attempts: u32,
+}
+
+impl DifficultyMatch {
+    pub fn is_within(&self, tolerance: f32) -> bool {
+        self.estimate.distance_to(self.target) <= tolerance
+    }
+}
+
+impl ArenaGenerator {
+    pub fn estimate_difficulty(&self, arena: &Arena) -> DifficultyEstimate {
+        estimate_difficulty(arena, &self.env_vars_db)
+    }
+
+    /// Generate from `base`, retuning rule count and hazard densities after each arena
+    /// until its estimate lands within `tolerance` of `target` (0.0..=1.0).
+    ///
+    /// After `MAX_DIFFICULTY_ATTEMPTS` the closest valid arena is returned with a
+    /// `DifficultyOffTarget` warning. Fails with the last attempt's error if none produced
+    /// a valid arena, or straight away on an invalid configuration before any valid arena.
+    pub fn generate_for_difficulty(
+        &mut self,
+        base: &GenerationConfig,
+        target: f32,
+        tolerance: f32,
+        monitor: &mut AnomalyMonitor,
+    ) -> GenerationResult<DifficultyMatch> {
+        if !(0.0..=1.0).contains(&target) {
+            return Err(ArenaGenerationError::InvalidConfiguration {
+                details: format!("difficulty target must be within 0.0..=1.0, got {}", target),
+            });
+        }
+
+        let mut config = base.clone().with_target_difficulty(target);
+        let mut best: Option<(DifficultyMatch, AnomalyMonitor)> = None;
+        let mut last_error = None;
+        let mut attempts = 0;
+
+        while attempts < MAX_DIFFICULTY_ATTEMPTS {
+            attempts += 1;
+            let mut attempt_monitor = AnomalyMonitor::new();
+
+            let arena = match self.generate(&config, &mut attempt_monitor) {
+                Ok(arena) => arena,
+                // Broken arena: the streams have moved on, so simply try again
+                Err(e) if e.is_retryable() => {
+                    last_error = Some(e);
+                    continue;
+                }
+                // Keep the closest arena found so far rather than throwing it away
+                Err(_) if best.is_some() => break,
+                Err(e) => return Err(e),
+            };
+
+            let estimate = self.estimate_difficulty(&arena);
+            let error = target - estimate.score;
+            let closer = best.as_ref().is_none_or(|(b, _)| estimate.distance_to(target) < b.estimate.distance_to(target));
+            if closer {
+                let candidate = DifficultyMatch { arena, estimate, target, config: config.clone(), attempts };
+                best = Some((candidate, attempt_monitor));
+            }
+
+            if error.abs() <= tolerance {
+                break;
+            }
+            retune(&mut config, error);
+        }
+
+        let Some((mut result, chosen_monitor)) = best else {
+            return Err(last_error.unwrap_or_else(|| ArenaGenerationError::GenerationFailed {
+                message: format!("no valid arena in {} attempts at difficulty {:.2}", attempts, target),
+            }));
+        };
+        result.attempts = attempts;
+        monitor.merge(chosen_monitor);
+
+        if !result.is_within(tolerance) {
+            monitor.report_anomaly(
+                AnomalyCode::DifficultyOffTarget,
+                format!("Closest arena scored {:.2} for a target of {:.2} (±{:.2}) after {} attempts",
+                        result.estimate.score, target, tolerance, attempts),
+                AnomalySeverity::Warning,
+                AnomalyLocation::none(),
+            );
+        }
+
+        result.arena.generation_metadata.constraints_applied.push(format!("difficulty:{:.2}", result.estimate.score));
+        monitor.record_metric("difficulty_target", target as f64);
+        monitor.record_metric("difficulty_score", result.estimate.score as f64);
+        monitor.record_metric("difficulty_attempts", attempts as f64);
+
+        Ok(result)
+    }
+}
+
+/// Push the config towards `error` (target minus estimate): more hazards, lava and rules
+/// when too easy, fewer when too hard
+fn retune(config: &mut GenerationConfig, error: f32) {
+    let d = &mut config.densities;
+    d.hazards = (d.hazards * (1.0 + 2.0 * error)).clamp(0.1, MAX_HAZARD_WEIGHT);
+    d.lava_expansion_chance = (d.lava_expansion_chance + error).clamp(0.0, 1.0);
+    d.min_walkable = (d.min_walkable - error as f64 * 0.2).clamp(0.25, 0.6);
+
+    // Never below what `validate` demands: the forced rules and every quota minimum
+    let quota_min = config.rule_quotas.iter().map(|quota| quota.min).max().unwrap_or(0);
+    let min_rules = (config.forced_rules.len() as u32).max(quota_min).max(1);
+    if error > RULE_COUNT_STEP && config.rule_count < MAX_TUNED_RULES {
+        config.rule_count += 1;
+    } else if error < -RULE_COUNT_STEP && config.rule_count > min_rules {
+        config.rule_count -= 1;
+    }
+}
@@ -7,14 +7,49 @@ use crate::app::monitoring::*;
 use rand::{Rng, SeedableRng};
 use rand::rngs::StdRng;
 use std::collections::{HashMap, HashSet};
-use std::time::Instant;
-use anyhow::Result;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+mod difficulty;
+mod pipeline;
+mod progress;
+mod repair;
+mod retry;
+mod shrink;
+mod stress;
+mod templates;
+
+pub use difficulty::*;
+pub use pipeline::*;
+pub use progress::*;
+pub use repair::*;
+pub use retry::*;
+pub use shrink::*;
+pub use stress::*;
+pub use templates::*;
+
+/// Mixed into the seed for the rule-selection stream, so that forcing a rule set
+/// leaves the layout stream untouched
+const RULE_STREAM_SALT: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Every fallible generation call fails with a typed `ArenaGenerationError`
+pub type GenerationResult<T> = std::result::Result<T, ArenaGenerationError>;
+
+/// Weight multiplier for `GenerationConfig::preferred_rules` in the random pick
+pub const PREFERRED_RULE_BOOST: f32 = 3.0;
 
 pub struct ArenaGenerator {
     rng: StdRng,
+    rule_rng: StdRng,
     rules_db: RulesDatabase,
     modules_db: ModulesDatabase,
     env_vars_db: EnvVarsDatabase,
+    config: GenerationConfig,
+    templates: Vec<Template>,
+    pipeline: GenerationPipeline,
+    last_repair_report: Option<RepairReport>,
+    stage_snapshots: Vec<StageSnapshot>,
+    progress: Option<Arc<GenerationProgress>>,
 }
 
 impl ArenaGenerator {
@@ -23,87 +58,229 @@ impl ArenaGenerator {
         
         Self {
             rng: StdRng::seed_from_u64(actual_seed),
+            rule_rng: StdRng::seed_from_u64(actual_seed ^ RULE_STREAM_SALT),
             rules_db: RulesDatabase::new(),
             modules_db: ModulesDatabase::new(),
             env_vars_db: EnvVarsDatabase::new(),
+            config: GenerationConfig::default(),
+            templates: Vec::new(),
+            pipeline: GenerationPipeline::standard(),
+            last_repair_report: None,
+            stage_snapshots: Vec::new(),
+            progress: None,
         }
     }
+
+    /// Defaults used by `generate_with_monitoring` and friends, which only override size and rules
+    pub fn with_config(mut self, config: GenerationConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn config(&self) -> &GenerationConfig {
+        &self.config
+    }
+
+    /// Footprint of the arenas generated from now on
+    pub fn with_shape(mut self, shape: ArenaShape) -> Self {
+        self.config.shape = shape;
+        self
+    }
+
+    /// Rules (and their relations) to pick from instead of the built-in database
+    pub fn with_rules_db(mut self, rules_db: RulesDatabase) -> Self {
+        self.rules_db = rules_db;
+        self
+    }
+
+    /// Set pieces stamped, in order, before the procedural layout fills the rest
+    pub fn with_templates(mut self, templates: Vec<Template>) -> Self {
+        self.templates = templates;
+        self
+    }
+
+    /// Replace the stages run for each arena
+    pub fn with_pipeline(mut self, pipeline: GenerationPipeline) -> Self {
+        self.pipeline = pipeline;
+        self
+    }
+
+    pub fn pipeline_mut(&mut self) -> &mut GenerationPipeline {
+        &mut self.pipeline
+    }
+
+    /// Arena after each stage of the last generation, when the pipeline has debug snapshots on
+    pub fn stage_snapshots(&self) -> &[StageSnapshot] {
+        &self.stage_snapshots
+    }
+
+    /// Number of repair passes tried before giving up on a broken arena (0 disables repair)
+    pub fn with_repair_attempts(mut self, attempts: u32) -> Self {
+        self.config.max_repair_attempts = attempts;
+        self
+    }
+
+    /// What the repair stage changed during the last generation
+    pub fn last_repair_report(&self) -> Option<&RepairReport> {
+        self.last_repair_report.as_ref()
+    }
     
-    pub fn generate_with_monitoring(&mut self, size: u32, rule_count: u32, monitor: &mut AnomalyMonitor) -> Result<Arena> {
+    /// Generate an arena from a full config; the config is recorded in the arena metadata
+    pub fn generate(&mut self, config: &GenerationConfig, monitor: &mut AnomalyMonitor) -> GenerationResult<Arena> {
+        let arena = self.build_arena(config.clone(), monitor)?;
+        Self::fail_on_critical(monitor)?;
+        Ok(arena)
+    }
+
+    pub fn generate_with_monitoring(&mut self, size: u32, rule_count: u32, monitor: &mut AnomalyMonitor) -> GenerationResult<Arena> {
+        self.generate_with_dimensions(size, size, rule_count, monitor)
+    }
+
+    /// Generate a `width` x `height` arena (before the shape mask is applied)
+    pub fn generate_with_dimensions(&mut self, width: u32, height: u32, rule_count: u32, monitor: &mut AnomalyMonitor) -> GenerationResult<Arena> {
+        let config = self.config.clone()
+            .with_size(width, height)
+            .with_rule_count(rule_count);
+        self.generate(&config, monitor)
+    }
+
+    /// Generate with exactly `rules` active instead of a random selection.
+    /// With the same seed and size, the layout stream is the same as for a random pick.
+    pub fn generate_with_rules(&mut self, size: u32, rules: &[RuleId], monitor: &mut AnomalyMonitor) -> GenerationResult<Arena> {
+        let config = self.config.clone()
+            .with_size(size, size)
+            .with_fixed_rules(rules.to_vec());
+        self.generate(&config, monitor)
+    }
+
+    fn fail_on_critical(monitor: &AnomalyMonitor) -> GenerationResult<()> {
+        if monitor.has_critical_anomalies() {
+            let anomalies = monitor.get_anomalies().iter()
+                .filter(|a| a.severity == AnomalySeverity::Critical)
+                .cloned()
+                .collect();
+            return Err(ArenaGenerationError::CriticalAnomaly { anomalies });
+        }
+        Ok(())
+    }
+
+    /// Run the whole pipeline, then validate the result. Returns the arena even when it has
+    /// critical anomalies; those of the final validation belong to no stage.
+    pub(crate) fn build_arena(&mut self, config: GenerationConfig, monitor: &mut AnomalyMonitor) -> GenerationResult<Arena> {
+        config.validate()?;
         monitor.start_generation();
         let start_time = Instant::now();
         
         // Create base arena
-        let mut arena = Arena::new(size, size, self.rng.r#gen());
-        
-        // Step 1: Select and apply rules
-        let selected_rules = self.select_compatible_rules(rule_count, monitor)?;
-        arena.active_rules = selected_rules;
-        
-        // Step 2: Configure environmental variables
-        self.configure_environment(&mut arena, monitor)?;
-        
-        // Step 3: Generate base layout using WFC
-        self.generate_base_layout(&mut arena, monitor)?;
-        
-        // Step 4: Apply rule-specific modifications
-        self.apply_rule_modifications(&mut arena, monitor)?;
+        let mut arena = Arena::with_shape(config.width, config.height, self.rng.r#gen(), &config.shape);
+        if arena.playable_cell_count() == 0 {
+            return Err(ArenaGenerationError::ConstraintViolation {
+                constraint: "playable_area".to_string(),
+                details: format!("shape {:?} leaves no playable cell in {}x{}", config.shape, arena.width, arena.height),
+            });
+        }
+        if arena.mask.is_some() {
+            arena.generation_metadata.constraints_applied.push(format!("shape:{}", config.shape.name()));
+        }
+        arena.generation_metadata.config = Some(config.clone());
         
-        // Step 5: Place interactive elements
-        self.place_interactive_elements(&mut arena, monitor)?;
+        // Run every stage (rules, environment, templates, layout, rule modifications,
+        // interactive elements, balance, repair by default)
+        let pipeline = std::mem::take(&mut self.pipeline);
+        let budget = TimeBudget::start(config.time_budget_ms);
+        let result = pipeline.run(self, &mut arena, config, budget, monitor);
+        self.pipeline = pipeline;
+        result?;
         
-        // Step 6: Balance and optimize
-        self.balance_arena(&mut arena, monitor)?;
+        monitor.validate_arena_with(&arena, self.rules_db.relations());
         
         // Record generation time
         let generation_time = start_time.elapsed();
         arena.generation_metadata.generation_time_ms = generation_time.as_millis() as u64;
         
-        // Final validation
-        monitor.validate_arena(&arena);
         monitor.check_generation_time();
         
-        if monitor.has_critical_anomalies() {
-            anyhow::bail!("Arena generation failed due to critical anomalies: {:?}", monitor.get_anomalies());
-        }
-        
         Ok(arena)
     }
+
+    fn resolve_rules(&self, ids: &[RuleId], monitor: &mut AnomalyMonitor) -> GenerationResult<Vec<Rule>> {
+        let mut selected = Vec::new();
+
+        for id in ids {
+            let rule = self.rules_db.get_rule(id)
+                .ok_or_else(|| ArenaGenerationError::InvalidConfiguration { details: format!("unknown rule {:?}", id) })?;
+
+            monitor.record_rule_application(rule.id.clone());
+            selected.push(rule.clone());
+        }
+
+        Ok(selected)
+    }
     
-    fn select_compatible_rules(&mut self, count: u32, monitor: &mut AnomalyMonitor) -> Result<Vec<Rule>> {
+    fn select_rules(&mut self, config: &GenerationConfig, monitor: &mut AnomalyMonitor) -> GenerationResult<Vec<Rule>> {
+        // Forced rules (and what they imply) come first and do not draw from the rule stream
+        let mut required_ids: Vec<RuleId> = Vec::new();
+        for id in &config.forced_rules {
+            for rule_id in std::iter::once(id.clone()).chain(self.rules_db.relations().requirements(id)) {
+                if !required_ids.contains(&rule_id) {
+                    required_ids.push(rule_id);
+                }
+            }
+        }
+        let mut selected = self.resolve_rules(&required_ids, monitor)?;
+        self.check_required_rules(&selected, config)?;
+        let mut incompatible_set: HashSet<RuleId> = selected.iter()
+            .flat_map(|rule| self.rules_db.relations().conflicts_of(&rule.id))
+            .collect();
+
         // Clone the rules to release the immutable borrow on `self`
         let all_rules = self.rules_db.get_all_rules().clone();
-        let mut selected = Vec::new();
-        let mut incompatible_set = HashSet::new();
+        let count = config.rule_count;
 
         // Use weighted selection based on difficulty and tags
-        for _ in 0..count {
-            let available_rules: Vec<Rule> = all_rules.iter()
+        for _ in (selected.len() as u32)..count {
+            let mut available_rules: Vec<Rule> = all_rules.iter()
+            .filter(|rule| !config.forbidden_rules.contains(&rule.id))
             .filter(|rule| !incompatible_set.contains(&rule.id))
             .filter(|rule| !selected.iter().any(|s: &Rule| s.id == rule.id))
+            .filter(|rule| !config.rule_quotas.iter().any(|q| q.applies_to(rule) && q.is_full(&selected)))
+            .filter(|rule| self.requirements_fit(rule, &selected, &incompatible_set, config))
             // The Fix: Call .cloned() twice to resolve the double reference.
             .cloned() // Converts Iterator<Item=&&Rule> to Iterator<Item=&Rule>
             .cloned() // Converts Iterator<Item=&Rule> to Iterator<Item=Rule>
             .collect();
 
+            // Once the remaining slots are all needed for quota minimums, only pick rules that fill one
+            let remaining = count - selected.len() as u32;
+            let unmet: Vec<&RuleQuota> = config.rule_quotas.iter()
+                .filter(|q| q.deficit(&selected) > 0)
+                .collect();
+            let needed: u32 = unmet.iter().map(|q| q.deficit(&selected)).sum();
+            if needed >= remaining && available_rules.iter().any(|r| unmet.iter().any(|q| q.applies_to(r))) {
+                available_rules.retain(|r| unmet.iter().any(|q| q.applies_to(r)));
+            }
+
             if available_rules.is_empty() {
                 monitor.report_anomaly(
-                    "RULES", 
+                    AnomalyCode::RuleSelectionShortfall,
                     format!("Could only select {} rules out of {} requested", selected.len(), count),
                     AnomalySeverity::Warning,
-                    None
+                    AnomalyLocation::none(),
                 );
                 break;
             }
 
             let weights: Vec<f32> = available_rules.iter()
-            .map(|rule| self.calculate_rule_weight(rule, &selected))
+            .map(|rule| {
+                let weight = self.calculate_rule_weight(rule, &selected, config.target_difficulty);
+                if config.preferred_rules.contains(&rule.id) { weight * PREFERRED_RULE_BOOST } else { weight }
+            })
             .collect();
 
             // Fix: Move the mutable borrow after all immutable borrows are done
             let selected_index = {
                 let total_weight: f32 = weights.iter().sum();
-                let mut target = self.rng.r#gen::<f32>() * total_weight;
+                let mut target = self.rule_rng.r#gen::<f32>() * total_weight;
             
                 let mut index = 0;
                 for (i, &weight) in weights.iter().enumerate() {
@@ -116,20 +293,96 @@ impl ArenaGenerator {
                 index
             };
 
-            let selected_rule = &available_rules[selected_index];
+            // The pick brings its requirements along
+            let selected_rule = available_rules[selected_index].clone();
+            let implied = self.missing_requirements(&selected_rule, &selected);
 
-            for incompatible in &selected_rule.incompatible_with {
-                incompatible_set.insert(incompatible.clone());
+            for rule in std::iter::once(selected_rule).chain(implied) {
+                incompatible_set.extend(self.rules_db.relations().conflicts_of(&rule.id));
+                monitor.record_rule_application(rule.id.clone());
+                selected.push(rule);
             }
 
-            monitor.record_rule_application(selected_rule.id.clone());
-            selected.push(selected_rule.clone());
+            if selected.len() as u32 >= count {
+                break;
+            }
+        }
+
+        for quota in &config.rule_quotas {
+            let deficit = quota.deficit(&selected);
+            if deficit > 0 {
+                monitor.report_anomaly(
+                    AnomalyCode::RuleSelectionShortfall,
+                    format!("Quota for tag '{}' is {} rule(s) short of its minimum {}", quota.tag, deficit, quota.min),
+                    AnomalySeverity::Warning,
+                    AnomalyLocation::none(),
+                );
+            }
         }
 
         Ok(selected)
     }
+
+    /// The required set must hold together on its own: no conflict, nothing forbidden, no quota overflow
+    fn check_required_rules(&self, required: &[Rule], config: &GenerationConfig) -> GenerationResult<()> {
+        let quotas = &config.rule_quotas;
+        if let Some(rule) = required.iter().find(|r| config.forbidden_rules.contains(&r.id)) {
+            return Err(ArenaGenerationError::InvalidConfiguration {
+                details: format!("rule {} is implied by the required rules but forbidden", rule.id),
+            });
+        }
+
+        for (i, a) in required.iter().enumerate() {
+            for b in &required[i + 1..] {
+                if self.rules_db.relations().conflicts(&a.id, &b.id) {
+                    return Err(ArenaGenerationError::InvalidConfiguration {
+                        details: format!("required rules {} and {} are incompatible", a.id, b.id),
+                    });
+                }
+            }
+        }
+
+        for quota in quotas {
+            if let Some(max) = quota.max.filter(|&max| quota.usage(required) > max) {
+                return Err(ArenaGenerationError::InvalidConfiguration {
+                    details: format!("required rules exceed the quota of {} for tag '{}'", max, quota.tag),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Rules implied by `rule` that are not active yet
+    fn missing_requirements(&self, rule: &Rule, selected: &[Rule]) -> Vec<Rule> {
+        self.rules_db.relations().requirements(&rule.id).iter()
+            .filter(|id| !selected.iter().any(|s| &s.id == *id))
+            .filter_map(|id| self.rules_db.get_rule(id).cloned())
+            .collect()
+    }
+
+    /// Whether `rule` plus the rules it implies can join `selected` within the config's limits
+    fn requirements_fit(&self, rule: &Rule, selected: &[Rule], incompatible: &HashSet<RuleId>, config: &GenerationConfig) -> bool {
+        let implied = self.missing_requirements(rule, selected);
+        if implied.is_empty() {
+            return true;
+        }
+
+        let relations = self.rules_db.relations();
+        let remaining = config.rule_count.saturating_sub(selected.len() as u32);
+        let fits_slots = (implied.len() as u32) < remaining;
+        let allowed = implied.iter().all(|r| !config.forbidden_rules.contains(&r.id) && !incompatible.contains(&r.id));
+        let consistent = implied.iter().all(|r| !relations.conflicts(&rule.id, &r.id))
+            && implied.iter().enumerate().all(|(i, a)| implied[i + 1..].iter().all(|b| !relations.conflicts(&a.id, &b.id)));
+        let within_quotas = config.rule_quotas.iter().all(|q| {
+            let added = std::iter::once(rule).chain(&implied).filter(|r| q.applies_to(r)).count() as u32;
+            q.max.is_none_or(|max| q.usage(selected) + added <= max)
+        });
+
+        fits_slots && allowed && consistent && within_quotas
+    }
        
-    fn calculate_rule_weight(&self, rule: &Rule, current_rules: &[Rule]) -> f32 {
+    fn calculate_rule_weight(&self, rule: &Rule, current_rules: &[Rule], target_difficulty: Option<f32>) -> f32 {
         let mut weight = 1.0;
         
         // Prefer diverse rule types
@@ -147,17 +400,26 @@ impl ArenaGenerator {
             weight *= 0.5;
         }
         
-        // Difficulty balancing
+        // Difficulty balancing: mild preference for easy rules, or a pull towards the target
+        let (easy_bias, hard_bias) = match target_difficulty {
+            Some(target) => (1.0 + 0.8 * (0.5 - target), 1.0 + 0.8 * (target - 0.5)),
+            None => (1.2, 0.8),
+        };
         if rule.tags.contains(&"difficulty_easy".to_string()) {
-            weight *= 1.2;
+            weight *= easy_bias;
         } else if rule.tags.contains(&"difficulty_hard".to_string()) {
-            weight *= 0.8;
+            weight *= hard_bias;
+        }
+
+        // Synergies with the rules already picked
+        for current in current_rules {
+            weight *= self.rules_db.relations().synergy(&current.id, &rule.id);
         }
         
         weight
     }
     
-    fn weighted_select<'a, T>(&mut self, items: &'a [T], weights: &[f32]) -> Result<&'a T> {
+    fn weighted_select<'a, T>(&mut self, items: &'a [T], weights: &[f32]) -> GenerationResult<&'a T> {
         let total_weight: f32 = weights.iter().sum();
         let mut target = self.rng.r#gen::<f32>() * total_weight;
     
@@ -169,10 +431,10 @@ impl ArenaGenerator {
         }
     
         // Fallback to last item
-        items.last().ok_or_else(|| anyhow::anyhow!("No items to select from"))
+        items.last().ok_or_else(|| ArenaGenerationError::GenerationFailed { message: "no items to select from".to_string() })
     }
     
-    fn configure_environment(&mut self, arena: &mut Arena,     monitor: &mut AnomalyMonitor) -> Result<()> {
+    fn configure_environment(&mut self, arena: &mut Arena, forced_env: &HashMap<EnvVarId, f64>, monitor: &mut AnomalyMonitor) -> GenerationResult<()> {
         let env_vars = self.env_vars_db.get_all_variables();
     
         for env_var in env_vars {
@@ -200,6 +462,19 @@ impl ArenaGenerator {
                 value += (self.rng.r#gen::<f64>() - 0.5) * variance;
             }
         
+            // Config overrides win over rules and variance
+            if let Some(&forced) = forced_env.get(&env_var.id) {
+                value = forced;
+                if !(env_var.range.0..=env_var.range.1).contains(&forced) {
+                    monitor.report_anomaly(
+                        AnomalyCode::EnvVariableOutOfRange,
+                        format!("Forced {:?} = {} clamped to {:?}", env_var.id, forced, env_var.range),
+                        AnomalySeverity::Warning,
+                        AnomalyLocation::none(),
+                    );
+                }
+            }
+        
             // Clamp to valid range
             value = value.clamp(env_var.range.0, env_var.range.1);
         
@@ -210,15 +485,22 @@ impl ArenaGenerator {
         Ok(())
     }
     
-    fn generate_base_layout(&mut self, arena: &mut Arena, monitor: &mut AnomalyMonitor) -> Result<()> {
+    fn generate_base_layout(&mut self, arena: &mut Arena, reserved: HashSet<(i32, i32)>, densities: &DensityConfig, budget: TimeBudget, monitor: &mut AnomalyMonitor) -> GenerationResult<()> {
         // Simple WFC-inspired algorithm
         let mut wfc = WFCGenerator::new(arena.width, arena.height, &mut self.rng);
+        wfc.mask = arena.mask.clone();
+        wfc.reserved = reserved;
+        wfc.density = densities.layout;
+        wfc.budget = budget;
         
         // Initialize constraints based on modules database
         let modules = self.modules_db.get_all_modules();
         for module in modules {
             if let Some(weight) = module.wfc_weight {
                 wfc.add_module_constraint(module.id.clone(), weight, &module.tags);
+                if module.tags.contains(&"hazard".to_string()) {
+                    wfc.increase_module_weight(&module.id, densities.hazards);
+                }
             }
         }
         
@@ -235,10 +517,10 @@ impl ArenaGenerator {
             arena.add_module(pos.0, pos.1, module_id, params);
         }
         
-        // Ensure player spawn exists
+        // Ensure player spawn exists; the spawn takes over the floor tile it was placed on
         if !arena.modules.iter().any(|m| matches!(m.module_id, ModuleId::Player)) {
             let spawn_pos = self.find_safe_spawn_location(arena);
-            arena.add_module(spawn_pos.0, spawn_pos.1, ModuleId::Player, None);
+            arena.set_module(spawn_pos.0, spawn_pos.1, ModuleId::Player, None);
         }
         
         Ok(())
@@ -259,6 +541,10 @@ impl ArenaGenerator {
                 wfc.increase_module_weight(&ModuleId::OrbEnergy, 2.5);
                 wfc.add_constraint("min_orbs", 5);
             }
+            RuleId::CrumblingFloor => {
+                wfc.increase_module_weight(&ModuleId::FloorFragile, 3.0);
+                wfc.add_constraint("min_fragile_floors", 4);
+            }
             _ => {}
         }
     }
@@ -274,8 +560,12 @@ impl ArenaGenerator {
             }
         }
         
-        // Fallback: center of arena
-        (arena.width as i32 / 2, arena.height as i32 / 2)
+        // Fallback: center of arena, or the first playable cell if the center is cut out
+        let center = (arena.width as i32 / 2, arena.height as i32 / 2);
+        if arena.is_valid_position(center.0, center.1) {
+            return center;
+        }
+        arena.playable_positions().first().copied().unwrap_or(center)
     }
     
     fn has_adjacent_hazard(&self, arena: &Arena, x: i32, y: i32) -> bool {
@@ -297,14 +587,21 @@ impl ArenaGenerator {
         false
     }
     
-    fn apply_rule_modifications(&mut self, arena: &mut Arena, monitor: &mut AnomalyMonitor) -> Result<()> {
+    fn apply_rule_modifications(&mut self, arena: &mut Arena, densities: &DensityConfig, monitor: &mut AnomalyMonitor) -> GenerationResult<()> {
         for rule in arena.active_rules.clone() {
             match rule.id {
                 RuleId::ProjectileRain => {
-                    self.add_projectile_hazards(arena)?;
+                    self.add_projectile_hazards(arena, rule.parameters.as_ref())?;
                 }
                 RuleId::LavaFloor => {
-                    self.enhance_lava_hazards(arena)?;
+                    self.enhance_lava_hazards(arena, densities.lava_expansion_chance)?;
+                }
+                RuleId::CrumblingFloor => {
+                    let multiplier = rule.parameters.as_ref()
+                        .and_then(|p| p.get("breakDelayMultiplier"))
+                        .and_then(|v| v.as_f64())
+                        .unwrap_or(1.0);
+                    self.hasten_fragile_floors(arena, multiplier);
                 }
                 _ => {}
             }
@@ -315,7 +612,17 @@ impl ArenaGenerator {
         Ok(())
     }
     
-    fn add_projectile_hazards(&mut self, arena: &mut Arena) -> Result<()> {
+    fn add_projectile_hazards(&mut self, arena: &mut Arena, rule_params: Option<&serde_json::Value>) -> GenerationResult<()> {
+        // The rule drives the turrets it drops: intensity scales their damage, frequency their sweep
+        let rule_param = |key: &str| rule_params.and_then(|p| p.get(key)).and_then(|v| v.as_f64()).unwrap_or(1.0);
+        let (intensity, frequency) = (rule_param("intensity"), rule_param("frequency"));
+        let defaults = self.modules_db.get_module(&ModuleId::HazardLaserTurretRotate)
+            .and_then(|module| module.parameters.clone())
+            .unwrap_or_else(|| serde_json::json!({"damagePerSecond": 40, "rotationSpeed": 45, "arc": 180, "beamLength": 20}));
+        let default_param = |key: &str, fallback: f64| defaults.get(key).and_then(|v| v.as_f64()).unwrap_or(fallback);
+        let damage = default_param("damagePerSecond", 40.0) * intensity;
+        let rotation_speed = default_param("rotationSpeed", 45.0) * frequency;
+
         let count = 2 + self.rng.gen_range(0..3);
         
         for _ in 0..count {
@@ -323,15 +630,20 @@ impl ArenaGenerator {
             let y = self.rng.gen_range(0..arena.height as i32);
             
             // Only place if position is free
-            if arena.get_cell(x, y).is_none() {
-                arena.add_module(x, y, ModuleId::HazardLaserTurretRotate, None);
+            if arena.is_valid_position(x, y) && arena.get_cell(x, y).is_none() {
+                let mut params = defaults.clone();
+                params["damagePerSecond"] = serde_json::json!(damage);
+                params["rotationSpeed"] = serde_json::json!(rotation_speed);
+                // Sweeps centred on one of the four axes, varied by position
+                params["direction"] = serde_json::json!(90 * (x + y).rem_euclid(4));
+                arena.add_module(x, y, ModuleId::HazardLaserTurretRotate, Some(params));
             }
         }
         
         Ok(())
     }
     
-    fn enhance_lava_hazards(&mut self, arena: &mut Arena) -> Result<()> {
+    fn enhance_lava_hazards(&mut self, arena: &mut Arena, expansion_chance: f32) -> GenerationResult<()> {
         // Find existing lava pits and potentially expand them
         let lava_positions: Vec<_> = arena.modules.iter()
             .filter(|cell| matches!(cell.module_id, ModuleId::HazardLavaPit))
@@ -339,16 +651,14 @@ impl ArenaGenerator {
             .collect();
             
         for (x, y) in lava_positions {
-            // 50% chance to expand each lava pit
-            if self.rng.r#gen::<f32>() < 0.5 {
+            // Each lava pit may spread to one neighbour
+            if self.rng.r#gen::<f32>() < expansion_chance {
                 let directions = [(0, 1), (0, -1), (1, 0), (-1, 0)];
                 let (dx, dy) = directions[self.rng.gen_range(0..directions.len())];
                 let nx = x + dx;
                 let ny = y + dy;
                 
-                if nx >= 0 && ny >= 0 && 
-                   nx < arena.width as i32 && ny < arena.height as i32 &&
-                   arena.get_cell(nx, ny).is_none() {
+                if arena.is_valid_position(nx, ny) && arena.get_cell(nx, ny).is_none() {
                     arena.add_module(nx, ny, ModuleId::HazardLavaPit, None);
                 }
             }
@@ -357,6 +667,20 @@ impl ArenaGenerator {
         Ok(())
     }
     
+    /// Fragile floors break `multiplier` times faster than their module default
+    fn hasten_fragile_floors(&self, arena: &mut Arena, multiplier: f64) {
+        let defaults = self.modules_db.get_module(&ModuleId::FloorFragile)
+            .and_then(|module| module.parameters.clone())
+            .unwrap_or_else(|| serde_json::json!({"breakDelay": 0.5, "respawnDelay": 5.0}));
+        let break_delay = defaults.get("breakDelay").and_then(|v| v.as_f64()).unwrap_or(0.5);
+
+        for cell in arena.modules.iter_mut().filter(|cell| cell.module_id == ModuleId::FloorFragile) {
+            let mut params = cell.module_params.clone().unwrap_or_else(|| defaults.clone());
+            params["breakDelay"] = serde_json::json!(break_delay * multiplier);
+            cell.module_params = Some(params);
+        }
+    }
+
     fn find_spawnable_locations(&self, arena: &Arena) -> Vec<(i32, i32)> {
         let mut valid_positions = Vec::new();
         let walkable_surfaces: std::collections::HashSet<(i32, i32)> = arena.modules.iter()
@@ -366,8 +690,8 @@ impl ArenaGenerator {
 
         for y in 0..arena.height as i32 {
             for x in 0..arena.width as i32 {
-                // L'emplacement doit être vide
-                if arena.get_cell(x, y).is_none() {
+                // L'emplacement doit être vide et dans l'empreinte de l'arène
+                if arena.is_valid_position(x, y) && arena.get_cell(x, y).is_none() {
                     // Vérifier les voisins
                     let neighbors = [(x, y + 1), (x, y - 1), (x + 1, y), (x - 1, y)];
                     let has_walkable_neighbor = neighbors.iter().any(|pos| walkable_surfaces.contains(pos));
@@ -381,7 +705,7 @@ impl ArenaGenerator {
         valid_positions
     }
 
-    fn place_interactive_elements(&mut self, arena: &mut Arena, _monitor: &mut AnomalyMonitor) -> Result<()> {
+    fn place_interactive_elements(&mut self, arena: &mut Arena, densities: &DensityConfig, _monitor: &mut AnomalyMonitor) -> GenerationResult<()> {
         // Trouvons tous les emplacements possibles une seule fois.
         let mut spawnable_locations = self.find_spawnable_locations(arena);
         // Mélangeons pour obtenir des placements aléatoires
@@ -389,7 +713,7 @@ impl ArenaGenerator {
         spawnable_locations.shuffle(&mut self.rng);
 
         // Place energy orbs
-        let orb_count = (arena.width * arena.height / 20).max(3);
+        let orb_count = DensityConfig::scaled(arena.playable_cell_count(), densities.orbs).max(densities.min_orbs);
         for _ in 0..orb_count {
             if let Some(pos) = spawnable_locations.pop() {
                 arena.add_module(pos.0, pos.1, ModuleId::OrbEnergy, None);
@@ -400,7 +724,7 @@ impl ArenaGenerator {
         }
     
         // Place some interactive elements based on arena size
-        let interactive_count = (arena.width * arena.height / 40).max(1);
+        let interactive_count = DensityConfig::scaled(arena.playable_cell_count(), densities.interactive).max(densities.min_interactive);
         for _ in 0..interactive_count {
             if let Some(pos) = spawnable_locations.pop() {
                 let element = match self.rng.gen_range(0..3) {
@@ -424,7 +748,7 @@ impl ArenaGenerator {
             let x = self.rng.gen_range(0..arena.width as i32);
             let y = self.rng.gen_range(0..arena.height as i32);
             
-            if arena.get_cell(x, y).is_none() {
+            if arena.is_valid_position(x, y) && arena.get_cell(x, y).is_none() {
                 return Some((x, y));
             }
         }
@@ -432,29 +756,31 @@ impl ArenaGenerator {
         None
     }
     
-    fn balance_arena(&mut self, arena: &mut Arena, monitor: &mut AnomalyMonitor) -> Result<()> {
+    fn balance_arena(&mut self, arena: &mut Arena, min_walkable: f64, monitor: &mut AnomalyMonitor) -> GenerationResult<()> {
         // Ensure minimum walkable area
         let walkable_count = arena.modules.iter()
             .filter(|cell| matches!(cell.module_id, 
                 ModuleId::FloorStd | ModuleId::FloorLarge | ModuleId::RampSteep))
             .count();
             
-        let required_walkable = (arena.width * arena.height / 3) as usize; // At least 1/3 walkable
+        let required_walkable = DensityConfig::scaled(arena.playable_cell_count(), min_walkable);
         
         if walkable_count < required_walkable {
             let needed = required_walkable - walkable_count;
+            let mut added = Vec::new();
             
             for _ in 0..needed {
                 if let Some(pos) = self.find_free_position(arena) {
                     arena.add_module(pos.0, pos.1, ModuleId::FloorStd, None);
+                    added.push(pos);
                 }
             }
             
             monitor.report_anomaly(
-                "BALANCE",
+                AnomalyCode::WalkableAreaPadded,
                 format!("Added {} floor tiles to meet minimum walkable area", needed),
                 AnomalySeverity::Info,
-                None,
+                AnomalyLocation::cells(added).with_modules(vec![ModuleId::FloorStd]),
             );
         }
         
@@ -466,9 +792,16 @@ impl ArenaGenerator {
 struct WFCGenerator {
     width: u32,
     height: u32,
+    /// Cells outside the arena footprint are never filled
+    mask: Option<ArenaMask>,
+    /// Cells already claimed by templates
+    reserved: HashSet<(i32, i32)>,
+    /// Share of playable cells filled
+    density: f32,
     rng: *mut StdRng,
     module_weights: HashMap<ModuleId, f32>,
     constraints: HashMap<String, i32>,
+    budget: TimeBudget,
 }
 
 impl WFCGenerator {
@@ -476,9 +809,13 @@ impl WFCGenerator {
         Self {
             width,
             height,
+            mask: None,
+            reserved: HashSet::new(),
+            density: 0.6,
             rng,
             module_weights: HashMap::new(),
             constraints: HashMap::new(),
+            budget: TimeBudget::unlimited(),
         }
     }
     
@@ -500,15 +837,19 @@ impl WFCGenerator {
         self.constraints.insert(name.to_string(), value);
     }
     
-    fn generate(&mut self, _monitor: &mut AnomalyMonitor) -> Result<Vec<((i32, i32), ModuleId, Option<serde_json::Value>)>> {
+    fn generate(&mut self, _monitor: &mut AnomalyMonitor) -> GenerationResult<Vec<((i32, i32), ModuleId, Option<serde_json::Value>)>> {
         let mut result = Vec::new();
         let rng = unsafe { &mut *self.rng };
         
         // Simple fill algorithm - place floors in majority of cells
-        let density = 0.6; // 60% filled
+        let density = self.density;
         
         for y in 0..self.height {
+            self.budget.check("layout")?;
             for x in 0..self.width {
+                if !self.is_playable(x as i32, y as i32) {
+                    continue;
+                }
                 if rng.r#gen::<f32>() < density {
                     let module_id = self.select_weighted_module(rng);
                     result.push(((x as i32, y as i32), module_id, None));
@@ -517,7 +858,7 @@ impl WFCGenerator {
         }
         
         // Ensure minimum constraints are met
-        self.enforce_constraints(&mut result, rng);
+        self.enforce_constraints(&mut result, rng)?;
         
         Ok(result)
     }
@@ -527,6 +868,7 @@ impl WFCGenerator {
         let weights = vec![
         (ModuleId::FloorStd, *self.module_weights.get(&ModuleId::FloorStd).unwrap_or(&10.0)),
         (ModuleId::FloorLarge, *self.module_weights.get(&ModuleId::FloorLarge).unwrap_or(&5.0)),
+        (ModuleId::FloorFragile, *self.module_weights.get(&ModuleId::FloorFragile).unwrap_or(&1.0)),
         (ModuleId::RampSteep, *self.module_weights.get(&ModuleId::RampSteep).unwrap_or(&3.0)),
         (ModuleId::HazardLavaPit, *self.module_weights.get(&ModuleId::HazardLavaPit).unwrap_or(&2.0)),
         (ModuleId::HazardLaserEmitterStatic, *self.module_weights.get(&ModuleId::HazardLaserEmitterStatic).unwrap_or(&1.0)),
@@ -548,7 +890,24 @@ impl WFCGenerator {
         ModuleId::FloorStd // Fallback
     }
     
-    fn enforce_constraints(&self, result: &mut Vec<((i32, i32), ModuleId, Option<serde_json::Value>)>, rng: &mut StdRng) {
+    fn is_playable(&self, x: i32, y: i32) -> bool {
+        !self.reserved.contains(&(x, y)) && self.mask.as_ref().is_none_or(|mask| mask.contains(x, y))
+    }
+
+    fn random_playable_position(&self, rng: &mut StdRng) -> Option<(i32, i32)> {
+        for _ in 0..100 { // Max 100 attempts
+            let x = rng.gen_range(0..self.width as i32);
+            let y = rng.gen_range(0..self.height as i32);
+
+            if self.is_playable(x, y) {
+                return Some((x, y));
+            }
+        }
+
+        None
+    }
+
+    fn enforce_constraints(&self, result: &mut Vec<((i32, i32), ModuleId, Option<serde_json::Value>)>, rng: &mut StdRng) -> GenerationResult<()> {
         // Enforce minimum lava pits
         if let Some(&min_lava) = self.constraints.get("min_lava_pits") {
             let current_lava = result.iter()
@@ -558,13 +917,27 @@ impl WFCGenerator {
             if (current_lava as i32) < min_lava {
                 let needed = min_lava - current_lava as i32;
                 for _ in 0..needed {
-                    let x = rng.gen_range(0..self.width as i32);
-                    let y = rng.gen_range(0..self.height as i32);
-                    result.push(((x, y), ModuleId::HazardLavaPit, None));
+                    let pos = self.constrained_position(rng, "min_lava_pits")?;
+                    result.push((pos, ModuleId::HazardLavaPit, None));
                 }
             }
         }
         
+        // Enforce minimum fragile floors
+        if let Some(&min_fragile) = self.constraints.get("min_fragile_floors") {
+            let current_fragile = result.iter()
+                .filter(|(_, module_id, _)| matches!(module_id, ModuleId::FloorFragile))
+                .count();
+
+            if (current_fragile as i32) < min_fragile {
+                let needed = min_fragile - current_fragile as i32;
+                for _ in 0..needed {
+                    let pos = self.constrained_position(rng, "min_fragile_floors")?;
+                    result.push((pos, ModuleId::FloorFragile, None));
+                }
+            }
+        }
+
         // Enforce minimum orbs
         if let Some(&min_orbs) = self.constraints.get("min_orbs") {
             let current_orbs = result.iter()
@@ -574,11 +947,26 @@ impl WFCGenerator {
             if (current_orbs as i32) < min_orbs {
                 let needed = min_orbs - current_orbs as i32;
                 for _ in 0..needed {
-                    let x = rng.gen_range(0..self.width as i32);
-                    let y = rng.gen_range(0..self.height as i32);
-                    result.push(((x, y), ModuleId::OrbEnergy, None));
+                    let pos = self.constrained_position(rng, "min_orbs")?;
+                    result.push((pos, ModuleId::OrbEnergy, None));
                 }
             }
         }
+
+        Ok(())
+    }
+
+    /// A random playable cell, else the first one; fails `constraint` when there is none at all
+    fn constrained_position(&self, rng: &mut StdRng, constraint: &str) -> GenerationResult<(i32, i32)> {
+        self.random_playable_position(rng)
+            .or_else(|| {
+                (0..self.height as i32)
+                    .flat_map(|y| (0..self.width as i32).map(move |x| (x, y)))
+                    .find(|&(x, y)| self.is_playable(x, y))
+            })
+            .ok_or_else(|| ArenaGenerationError::ConstraintViolation {
+                constraint: constraint.to_string(),
+                details: format!("no playable cell left in the {}x{} layout", self.width, self.height),
+            })
     }
 }
@@ -0,0 +1,372 @@
+//generation/pipeline.rs
+use super::*;
+
+/// One step of arena generation. Stages run in pipeline order on the same arena.
+pub trait GenerationStage: Send + Sync {
+    /// Stable name used for reordering, timing metrics and anomaly attribution
+    fn name(&self) -> &str;
+
+    fn run(&self, ctx: &mut StageContext<'_>) -> GenerationResult<()>;
+}
+
+/// What a stage gets to work with
+pub struct StageContext<'a> {
+    pub arena: &'a mut Arena,
+    pub monitor: &'a mut AnomalyMonitor,
+    generator: &'a mut ArenaGenerator,
+    config: GenerationConfig,
+    reserved: HashSet<(i32, i32)>,
+    budget: TimeBudget,
+}
+
+impl StageContext<'_> {
+    /// Config of the arena being generated
+    pub fn config(&self) -> &GenerationConfig {
+        &self.config
+    }
+
+    /// The generator's layout stream; stages that draw from it stay reproducible per seed
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.generator.rng
+    }
+
+    pub fn rules_db(&self) -> &RulesDatabase {
+        &self.generator.rules_db
+    }
+
+    pub fn modules_db(&self) -> &ModulesDatabase {
+        &self.generator.modules_db
+    }
+
+    pub fn env_vars_db(&self) -> &EnvVarsDatabase {
+        &self.generator.env_vars_db
+    }
+
+    /// Keep cells away from the procedural layout (used by templates)
+    pub fn reserve(&mut self, cells: impl IntoIterator<Item = (i32, i32)>) {
+        self.reserved.extend(cells);
+    }
+
+    pub fn reserved(&self) -> &HashSet<(i32, i32)> {
+        &self.reserved
+    }
+
+    /// Long-running stages should check this between steps
+    pub fn time_budget(&self) -> TimeBudget {
+        self.budget
+    }
+}
+
+/// Wall-clock limit of one generation, from `GenerationConfig::time_budget_ms`
+#[derive(Debug, Clone, Copy)]
+pub struct TimeBudget {
+    start: Instant,
+    limit: Option<Duration>,
+}
+
+impl TimeBudget {
+    pub fn start(limit_ms: Option<u64>) -> Self {
+        Self {
+            start: Instant::now(),
+            limit: limit_ms.map(Duration::from_millis),
+        }
+    }
+
+    pub fn unlimited() -> Self {
+        Self::start(None)
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// `Timeout` naming `stage` once the budget is spent
+    pub fn check(&self, stage: &str) -> GenerationResult<()> {
+        match self.limit {
+            Some(limit) if self.start.elapsed() > limit => Err(ArenaGenerationError::Timeout {
+                max_time_ms: limit.as_millis() as u64,
+                stage: stage.to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Arena state right after a stage, recorded in debug mode
+#[derive(Debug, Clone)]
+pub struct StageSnapshot {
+    pub stage: String,
+    pub elapsed: Duration,
+    pub arena: Arena,
+}
+
+/// Ordered list of stages run by `ArenaGenerator`
+pub struct GenerationPipeline {
+    stages: Vec<Box<dyn GenerationStage>>,
+    debug_snapshots: bool,
+}
+
+impl Default for GenerationPipeline {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl GenerationPipeline {
+    pub fn empty() -> Self {
+        Self {
+            stages: Vec::new(),
+            debug_snapshots: false,
+        }
+    }
+
+    /// The built-in stages, in the order generation has always used
+    pub fn standard() -> Self {
+        Self::empty()
+            .with_stage(SelectRulesStage)
+            .with_stage(EnvironmentStage)
+            .with_stage(TemplatesStage)
+            .with_stage(LayoutStage)
+            .with_stage(RuleModificationsStage)
+            .with_stage(InteractiveElementsStage)
+            .with_stage(BalanceStage)
+            .with_stage(RepairStage)
+    }
+
+    pub fn with_stage(mut self, stage: impl GenerationStage + 'static) -> Self {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    /// Snapshot the arena after every stage (see `ArenaGenerator::stage_snapshots`)
+    pub fn with_debug_snapshots(mut self, enabled: bool) -> Self {
+        self.debug_snapshots = enabled;
+        self
+    }
+
+    pub fn stage_names(&self) -> Vec<&str> {
+        self.stages.iter().map(|s| s.name()).collect()
+    }
+
+    pub fn push(&mut self, stage: impl GenerationStage + 'static) {
+        self.stages.push(Box::new(stage));
+    }
+
+    pub fn insert_before(&mut self, name: &str, stage: impl GenerationStage + 'static) -> GenerationResult<()> {
+        let index = self.index_of(name)?;
+        self.stages.insert(index, Box::new(stage));
+        Ok(())
+    }
+
+    pub fn insert_after(&mut self, name: &str, stage: impl GenerationStage + 'static) -> GenerationResult<()> {
+        let index = self.index_of(name)?;
+        self.stages.insert(index + 1, Box::new(stage));
+        Ok(())
+    }
+
+    pub fn replace(&mut self, name: &str, stage: impl GenerationStage + 'static) -> GenerationResult<()> {
+        let index = self.index_of(name)?;
+        self.stages[index] = Box::new(stage);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> GenerationResult<()> {
+        let index = self.index_of(name)?;
+        self.stages.remove(index);
+        Ok(())
+    }
+
+    /// Move a stage to `position` in the run order
+    pub fn move_to(&mut self, name: &str, position: usize) -> GenerationResult<()> {
+        let index = self.index_of(name)?;
+        let stage = self.stages.remove(index);
+        self.stages.insert(position.min(self.stages.len()), stage);
+        Ok(())
+    }
+
+    fn index_of(&self, name: &str) -> GenerationResult<usize> {
+        self.stages.iter()
+            .position(|s| s.name() == name)
+            .ok_or_else(|| ArenaGenerationError::InvalidConfiguration {
+                details: format!("no generation stage named '{}' (stages: {:?})", name, self.stage_names()),
+            })
+    }
+
+    pub(crate) fn run(
+        &self,
+        generator: &mut ArenaGenerator,
+        arena: &mut Arena,
+        config: GenerationConfig,
+        budget: TimeBudget,
+        monitor: &mut AnomalyMonitor,
+    ) -> GenerationResult<()> {
+        generator.stage_snapshots.clear();
+        let progress = generator.progress.clone();
+        if let Some(progress) = &progress {
+            progress.begin_generation(self.stages.len());
+        }
+
+        let mut ctx = StageContext {
+            arena,
+            monitor,
+            generator,
+            config,
+            reserved: HashSet::new(),
+            budget,
+        };
+
+        for (index, stage) in self.stages.iter().enumerate() {
+            if let Some(progress) = &progress {
+                progress.check_cancelled(stage.name())?;
+            }
+            let start = Instant::now();
+            ctx.monitor.begin_stage(stage.name());
+            let result = stage.run(&mut ctx);
+            let elapsed = start.elapsed();
+            ctx.monitor.end_stage(elapsed);
+
+            if self.debug_snapshots {
+                let snapshot = StageSnapshot {
+                    stage: stage.name().to_string(),
+                    elapsed,
+                    arena: ctx.arena.clone(),
+                };
+                ctx.generator.stage_snapshots.push(snapshot);
+            }
+
+            result.map_err(|e| match e {
+                ArenaGenerationError::GenerationFailed { message } => ArenaGenerationError::GenerationFailed {
+                    message: format!("stage '{}': {}", stage.name(), message),
+                },
+                other => other,
+            })?;
+            // Once the last stage is done there is nothing left to save by stopping
+            if index + 1 < self.stages.len() {
+                budget.check(stage.name())?;
+            }
+            if let Some(progress) = &progress {
+                progress.complete_stage(stage.name());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Picks the active rules: forced ones, then a weighted random pick up to the rule count
+pub struct SelectRulesStage;
+
+impl GenerationStage for SelectRulesStage {
+    fn name(&self) -> &str {
+        "select_rules"
+    }
+
+    fn run(&self, ctx: &mut StageContext<'_>) -> GenerationResult<()> {
+        ctx.arena.active_rules = ctx.generator.select_rules(&ctx.config, ctx.monitor)?;
+        Ok(())
+    }
+}
+
+pub struct EnvironmentStage;
+
+impl GenerationStage for EnvironmentStage {
+    fn name(&self) -> &str {
+        "environment"
+    }
+
+    fn run(&self, ctx: &mut StageContext<'_>) -> GenerationResult<()> {
+        ctx.generator.configure_environment(ctx.arena, &ctx.config.forced_env, ctx.monitor)
+    }
+}
+
+/// Stamps the generator's templates and reserves their cells for the layout stage
+pub struct TemplatesStage;
+
+impl GenerationStage for TemplatesStage {
+    fn name(&self) -> &str {
+        "templates"
+    }
+
+    fn run(&self, ctx: &mut StageContext<'_>) -> GenerationResult<()> {
+        let claimed = ctx.generator.stamp_templates(ctx.arena, ctx.monitor);
+        ctx.reserve(claimed);
+        Ok(())
+    }
+}
+
+/// WFC fill of every unreserved cell, plus the player spawn
+pub struct LayoutStage;
+
+impl GenerationStage for LayoutStage {
+    fn name(&self) -> &str {
+        "layout"
+    }
+
+    fn run(&self, ctx: &mut StageContext<'_>) -> GenerationResult<()> {
+        let reserved = ctx.reserved.clone();
+        ctx.generator.generate_base_layout(ctx.arena, reserved, &ctx.config.densities, ctx.budget, ctx.monitor)
+    }
+}
+
+pub struct RuleModificationsStage;
+
+impl GenerationStage for RuleModificationsStage {
+    fn name(&self) -> &str {
+        "rule_modifications"
+    }
+
+    fn run(&self, ctx: &mut StageContext<'_>) -> GenerationResult<()> {
+        ctx.generator.apply_rule_modifications(ctx.arena, &ctx.config.densities, ctx.monitor)
+    }
+}
+
+pub struct InteractiveElementsStage;
+
+impl GenerationStage for InteractiveElementsStage {
+    fn name(&self) -> &str {
+        "interactive_elements"
+    }
+
+    fn run(&self, ctx: &mut StageContext<'_>) -> GenerationResult<()> {
+        ctx.generator.place_interactive_elements(ctx.arena, &ctx.config.densities, ctx.monitor)
+    }
+}
+
+pub struct BalanceStage;
+
+impl GenerationStage for BalanceStage {
+    fn name(&self) -> &str {
+        "balance"
+    }
+
+    fn run(&self, ctx: &mut StageContext<'_>) -> GenerationResult<()> {
+        ctx.generator.balance_arena(ctx.arena, ctx.config.densities.min_walkable, ctx.monitor)
+    }
+}
+
+/// Repairs what validation flags; the final validation runs after the pipeline
+pub struct RepairStage;
+
+impl GenerationStage for RepairStage {
+    fn name(&self) -> &str {
+        "repair"
+    }
+
+    fn run(&self, ctx: &mut StageContext<'_>) -> GenerationResult<()> {
+        let (_, report) = ctx.generator.validate_and_repair(ctx.arena, ctx.config.max_repair_attempts);
+        for record in &report.repairs {
+            ctx.monitor.report_anomaly(
+                AnomalyCode::ArenaRepaired,
+                format!("[{}] {}", record.trigger, record.action.describe()),
+                AnomalySeverity::Info,
+                AnomalyLocation::cells(record.action.cells()),
+            );
+        }
+        if report.attempts > 0 {
+            ctx.arena.generation_metadata.constraints_applied.push(format!("repair:{}", report.repairs.len()));
+        }
+        ctx.monitor.record_metric("repair_attempts", report.attempts as f64);
+        ctx.generator.last_repair_report = Some(report);
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+//generation/progress.rs
+use super::*;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Progress of a generation running elsewhere (another thread, a background task),
+/// shared through an `Arc`. The pipeline fills it in as stages complete and stops
+/// before the next stage once `cancel` has been called.
+#[derive(Debug, Default)]
+pub struct GenerationProgress {
+    attempt: AtomicU32,
+    stages_done: AtomicU32,
+    stages_total: AtomicU32,
+    stage: Mutex<String>,
+    cancelled: AtomicBool,
+}
+
+/// Copy of `GenerationProgress` at one instant
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressSnapshot {
+    /// 1-based retry attempt, 0 outside `generate_until_valid`
+    pub attempt: u32,
+    pub stages_done: u32,
+    pub stages_total: u32,
+    /// Last completed stage, empty before the first one
+    pub stage: String,
+    pub cancelled: bool,
+}
+
+impl ProgressSnapshot {
+    /// Share of the current generation's stages completed, 0.0..=1.0
+    pub fn fraction(&self) -> f32 {
+        if self.stages_total == 0 {
+            return 0.0;
+        }
+        (self.stages_done as f32 / self.stages_total as f32).min(1.0)
+    }
+}
+
+impl GenerationProgress {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Ask the generation to stop; it fails with `Cancelled` before its next stage
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// `Cancelled` naming the stage about to run once `cancel` has been called
+    pub fn check_cancelled(&self, stage: &str) -> GenerationResult<()> {
+        if self.is_cancelled() {
+            return Err(ArenaGenerationError::Cancelled { stage: stage.to_string() });
+        }
+        Ok(())
+    }
+
+    pub fn snapshot(&self) -> ProgressSnapshot {
+        ProgressSnapshot {
+            attempt: self.attempt.load(Ordering::Relaxed),
+            stages_done: self.stages_done.load(Ordering::Relaxed),
+            stages_total: self.stages_total.load(Ordering::Relaxed),
+            stage: self.stage.lock().map(|s| s.clone()).unwrap_or_default(),
+            cancelled: self.is_cancelled(),
+        }
+    }
+
+    pub fn fraction(&self) -> f32 {
+        self.snapshot().fraction()
+    }
+
+    pub(crate) fn begin_attempt(&self, attempt: u32) {
+        self.attempt.store(attempt, Ordering::Relaxed);
+    }
+
+    pub(crate) fn begin_generation(&self, stages_total: usize) {
+        self.stages_done.store(0, Ordering::Relaxed);
+        self.stages_total.store(stages_total as u32, Ordering::Relaxed);
+        if let Ok(mut stage) = self.stage.lock() {
+            stage.clear();
+        }
+    }
+
+    pub(crate) fn complete_stage(&self, name: &str) {
+        if let Ok(mut stage) = self.stage.lock() {
+            *stage = name.to_string();
+        }
+        self.stages_done.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl ArenaGenerator {
+    /// Report stage completion to `progress` and stop when it is cancelled
+    pub fn with_progress(mut self, progress: Arc<GenerationProgress>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    pub fn progress(&self) -> Option<&Arc<GenerationProgress>> {
+        self.progress.as_ref()
+    }
+}
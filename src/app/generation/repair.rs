@@ -0,0 +1,688 @@
+//generation/repair.rs
+use super::*;
+use rand::seq::SliceRandom;
+use std::collections::VecDeque;
+
+/// Longest bridge (in converted cells) before an orb is relocated instead
+const MAX_BRIDGE_LENGTH: usize = 4;
+
+/// Hazards of a given type are never thinned below this count
+const MIN_HAZARDS_KEPT: usize = 2;
+
+/// Minimum share of the arena that must be walkable after repair
+const REPAIR_WALKABLE_RATIO: f64 = 0.34;
+
+#[derive(Debug, Clone)]
+pub enum RepairAction {
+    BridgedRegion { target: (i32, i32), path: Vec<(i32, i32)> },
+    RelocatedElement { module_id: ModuleId, from: (i32, i32), to: (i32, i32) },
+    ThinnedHazards { removed: Vec<(i32, i32)> },
+    RemovedDuplicateSpawn { at: (i32, i32) },
+    ResolvedOverlap { at: (i32, i32), kept: ModuleId, removed: Vec<ModuleId> },
+    ExtendedWalkableArea { added: Vec<(i32, i32)> },
+    PlacedMissingModule { module_id: ModuleId, at: (i32, i32) },
+}
+
+impl RepairAction {
+    pub fn describe(&self) -> String {
+        match self {
+            RepairAction::BridgedRegion { target, path } => {
+                format!("Bridged {} cells to connect ({}, {}) to the spawn region", path.len(), target.0, target.1)
+            }
+            RepairAction::RelocatedElement { module_id, from, to } => {
+                format!("Relocated {:?} from ({}, {}) to ({}, {})", module_id, from.0, from.1, to.0, to.1)
+            }
+            RepairAction::ThinnedHazards { removed } => {
+                format!("Replaced {} clustered hazards with floor", removed.len())
+            }
+            RepairAction::RemovedDuplicateSpawn { at } => {
+                format!("Removed duplicate player spawn at ({}, {})", at.0, at.1)
+            }
+            RepairAction::ResolvedOverlap { at, kept, removed } => {
+                format!("Kept {:?} at ({}, {}), removed {:?}", kept, at.0, at.1, removed)
+            }
+            RepairAction::ExtendedWalkableArea { added } => {
+                format!("Added {} connected floor tiles", added.len())
+            }
+            RepairAction::PlacedMissingModule { module_id, at } => {
+                format!("Placed missing {:?} at ({}, {})", module_id, at.0, at.1)
+            }
+        }
+    }
+
+    pub fn cells(&self) -> Vec<(i32, i32)> {
+        match self {
+            RepairAction::BridgedRegion { path, .. } => path.clone(),
+            RepairAction::RelocatedElement { from, to, .. } => vec![*from, *to],
+            RepairAction::ThinnedHazards { removed } => removed.clone(),
+            RepairAction::RemovedDuplicateSpawn { at } => vec![*at],
+            RepairAction::ResolvedOverlap { at, .. } => vec![*at],
+            RepairAction::ExtendedWalkableArea { added } => added.clone(),
+            RepairAction::PlacedMissingModule { at, .. } => vec![*at],
+        }
+    }
+}
+
+/// A single fix, with the anomaly that triggered it
+#[derive(Debug, Clone)]
+pub struct RepairRecord {
+    pub trigger: AnomalyCode,
+    pub action: RepairAction,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RepairReport {
+    pub attempts: u32,
+    pub repairs: Vec<RepairRecord>,
+    pub resolved: bool,
+    pub remaining_critical: Vec<AnomalyCode>,
+}
+
+impl RepairReport {
+    pub fn is_empty(&self) -> bool {
+        self.repairs.is_empty()
+    }
+}
+
+impl ArenaGenerator {
+    /// Validate the arena and repair it until no repairable anomaly is left or
+    /// the attempt budget is spent. Returns the monitor of the last validation.
+    pub(crate) fn validate_and_repair(&mut self, arena: &mut Arena, max_attempts: u32) -> (AnomalyMonitor, RepairReport) {
+        let mut report = RepairReport::default();
+        let mut diagnostics = AnomalyMonitor::new();
+        diagnostics.validate_arena_with(arena, self.rules_db.relations());
+
+        while needs_repair(&diagnostics) && report.attempts < max_attempts {
+            report.attempts += 1;
+
+            let repairs = self.repair_pass(arena, diagnostics.get_anomalies());
+            if repairs.is_empty() {
+                break; // Nothing we know how to fix
+            }
+            report.repairs.extend(repairs);
+
+            diagnostics = AnomalyMonitor::new();
+            diagnostics.validate_arena_with(arena, self.rules_db.relations());
+        }
+
+        report.resolved = !diagnostics.has_critical_anomalies();
+        report.remaining_critical = diagnostics.get_anomalies().iter()
+            .filter(|a| a.severity == AnomalySeverity::Critical)
+            .map(|a| a.code)
+            .collect();
+
+        (diagnostics, report)
+    }
+
+    fn repair_pass(&mut self, arena: &mut Arena, anomalies: &[Anomaly]) -> Vec<RepairRecord> {
+        let mut repairs = Vec::new();
+        let has = |code: AnomalyCode| anomalies.iter().any(|a| a.code == code);
+        let cells_of = |code: AnomalyCode| -> Vec<(i32, i32)> {
+            anomalies.iter()
+                .filter(|a| a.code == code)
+                .flat_map(|a| a.location.cells.iter().copied())
+                .collect()
+        };
+
+        // Overlaps first: every other fix assumes one module per cell
+        for pos in cells_of(AnomalyCode::OverlappingModules) {
+            if let Some(action) = resolve_overlap(arena, pos) {
+                repairs.push(RepairRecord { trigger: AnomalyCode::OverlappingModules, action });
+            }
+        }
+
+        if has(AnomalyCode::DuplicatePlayerSpawn) {
+            for at in remove_duplicate_spawns(arena) {
+                repairs.push(RepairRecord {
+                    trigger: AnomalyCode::DuplicatePlayerSpawn,
+                    action: RepairAction::RemovedDuplicateSpawn { at },
+                });
+            }
+        }
+
+        if arena.get_player_position().is_none() {
+            let trigger = if has(AnomalyCode::MissingPlayerSpawn) {
+                AnomalyCode::MissingPlayerSpawn
+            } else {
+                AnomalyCode::MissingPlayerModule
+            };
+            let at = self.find_safe_spawn_location(arena);
+            arena.set_module(at.0, at.1, ModuleId::Player, None);
+            repairs.push(RepairRecord {
+                trigger,
+                action: RepairAction::PlacedMissingModule { module_id: ModuleId::Player, at },
+            });
+        }
+
+        let mut clustered = cells_of(AnomalyCode::HazardClustering);
+        clustered.extend(cells_of(AnomalyCode::ExcessiveHazardDensity));
+        if !clustered.is_empty() {
+            let removed = thin_hazards(arena, &clustered);
+            if !removed.is_empty() {
+                let trigger = if has(AnomalyCode::HazardClustering) {
+                    AnomalyCode::HazardClustering
+                } else {
+                    AnomalyCode::ExcessiveHazardDensity
+                };
+                repairs.push(RepairRecord { trigger, action: RepairAction::ThinnedHazards { removed } });
+            }
+        }
+
+        if has(AnomalyCode::LowWalkableRatio) || has(AnomalyCode::InsufficientWalkableSurfaces) {
+            let added = self.extend_walkable_area(arena);
+            if !added.is_empty() {
+                let trigger = if has(AnomalyCode::LowWalkableRatio) {
+                    AnomalyCode::LowWalkableRatio
+                } else {
+                    AnomalyCode::InsufficientWalkableSurfaces
+                };
+                repairs.push(RepairRecord { trigger, action: RepairAction::ExtendedWalkableArea { added } });
+            }
+        }
+
+        for pos in cells_of(AnomalyCode::UnreachableOrb) {
+            if let Some(action) = self.reconnect_element(arena, pos) {
+                repairs.push(RepairRecord { trigger: AnomalyCode::UnreachableOrb, action });
+            }
+        }
+
+        for pos in cells_of(AnomalyCode::IsolatedElement) {
+            if let Some(action) = self.relocate_element(arena, pos) {
+                repairs.push(RepairRecord { trigger: AnomalyCode::IsolatedElement, action });
+            }
+        }
+
+        if arena.count_modules_by_type(&ModuleId::OrbEnergy) == 0 {
+            for _ in 0..3 {
+                match self.find_reachable_free_position(arena) {
+                    Some(at) => {
+                        arena.add_module(at.0, at.1, ModuleId::OrbEnergy, None);
+                        repairs.push(RepairRecord {
+                            trigger: AnomalyCode::MissingEnergyOrbs,
+                            action: RepairAction::PlacedMissingModule { module_id: ModuleId::OrbEnergy, at },
+                        });
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        repairs
+    }
+
+    /// Try to connect an element to the spawn region with a short floor bridge,
+    /// falling back to moving it next to reachable ground.
+    fn reconnect_element(&mut self, arena: &mut Arena, pos: (i32, i32)) -> Option<RepairAction> {
+        let start = arena.get_player_position()?;
+        let reachable = arena.get_reachable_positions(start);
+        if reachable.contains(&pos) || arena.get_cell(pos.0, pos.1).is_none() {
+            return None; // Already fixed by an earlier repair
+        }
+
+        if let Some(path) = find_bridge(arena, pos, &reachable) {
+            if path.len() <= MAX_BRIDGE_LENGTH {
+                for &(x, y) in &path {
+                    arena.set_module(x, y, ModuleId::FloorStd, None);
+                }
+                return Some(RepairAction::BridgedRegion { target: pos, path });
+            }
+        }
+
+        self.relocate_element(arena, pos)
+    }
+
+    fn relocate_element(&mut self, arena: &mut Arena, from: (i32, i32)) -> Option<RepairAction> {
+        let cell = arena.get_cell(from.0, from.1)?.clone();
+        if matches!(cell.module_id, ModuleId::Player) {
+            return None;
+        }
+
+        arena.remove_modules_at(from.0, from.1);
+        match self.find_reachable_free_position(arena) {
+            Some(to) => {
+                arena.add_module(to.0, to.1, cell.module_id.clone(), cell.module_params);
+                Some(RepairAction::RelocatedElement { module_id: cell.module_id, from, to })
+            }
+            None => {
+                // Nowhere to go, put it back
+                arena.modules.push(cell);
+                None
+            }
+        }
+    }
+
+    /// Free cell next to walkable ground that the player can reach
+    fn find_reachable_free_position(&mut self, arena: &Arena) -> Option<(i32, i32)> {
+        let start = arena.get_player_position()?;
+        let reachable = arena.get_reachable_positions(start);
+
+        let mut candidates: Vec<(i32, i32)> = reachable.iter()
+            .filter(|&&(x, y)| arena.get_cell(x, y).is_some_and(|c| is_walkable(&c.module_id)))
+            .flat_map(|&(x, y)| arena.get_adjacent_positions(x, y))
+            .filter(|&(x, y)| arena.get_cell(x, y).is_none())
+            .collect();
+
+        candidates.sort();
+        candidates.dedup();
+        candidates.choose(&mut self.rng).copied()
+    }
+
+    /// Grow the walkable area outward from the spawn region so new tiles stay connected
+    fn extend_walkable_area(&mut self, arena: &mut Arena) -> Vec<(i32, i32)> {
+        let total_cells = arena.playable_cell_count() as f64;
+        let required = (total_cells * REPAIR_WALKABLE_RATIO).ceil() as usize;
+        let mut walkable = arena.modules.iter()
+            .filter(|cell| matches!(cell.module_id,
+                ModuleId::FloorStd | ModuleId::FloorLarge | ModuleId::RampSteep))
+            .count();
+
+        let mut added = Vec::new();
+        while walkable < required {
+            let Some(start) = arena.get_player_position() else { break };
+            let reachable = arena.get_reachable_positions(start);
+
+            let mut frontier: Vec<(i32, i32)> = reachable.iter()
+                .flat_map(|&(x, y)| arena.get_adjacent_positions(x, y))
+                .filter(|pos| !reachable.contains(pos))
+                .filter(|&(x, y)| arena.get_cell(x, y).is_none_or(|c| is_convertible(&c.module_id)))
+                .collect();
+            frontier.sort();
+            frontier.dedup();
+
+            // Prefer empty cells over replacing walls and hazards
+            let empty: Vec<_> = frontier.iter()
+                .copied()
+                .filter(|&(x, y)| arena.get_cell(x, y).is_none())
+                .collect();
+            let pool = if empty.is_empty() { &frontier } else { &empty };
+
+            let Some(&(x, y)) = pool.choose(&mut self.rng) else { break };
+            arena.set_module(x, y, ModuleId::FloorStd, None);
+            added.push((x, y));
+            walkable += 1;
+        }
+
+        added
+    }
+}
+
+fn needs_repair(diagnostics: &AnomalyMonitor) -> bool {
+    diagnostics.get_anomalies().iter().any(|a| {
+        a.severity == AnomalySeverity::Critical || matches!(a.code,
+            AnomalyCode::OverlappingModules |
+            AnomalyCode::DuplicatePlayerSpawn |
+            AnomalyCode::HazardClustering |
+            AnomalyCode::ExcessiveHazardDensity)
+    })
+}
+
+fn is_walkable(module_id: &ModuleId) -> bool {
+    matches!(module_id, ModuleId::FloorStd | ModuleId::FloorLarge | ModuleId::RampSteep)
+}
+
+/// Cells the player's flood fill walks through
+fn is_traversable(module_id: &ModuleId) -> bool {
+    matches!(module_id,
+        ModuleId::FloorStd | ModuleId::FloorLarge | ModuleId::FloorFragile |
+        ModuleId::RampSteep | ModuleId::RampLow |
+        ModuleId::OrbEnergy |
+        ModuleId::InteractButtonFloor)
+}
+
+/// Cells a repair may overwrite with floor
+fn is_convertible(module_id: &ModuleId) -> bool {
+    matches!(module_id,
+        ModuleId::WallLow | ModuleId::WallHigh | ModuleId::PanelGlass |
+        ModuleId::HazardLavaPit | ModuleId::HazardLaserEmitterStatic | ModuleId::HazardLaserTurretRotate |
+        ModuleId::MoveClimbSurface | ModuleId::DecorArchMetallic)
+}
+
+fn is_hazard(module_id: &ModuleId) -> bool {
+    matches!(module_id,
+        ModuleId::HazardLavaPit | ModuleId::HazardLaserEmitterStatic | ModuleId::HazardLaserTurretRotate)
+}
+
+/// Which module survives when several share a cell
+fn module_priority(module_id: &ModuleId) -> u8 {
+    match module_id {
+        ModuleId::Player => 5,
+        ModuleId::OrbEnergy => 4,
+        ModuleId::MoveTeleporterIn | ModuleId::MoveTeleporterOut |
+        ModuleId::InteractButtonFloor | ModuleId::InteractButtonWall |
+        ModuleId::InteractLever | ModuleId::InteractEnemySpawner |
+        ModuleId::InteractBarrierEnergy => 3,
+        ModuleId::HazardLavaPit | ModuleId::HazardLaserEmitterStatic |
+        ModuleId::HazardLaserTurretRotate => 2,
+        ModuleId::FloorStd | ModuleId::FloorLarge | ModuleId::FloorFragile |
+        ModuleId::RampLow | ModuleId::RampSteep => 1,
+        _ => 0,
+    }
+}
+
+fn resolve_overlap(arena: &mut Arena, (x, y): (i32, i32)) -> Option<RepairAction> {
+    let mut stacked = arena.remove_modules_at(x, y);
+    if stacked.len() < 2 {
+        arena.modules.extend(stacked);
+        return None;
+    }
+
+    // Stable: among equal priorities the first placed module wins
+    let best = stacked.iter()
+        .enumerate()
+        .max_by_key(|(i, cell)| (module_priority(&cell.module_id), std::cmp::Reverse(*i)))
+        .map(|(i, _)| i)?;
+
+    let kept = stacked.swap_remove(best);
+    let removed = stacked.into_iter().map(|cell| cell.module_id).collect();
+    let kept_id = kept.module_id.clone();
+    arena.modules.push(kept);
+
+    Some(RepairAction::ResolvedOverlap { at: (x, y), kept: kept_id, removed })
+}
+
+/// Keep the first spawn, turn the others into floor
+fn remove_duplicate_spawns(arena: &mut Arena) -> Vec<(i32, i32)> {
+    let spawns: Vec<(i32, i32)> = arena.modules.iter()
+        .filter(|cell| matches!(cell.module_id, ModuleId::Player))
+        .map(|cell| (cell.x, cell.y))
+        .collect();
+
+    let duplicates: Vec<(i32, i32)> = spawns.into_iter().skip(1).collect();
+    for &(x, y) in &duplicates {
+        arena.set_module(x, y, ModuleId::FloorStd, None);
+    }
+
+    duplicates
+}
+
+/// Replace the most crowded hazards with floor, never dropping a hazard type below
+/// `MIN_HAZARDS_KEPT`. Removes at most a third of the flagged hazards per pass.
+fn thin_hazards(arena: &mut Arena, flagged: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    let mut hazards: Vec<(i32, i32)> = flagged.iter()
+        .copied()
+        .filter(|&(x, y)| arena.get_cell(x, y).is_some_and(|c| is_hazard(&c.module_id)))
+        .collect();
+    hazards.sort();
+    hazards.dedup();
+
+    let crowding = |pos: (i32, i32), others: &[(i32, i32)]| {
+        others.iter()
+            .filter(|&&other| other != pos && (other.0 - pos.0).abs() + (other.1 - pos.1).abs() <= 1)
+            .count()
+    };
+
+    let budget = (hazards.len() / 3).max(1);
+    let mut removed = Vec::new();
+
+    while removed.len() < budget {
+        let Some((index, neighbours)) = hazards.iter()
+            .enumerate()
+            .map(|(i, &pos)| (i, crowding(pos, &hazards)))
+            .max_by_key(|&(i, n)| (n, std::cmp::Reverse(i)))
+        else { break };
+
+        if neighbours == 0 {
+            break; // No cluster left
+        }
+
+        let (x, y) = hazards[index];
+        let module_id = arena.get_cell(x, y).map(|c| c.module_id.clone());
+        let Some(module_id) = module_id else { break };
+        if arena.count_modules_by_type(&module_id) <= MIN_HAZARDS_KEPT {
+            hazards.remove(index);
+            continue;
+        }
+
+        arena.set_module(x, y, ModuleId::FloorStd, None);
+        hazards.remove(index);
+        removed.push((x, y));
+    }
+
+    removed
+}
+
+/// Shortest path from `target` to the reachable region. Returns the cells that
+/// must become floor, or `None` when the target is walled in by modules we
+/// cannot overwrite.
+fn find_bridge(arena: &Arena, target: (i32, i32), reachable: &HashSet<(i32, i32)>) -> Option<Vec<(i32, i32)>> {
+    let mut previous: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+    let mut queue = VecDeque::new();
+    queue.push_back(target);
+    previous.insert(target, target);
+
+    while let Some(pos) = queue.pop_front() {
+        if reachable.contains(&pos) {
+            // Walk back to the target, collecting cells that need converting
+            let mut path = Vec::new();
+            let mut current = previous[&pos];
+            while current != target {
+                let needs_floor = arena.get_cell(current.0, current.1)
+                    .is_none_or(|c| !is_traversable(&c.module_id));
+                if needs_floor {
+                    path.push(current);
+                }
+                current = previous[&current];
+            }
+            return Some(path);
+        }
+
+        for next in arena.get_adjacent_positions(pos.0, pos.1) {
+            if previous.contains_key(&next) {
+                continue;
+            }
+            let passable = reachable.contains(&next) || match arena.get_cell(next.0, next.1) {
+                None => true,
+                Some(cell) => is_traversable(&cell.module_id) || is_convertible(&cell.module_id),
+            };
+            if passable {
+                previous.insert(next, pos);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `width`x`height` floor with gravity set; `cells` override single positions (`None` empties them)
+    fn arena(width: u32, height: u32, cells: &[((i32, i32), Option<ModuleId>)]) -> Arena {
+        let mut arena = Arena::new(width, height, 1);
+        arena.env_variables.insert(EnvVarId::Gravity, 1.0);
+        for pos in arena.playable_positions() {
+            arena.add_module(pos.0, pos.1, ModuleId::FloorStd, None);
+        }
+        for (pos, module_id) in cells {
+            arena.remove_modules_at(pos.0, pos.1);
+            if let Some(module_id) = module_id {
+                arena.add_module(pos.0, pos.1, module_id.clone(), None);
+            }
+        }
+        arena
+    }
+
+    fn modules_at(arena: &Arena, x: i32, y: i32) -> Vec<ModuleId> {
+        arena.modules.iter()
+            .filter(|cell| cell.x == x && cell.y == y)
+            .map(|cell| cell.module_id.clone())
+            .collect()
+    }
+
+    fn critical_codes(arena: &Arena) -> Vec<AnomalyCode> {
+        let mut monitor = AnomalyMonitor::new();
+        monitor.validate_arena(arena);
+        monitor.get_anomalies().iter()
+            .filter(|a| a.severity == AnomalySeverity::Critical)
+            .map(|a| a.code)
+            .collect()
+    }
+
+    #[test]
+    fn overlaps_keep_the_highest_priority_module() {
+        let mut arena = arena(3, 1, &[((0, 0), Some(ModuleId::Player))]);
+        arena.add_module(1, 0, ModuleId::WallLow, None);
+        arena.add_module(1, 0, ModuleId::OrbEnergy, None);
+
+        let action = resolve_overlap(&mut arena, (1, 0)).unwrap();
+        assert!(matches!(&action, RepairAction::ResolvedOverlap { at: (1, 0), kept: ModuleId::OrbEnergy, removed }
+            if removed.len() == 2 && removed.contains(&ModuleId::FloorStd) && removed.contains(&ModuleId::WallLow)),
+            "{:?}", action);
+        assert_eq!(modules_at(&arena, 1, 0), vec![ModuleId::OrbEnergy]);
+
+        // Equal priorities: the first placed module wins
+        arena.add_module(2, 0, ModuleId::FloorLarge, None);
+        let action = resolve_overlap(&mut arena, (2, 0)).unwrap();
+        assert!(matches!(action, RepairAction::ResolvedOverlap { kept: ModuleId::FloorStd, .. }), "{:?}", action);
+
+        // A single module is left alone
+        assert!(resolve_overlap(&mut arena, (2, 0)).is_none());
+        assert_eq!(modules_at(&arena, 2, 0), vec![ModuleId::FloorStd]);
+    }
+
+    #[test]
+    fn clustered_hazards_are_thinned_down_to_the_minimum() {
+        let lava = |x| ((x, 1), Some(ModuleId::HazardLavaPit));
+        let mut arena = arena(7, 3, &[lava(0), lava(1), lava(2), lava(3), lava(4), lava(5)]);
+        let flagged: Vec<(i32, i32)> = (0..6).map(|x| (x, 1)).collect();
+
+        // A third of the flagged hazards per pass, most crowded first
+        let removed = thin_hazards(&mut arena, &flagged);
+        assert_eq!(removed.len(), 2);
+        assert!(removed.iter().all(|&(x, _)| x > 0 && x < 5), "{:?}", removed);
+        assert!(removed.iter().all(|&(x, y)| modules_at(&arena, x, y) == vec![ModuleId::FloorStd]));
+        assert_eq!(arena.count_modules_by_type(&ModuleId::HazardLavaPit), 4);
+
+        // Never below two of a type, even while they still touch
+        let mut pair = self::arena(4, 1, &[((0, 0), Some(ModuleId::HazardLavaPit)), ((1, 0), Some(ModuleId::HazardLavaPit))]);
+        assert!(thin_hazards(&mut pair, &[(0, 0), (1, 0)]).is_empty());
+        assert_eq!(pair.count_modules_by_type(&ModuleId::HazardLavaPit), 2);
+
+        // Flagged cells that aren't hazards are ignored
+        assert!(thin_hazards(&mut pair, &[(2, 0), (3, 0)]).is_empty());
+    }
+
+    #[test]
+    fn unreachable_orbs_are_bridged_to_the_spawn_region() {
+        let mut arena = arena(5, 1, &[
+            ((0, 0), Some(ModuleId::Player)),
+            ((2, 0), Some(ModuleId::WallLow)),
+            ((3, 0), None),
+            ((4, 0), Some(ModuleId::OrbEnergy)),
+        ]);
+        assert!(critical_codes(&arena).contains(&AnomalyCode::UnreachableOrb));
+
+        let mut generator = ArenaGenerator::new(Some(1));
+        let action = generator.reconnect_element(&mut arena, (4, 0)).unwrap();
+        assert!(matches!(&action, RepairAction::BridgedRegion { target: (4, 0), path }
+            if path.len() == 2 && path.contains(&(2, 0)) && path.contains(&(3, 0))), "{:?}", action);
+        assert!(arena.get_reachable_positions((0, 0)).contains(&(4, 0)));
+        assert!(!critical_codes(&arena).contains(&AnomalyCode::UnreachableOrb));
+
+        // Reachable orbs need nothing
+        assert!(generator.reconnect_element(&mut arena, (4, 0)).is_none());
+    }
+
+    #[test]
+    fn orbs_too_far_to_bridge_are_relocated() {
+        let wall = |x| ((x, 0), Some(ModuleId::WallHigh));
+        let mut arena = arena(10, 1, &[
+            ((0, 0), Some(ModuleId::Player)),
+            ((2, 0), None),
+            wall(3), wall(4), wall(5), wall(6), wall(7), wall(8),
+            ((9, 0), Some(ModuleId::OrbEnergy)),
+        ]);
+
+        // Seven cells to convert is past `MAX_BRIDGE_LENGTH`: the orb moves next to reachable floor instead
+        let mut generator = ArenaGenerator::new(Some(1));
+        let action = generator.reconnect_element(&mut arena, (9, 0)).unwrap();
+        assert!(matches!(action, RepairAction::RelocatedElement { module_id: ModuleId::OrbEnergy, from: (9, 0), to: (2, 0) }),
+            "{:?}", action);
+        assert!(arena.get_cell(9, 0).is_none());
+        assert_eq!(modules_at(&arena, 2, 0), vec![ModuleId::OrbEnergy]);
+
+        // With no free cell left it stays where it is
+        arena.set_module(9, 0, ModuleId::InteractButtonFloor, None);
+        assert!(generator.relocate_element(&mut arena, (9, 0)).is_none());
+        assert_eq!(modules_at(&arena, 9, 0), vec![ModuleId::InteractButtonFloor]);
+
+        // The spawn is never moved
+        assert!(generator.relocate_element(&mut arena, (0, 0)).is_none());
+    }
+
+    #[test]
+    fn broken_arenas_are_repaired_until_valid() {
+        let mut arena = arena(6, 6, &[
+            ((0, 0), Some(ModuleId::Player)),
+            ((5, 5), Some(ModuleId::Player)),
+            ((2, 0), None),
+            ((0, 2), None),
+        ]);
+        arena.add_module(3, 3, ModuleId::WallLow, None);
+
+        let mut generator = ArenaGenerator::new(Some(1));
+        let (diagnostics, report) = generator.validate_and_repair(&mut arena, 3);
+        assert!(report.resolved, "{:?}", diagnostics.get_anomalies());
+        assert!(report.remaining_critical.is_empty());
+        assert!(!diagnostics.has_critical_anomalies());
+
+        let triggers: Vec<AnomalyCode> = report.repairs.iter().map(|r| r.trigger).collect();
+        assert!(triggers.contains(&AnomalyCode::OverlappingModules), "{:?}", triggers);
+        assert!(triggers.contains(&AnomalyCode::DuplicatePlayerSpawn), "{:?}", triggers);
+        assert!(triggers.contains(&AnomalyCode::MissingEnergyOrbs), "{:?}", triggers);
+        assert_eq!(modules_at(&arena, 3, 3), vec![ModuleId::FloorStd]);
+        assert_eq!(modules_at(&arena, 5, 5), vec![ModuleId::FloorStd]);
+        assert_eq!(arena.get_player_position(), Some((0, 0)));
+    }
+
+    #[test]
+    fn missing_spawns_replace_the_cell_they_land_on() {
+        let mut arena = arena(4, 4, &[((3, 3), Some(ModuleId::OrbEnergy))]);
+
+        let mut generator = ArenaGenerator::new(Some(1));
+        let (_, report) = generator.validate_and_repair(&mut arena, 3);
+        let placed = report.repairs.iter()
+            .find_map(|r| match r.action {
+                RepairAction::PlacedMissingModule { module_id: ModuleId::Player, at } => Some((r.trigger, at)),
+                _ => None,
+            })
+            .expect("spawn placed");
+
+        assert_eq!(placed.0, AnomalyCode::MissingPlayerSpawn);
+        assert_eq!(modules_at(&arena, placed.1 .0, placed.1 .1), vec![ModuleId::Player]);
+        assert!(report.resolved);
+    }
+
+    #[test]
+    fn repair_gives_up_after_max_attempts() {
+        // Gravity missing is critical and nothing repairs it
+        let mut arena = arena(4, 4, &[((0, 0), Some(ModuleId::Player)), ((3, 3), Some(ModuleId::OrbEnergy))]);
+        arena.env_variables.clear();
+        arena.add_module(1, 1, ModuleId::WallLow, None);
+
+        let mut generator = ArenaGenerator::new(Some(1));
+
+        // No attempt allowed: the arena is left as it was
+        let (_, report) = generator.validate_and_repair(&mut arena.clone(), 0);
+        assert_eq!(report.attempts, 0);
+        assert!(report.is_empty());
+        assert!(!report.resolved);
+
+        // The budget runs out before the pass that would find nothing left to fix
+        let (_, report) = generator.validate_and_repair(&mut arena.clone(), 1);
+        assert_eq!(report.attempts, 1);
+        assert_eq!(report.repairs.len(), 1);
+        assert!(!report.resolved);
+        assert_eq!(report.remaining_critical, vec![AnomalyCode::MissingEnvVariable]);
+
+        // With budget to spare it stops as soon as a pass repairs nothing
+        let (diagnostics, report) = generator.validate_and_repair(&mut arena, 5);
+        assert_eq!(report.attempts, 2);
+        assert_eq!(report.repairs.len(), 1);
+        assert!(!report.resolved);
+        assert!(diagnostics.has_critical_anomalies());
+        assert_eq!(modules_at(&arena, 1, 1), vec![ModuleId::FloorStd]);
+    }
+}
@@ -0,0 +1,287 @@
+//generation/retry.rs
+use super::*;
+
+/// Deepest relaxation `generate_until_valid` applies
+pub const MAX_RELAXATION_LEVEL: u32 = 3;
+
+/// Smallest side of the fallback arena
+const MIN_FALLBACK_SIZE: u32 = 4;
+
+/// Odd constant spreading successive attempts over the seed space
+const SEED_STEP: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Seed of the `attempt`-th try for `master_seed`. Attempt 0 is the master seed itself,
+/// so a first-try success replays with a plain `ArenaGenerator::new(Some(master_seed))`.
+pub fn derive_seed(master_seed: u64, attempt: u32) -> u64 {
+    if attempt == 0 {
+        return master_seed;
+    }
+
+    // splitmix64 finalizer
+    let mut z = master_seed.wrapping_add(SEED_STEP.wrapping_mul(attempt as u64));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// How hard `generate_until_valid` tries before giving up
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Attempts, each with its own derived seed
+    pub max_attempts: u32,
+    /// Wall-clock limit over all attempts
+    pub time_budget: Option<Duration>,
+    /// Relax the config one level every this many failed attempts (0 never relaxes)
+    pub relax_every: u32,
+    /// Match a difficulty `(target, tolerance)` with `generate_for_difficulty` on every attempt
+    pub difficulty: Option<(f32, f32)>,
+    /// Hand back `ArenaGenerator::fallback_arena` instead of an error once out of attempts
+    pub fallback: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 8,
+            time_budget: Some(Duration::from_secs(5)),
+            relax_every: 2,
+            difficulty: None,
+            fallback: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    pub fn with_time_budget(mut self, budget: Option<Duration>) -> Self {
+        self.time_budget = budget;
+        self
+    }
+
+    pub fn with_relax_every(mut self, relax_every: u32) -> Self {
+        self.relax_every = relax_every;
+        self
+    }
+
+    pub fn with_difficulty(mut self, target: f32, tolerance: f32) -> Self {
+        self.difficulty = Some((target, tolerance));
+        self
+    }
+
+    pub fn with_fallback(mut self, fallback: bool) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Relaxation level for a 0-based attempt index
+    pub fn relaxation_level(&self, attempt: u32) -> u32 {
+        if self.relax_every == 0 {
+            return 0;
+        }
+        (attempt / self.relax_every).min(MAX_RELAXATION_LEVEL)
+    }
+}
+
+/// One rejected attempt
+#[derive(Debug, Clone)]
+pub struct RetryFailure {
+    pub seed: u64,
+    pub relaxation_level: u32,
+    pub error: ArenaGenerationError,
+}
+
+/// Result of `generate_until_valid`
+#[derive(Debug, Clone)]
+pub struct RetryOutcome {
+    pub arena: Arena,
+    /// Seed that produced the arena (the master seed for the fallback)
+    pub seed: u64,
+    /// Attempts run, the successful one included
+    pub attempts: u32,
+    pub relaxation_level: u32,
+    /// What the relaxation changed, in order
+    pub relaxations: Vec<String>,
+    pub failures: Vec<RetryFailure>,
+    /// Set when the difficulty policy was used
+    pub estimate: Option<DifficultyEstimate>,
+    /// True when every attempt failed and the arena is the fallback
+    pub fallback: bool,
+}
+
+/// `config` relaxed to `level`, with a note per change
+pub fn relax_config(config: &GenerationConfig, level: u32) -> (GenerationConfig, Vec<String>) {
+    let mut relaxed = config.clone();
+    let mut notes = Vec::new();
+    let forced = relaxed.forced_rules.len() as u32;
+
+    if level >= 1 {
+        if !relaxed.rule_quotas.is_empty() || !relaxed.preferred_rules.is_empty() {
+            relaxed.rule_quotas.clear();
+            relaxed.preferred_rules.clear();
+            notes.push("dropped rule quotas and preferences".to_string());
+        }
+        relaxed.max_repair_attempts += 2;
+        notes.push(format!("repair attempts raised to {}", relaxed.max_repair_attempts));
+    }
+
+    if level >= 2 && relaxed.rule_count > forced {
+        relaxed.rule_count -= 1;
+        notes.push(format!("rule count lowered to {}", relaxed.rule_count));
+    }
+
+    if level >= 3 {
+        if relaxed.rule_count > forced {
+            relaxed.rule_count = forced;
+            notes.push("only forced rules kept".to_string());
+        }
+        relaxed.densities = DensityConfig::default();
+        relaxed.time_budget_ms = relaxed.time_budget_ms.map(|ms| ms * 2);
+        notes.push("default densities, doubled time budget".to_string());
+    }
+
+    (relaxed, notes)
+}
+
+impl ArenaGenerator {
+    /// Restart both random streams as if built with `ArenaGenerator::new(Some(seed))`
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.rule_rng = StdRng::seed_from_u64(seed ^ RULE_STREAM_SALT);
+    }
+
+    /// Generate with seeds derived from `master_seed` until an arena passes, relaxing
+    /// the config as failures pile up. Deterministic for a given master seed and policy,
+    /// unless the policy's time budget cuts the run short.
+    ///
+    /// Out of attempts, returns the fallback arena if the policy allows it, else the last error.
+    /// A cancelled `GenerationProgress` stops the loop with `Cancelled` and no fallback.
+    pub fn generate_until_valid(
+        &mut self,
+        master_seed: u64,
+        config: &GenerationConfig,
+        policy: &RetryPolicy,
+        monitor: &mut AnomalyMonitor,
+    ) -> GenerationResult<RetryOutcome> {
+        let start = Instant::now();
+        let mut failures: Vec<RetryFailure> = Vec::new();
+        let mut attempts = 0;
+
+        while attempts < policy.max_attempts {
+            if policy.time_budget.is_some_and(|budget| start.elapsed() > budget) {
+                break;
+            }
+            if let Some(progress) = &self.progress {
+                progress.check_cancelled("retry")?;
+                progress.begin_attempt(attempts + 1);
+            }
+
+            let seed = derive_seed(master_seed, attempts);
+            let level = policy.relaxation_level(attempts);
+            let (relaxed, relaxations) = relax_config(config, level);
+            attempts += 1;
+
+            self.reseed(seed);
+            let mut attempt_monitor = AnomalyMonitor::new();
+            let result = match policy.difficulty {
+                Some((target, tolerance)) => self.generate_for_difficulty(&relaxed, target, tolerance, &mut attempt_monitor)
+                    .map(|m| (m.arena, Some(m.estimate))),
+                None => self.generate(&relaxed, &mut attempt_monitor).map(|arena| (arena, None)),
+            };
+
+            match result {
+                Ok((mut arena, estimate)) => {
+                    if level > 0 {
+                        arena.generation_metadata.constraints_applied.push(format!("relaxed:{}", level));
+                    }
+                    monitor.merge(attempt_monitor);
+                    monitor.record_metric("retry_attempts", attempts as f64);
+                    return Ok(RetryOutcome {
+                        arena,
+                        seed,
+                        attempts,
+                        relaxation_level: level,
+                        relaxations,
+                        failures,
+                        estimate,
+                        fallback: false,
+                    });
+                }
+                // Cancelled on purpose: no fallback either
+                Err(error @ ArenaGenerationError::Cancelled { .. }) => return Err(error),
+                Err(error) => {
+                    let retryable = error.is_retryable();
+                    failures.push(RetryFailure { seed, relaxation_level: level, error });
+                    if !retryable {
+                        break;
+                    }
+                }
+            }
+        }
+
+        monitor.record_metric("retry_attempts", attempts as f64);
+
+        if !policy.fallback {
+            return Err(failures.pop().map(|f| f.error).unwrap_or_else(|| ArenaGenerationError::Timeout {
+                max_time_ms: policy.time_budget.map_or(0, |b| b.as_millis() as u64),
+                stage: "retry".to_string(),
+            }));
+        }
+
+        monitor.report_anomaly(
+            AnomalyCode::FallbackArenaUsed,
+            format!("No valid arena in {} attempts from master seed {}; using the fallback arena", attempts, master_seed),
+            AnomalySeverity::Warning,
+            AnomalyLocation::none(),
+        );
+
+        Ok(RetryOutcome {
+            arena: self.fallback_arena(config, master_seed),
+            seed: master_seed,
+            attempts,
+            relaxation_level: policy.relaxation_level(attempts.saturating_sub(1)),
+            relaxations: Vec::new(),
+            failures,
+            estimate: None,
+            fallback: true,
+        })
+    }
+
+    /// Plain, always-valid arena: floor over the whole footprint, spawn near the middle,
+    /// orbs spread evenly, no rules and default environment
+    pub fn fallback_arena(&self, config: &GenerationConfig, seed: u64) -> Arena {
+        let mut arena = Arena::with_shape(config.width.max(MIN_FALLBACK_SIZE), config.height.max(MIN_FALLBACK_SIZE), seed, &config.shape);
+        if arena.playable_cell_count() < (MIN_FALLBACK_SIZE * MIN_FALLBACK_SIZE) as usize {
+            arena = Arena::new(arena.width.max(MIN_FALLBACK_SIZE), arena.height.max(MIN_FALLBACK_SIZE), seed);
+        }
+
+        let positions = arena.playable_positions();
+        for &(x, y) in &positions {
+            arena.add_module(x, y, ModuleId::FloorStd, None);
+        }
+
+        let center = (arena.width as i32 / 2, arena.height as i32 / 2);
+        let spawn = *positions.iter()
+            .min_by_key(|&&(x, y)| (x - center.0).abs() + (y - center.1).abs())
+            .expect("fallback arena has playable cells");
+        arena.set_module(spawn.0, spawn.1, ModuleId::Player, None);
+
+        let orbs = DensityConfig::scaled(positions.len(), config.densities.orbs).max(config.densities.min_orbs).max(1);
+        let candidates: Vec<(i32, i32)> = positions.iter().copied().filter(|&p| p != spawn).collect();
+        let step = (candidates.len() / orbs).max(1);
+        for &(x, y) in candidates.iter().step_by(step).take(orbs) {
+            arena.set_module(x, y, ModuleId::OrbEnergy, None);
+        }
+
+        for env_var in self.env_vars_db.get_all_variables() {
+            arena.env_variables.insert(env_var.id.clone(), env_var.default_value);
+        }
+
+        arena.generation_metadata.constraints_applied.push("fallback".to_string());
+        arena.generation_metadata.config = Some(config.clone());
+        arena
+    }
+}
@@ -0,0 +1,145 @@
+//generation/shrink.rs
+use super::*;
+use serde::{Deserialize, Serialize};
+
+/// Smallest arena size the shrinker will try
+pub const MIN_SHRINK_SIZE: u32 = 3;
+
+/// Upper bound on generations a single shrink may run
+pub const MAX_SHRINK_ATTEMPTS: u32 = 200;
+
+/// Smallest (size, rules) found that still fails with `code` for this seed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MinimalRepro {
+    pub seed: u64,
+    pub size: u32,
+    pub rules: Vec<RuleId>,
+    pub code: AnomalyCode,
+    pub original_size: u32,
+    pub original_rules: Vec<RuleId>,
+    /// Generations run while shrinking
+    pub attempts: u32,
+    /// The failing arena for the minimal config
+    pub arena: Arena,
+}
+
+impl MinimalRepro {
+    pub fn to_json_string(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Regenerate the minimal config; the result must fail with `code` again
+    pub fn replay(&self, monitor: &mut AnomalyMonitor) -> GenerationResult<Arena> {
+        ArenaGenerator::new(Some(self.seed)).generate_with_rules(self.size, &self.rules, monitor)
+    }
+}
+
+struct Shrinker {
+    seed: u64,
+    code: AnomalyCode,
+    attempts: u32,
+}
+
+impl Shrinker {
+    /// Generate the config and return the arena if it still fails with the target code
+    fn reproduces(&mut self, size: u32, rules: &[RuleId]) -> Option<Arena> {
+        if self.attempts >= MAX_SHRINK_ATTEMPTS {
+            return None;
+        }
+        self.attempts += 1;
+
+        let mut monitor = AnomalyMonitor::new();
+        let arena = ArenaGenerator::new(Some(self.seed))
+            .build_arena(GenerationConfig::default().with_size(size, size).with_fixed_rules(rules.to_vec()), &mut monitor)
+            .ok()?;
+
+        let failed = monitor.get_anomalies_by_code(self.code).iter()
+            .any(|a| a.severity == AnomalySeverity::Critical);
+
+        failed.then_some(arena)
+    }
+
+    /// Smallest size in [MIN_SHRINK_SIZE, size) that still fails, tried smallest first
+    fn shrink_size(&mut self, size: u32, rules: &[RuleId]) -> Option<(u32, Arena)> {
+        (MIN_SHRINK_SIZE..size).find_map(|candidate| {
+            self.reproduces(candidate, rules).map(|arena| (candidate, arena))
+        })
+    }
+
+    /// Drop rules one at a time until no single removal still fails
+    fn shrink_rules(&mut self, size: u32, rules: &mut Vec<RuleId>) -> Option<Arena> {
+        let mut last = None;
+        let mut i = 0;
+
+        while i < rules.len() {
+            let mut candidate = rules.clone();
+            candidate.remove(i);
+
+            match self.reproduces(size, &candidate) {
+                Some(arena) => {
+                    *rules = candidate;
+                    last = Some(arena);
+                }
+                None => i += 1,
+            }
+        }
+
+        last
+    }
+}
+
+/// Search for the smallest arena size and rule subset that still fail with `code`.
+///
+/// Fails if the given config does not reproduce the anomaly in the first place.
+pub fn shrink_failure(seed: u64, size: u32, rules: &[RuleId], code: AnomalyCode) -> GenerationResult<MinimalRepro> {
+    let mut shrinker = Shrinker { seed, code, attempts: 0 };
+
+    let Some(mut arena) = shrinker.reproduces(size, rules) else {
+        return Err(ArenaGenerationError::InvalidConfiguration {
+            details: format!("seed {} at size {} with {:?} does not reproduce {}", seed, size, rules, code),
+        });
+    };
+
+    let mut best_size = size;
+    let mut best_rules = rules.to_vec();
+
+    // Fewer rules can unlock a smaller size and vice versa, so alternate until stable
+    loop {
+        let mut progressed = false;
+
+        if let Some((smaller, failing)) = shrinker.shrink_size(best_size, &best_rules) {
+            best_size = smaller;
+            arena = failing;
+            progressed = true;
+        }
+
+        if let Some(failing) = shrinker.shrink_rules(best_size, &mut best_rules) {
+            arena = failing;
+            progressed = true;
+        }
+
+        if !progressed || shrinker.attempts >= MAX_SHRINK_ATTEMPTS {
+            break;
+        }
+    }
+
+    Ok(MinimalRepro {
+        seed,
+        size: best_size,
+        rules: best_rules,
+        code,
+        original_size: size,
+        original_rules: rules.to_vec(),
+        attempts: shrinker.attempts,
+        arena,
+    })
+}
+
+impl FailingSeed {
+    /// Shrink this stress failure once per critical code it reported
+    pub fn shrink(&self) -> Vec<GenerationResult<MinimalRepro>> {
+        self.codes.iter()
+            .map(|&code| shrink_failure(self.case.seed, self.case.size, &self.rules, code))
+            .collect()
+    }
+}
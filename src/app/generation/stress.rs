@@ -0,0 +1,225 @@
+//generation/stress.rs
+use super::*;
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+
+/// Seed range and parameter grid for a stress run. Every (seed, size, rule count)
+/// combination is generated once.
+#[derive(Debug, Clone)]
+pub struct StressConfig {
+    pub seeds: Range<u64>,
+    pub sizes: Vec<u32>,
+    pub rule_counts: Vec<u32>,
+}
+
+impl Default for StressConfig {
+    fn default() -> Self {
+        Self {
+            seeds: 0..100,
+            sizes: vec![8, 12, 16],
+            rule_counts: vec![1, 2, 3],
+        }
+    }
+}
+
+impl StressConfig {
+    pub fn new(seeds: Range<u64>) -> Self {
+        Self {
+            seeds,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_sizes(mut self, sizes: Vec<u32>) -> Self {
+        self.sizes = sizes;
+        self
+    }
+
+    pub fn with_rule_counts(mut self, rule_counts: Vec<u32>) -> Self {
+        self.rule_counts = rule_counts;
+        self
+    }
+
+    pub fn cases(&self) -> Vec<StressCase> {
+        let mut cases = Vec::new();
+        for seed in self.seeds.clone() {
+            for &size in &self.sizes {
+                for &rule_count in &self.rule_counts {
+                    cases.push(StressCase { seed, size, rule_count });
+                }
+            }
+        }
+        cases
+    }
+}
+
+/// One generation in a stress run; enough to reproduce it exactly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StressCase {
+    pub seed: u64,
+    pub size: u32,
+    pub rule_count: u32,
+}
+
+impl StressCase {
+    pub fn run(&self, monitor: &mut AnomalyMonitor) -> GenerationResult<Arena> {
+        ArenaGenerator::new(Some(self.seed)).generate_with_monitoring(self.size, self.rule_count, monitor)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FailingSeed {
+    pub case: StressCase,
+    pub rules: Vec<RuleId>,
+    pub codes: Vec<AnomalyCode>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FailureRate {
+    pub runs: usize,
+    pub failures: usize,
+}
+
+impl FailureRate {
+    pub fn rate(&self) -> f64 {
+        if self.runs == 0 {
+            0.0
+        } else {
+            self.failures as f64 / self.runs as f64
+        }
+    }
+
+    fn record(&mut self, failed: bool) {
+        self.runs += 1;
+        if failed {
+            self.failures += 1;
+        }
+    }
+}
+
+pub struct StressReport {
+    pub total: FailureRate,
+    pub by_size: BTreeMap<u32, FailureRate>,
+    pub by_rule_count: BTreeMap<u32, FailureRate>,
+    /// Keyed by the sorted display names of the active rules, e.g. "LAVA_FLOOR+NO_JUMP"
+    pub by_rule_combination: BTreeMap<String, FailureRate>,
+    pub failing_seeds: Vec<FailingSeed>,
+    pub monitor: AnomalyMonitor,
+    pub elapsed_ms: u64,
+}
+
+struct CaseOutcome {
+    case: StressCase,
+    rules: Vec<RuleId>,
+    error: Option<String>,
+    monitor: AnomalyMonitor,
+}
+
+/// Generate every case of the grid in parallel and aggregate the results.
+/// Aggregation happens in case order, so the report is deterministic.
+pub fn run_stress_test(config: &StressConfig) -> StressReport {
+    let start = Instant::now();
+
+    let outcomes: Vec<CaseOutcome> = config.cases()
+        .into_par_iter()
+        .map(|case| {
+            let mut monitor = AnomalyMonitor::new();
+            let result = case.run(&mut monitor);
+            CaseOutcome {
+                case,
+                rules: monitor.get_rules_applied(),
+                error: result.err().map(|e| e.to_string()),
+                monitor,
+            }
+        })
+        .collect();
+
+    let mut report = StressReport {
+        total: FailureRate::default(),
+        by_size: BTreeMap::new(),
+        by_rule_count: BTreeMap::new(),
+        by_rule_combination: BTreeMap::new(),
+        failing_seeds: Vec::new(),
+        monitor: AnomalyMonitor::new(),
+        elapsed_ms: 0,
+    };
+
+    for outcome in outcomes {
+        let failed = outcome.error.is_some();
+
+        report.total.record(failed);
+        report.by_size.entry(outcome.case.size).or_default().record(failed);
+        report.by_rule_count.entry(outcome.case.rule_count).or_default().record(failed);
+        report.by_rule_combination.entry(rule_combination_key(&outcome.rules)).or_default().record(failed);
+
+        if let Some(message) = outcome.error {
+            let mut codes: Vec<AnomalyCode> = outcome.monitor.get_anomalies().iter()
+                .filter(|a| a.severity == AnomalySeverity::Critical)
+                .map(|a| a.code)
+                .collect();
+            codes.sort();
+            codes.dedup();
+
+            report.failing_seeds.push(FailingSeed {
+                case: outcome.case,
+                rules: outcome.rules,
+                codes,
+                message,
+            });
+        }
+
+        report.monitor.merge(outcome.monitor);
+    }
+
+    report.elapsed_ms = start.elapsed().as_millis() as u64;
+    report
+}
+
+pub fn rule_combination_key(rules: &[RuleId]) -> String {
+    if rules.is_empty() {
+        return "NONE".to_string();
+    }
+
+    let mut names: Vec<String> = rules.iter().map(|r| r.to_string()).collect();
+    names.sort();
+    names.join("+")
+}
+
+impl fmt::Display for StressReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Stress Report:")?;
+        writeln!(f, "  Runs: {} ({} failed, {:.1}%) in {}ms",
+                 self.total.runs, self.total.failures, self.total.rate() * 100.0, self.elapsed_ms)?;
+
+        writeln!(f, "  By size:")?;
+        for (size, rate) in &self.by_size {
+            writeln!(f, "    {}x{}: {}/{} ({:.1}%)", size, size, rate.failures, rate.runs, rate.rate() * 100.0)?;
+        }
+
+        writeln!(f, "  By rule count:")?;
+        for (count, rate) in &self.by_rule_count {
+            writeln!(f, "    {}: {}/{} ({:.1}%)", count, rate.failures, rate.runs, rate.rate() * 100.0)?;
+        }
+
+        writeln!(f, "  By rule combination:")?;
+        for (combination, rate) in &self.by_rule_combination {
+            if rate.failures > 0 {
+                writeln!(f, "    {}: {}/{} ({:.1}%)", combination, rate.failures, rate.runs, rate.rate() * 100.0)?;
+            }
+        }
+
+        if !self.failing_seeds.is_empty() {
+            writeln!(f, "  Failing seeds:")?;
+            for failure in &self.failing_seeds {
+                let codes: Vec<&str> = failure.codes.iter().map(|c| c.as_str()).collect();
+                writeln!(f, "    seed={} size={} rules={} [{}]",
+                         failure.case.seed, failure.case.size, failure.case.rule_count, codes.join(", "))?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,330 @@
+//generation/templates.rs
+use super::*;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+/// Hand-authored chunk stamped into the arena before WFC fills the rest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Template {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    /// Cells relative to the template's top-left corner
+    pub cells: Vec<TemplateCell>,
+    #[serde(default)]
+    pub anchor: TemplateAnchor,
+    #[serde(default = "default_true")]
+    pub allow_rotation: bool,
+    #[serde(default = "default_true")]
+    pub allow_mirror: bool,
+    #[serde(default)]
+    pub constraints: PlacementConstraints,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateCell {
+    pub x: i32,
+    pub y: i32,
+    pub module_id: ModuleId,
+    #[serde(default)]
+    pub params: Option<serde_json::Value>,
+}
+
+/// Where in the arena a template may be placed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TemplateAnchor {
+    #[default]
+    Anywhere,
+    Center,
+    /// Touching one of the arena edges (after margin)
+    Edge,
+    /// In one of the four corners (after margin)
+    Corner,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlacementConstraints {
+    /// How many copies to stamp
+    #[serde(default = "default_count")]
+    pub count: u32,
+    /// Only stamp when all of these rules are active
+    #[serde(default)]
+    pub requires_rules: Vec<RuleId>,
+    /// Skip arenas smaller than this in either dimension
+    #[serde(default)]
+    pub min_arena_size: u32,
+    /// Free cells kept between the template and the arena edge
+    #[serde(default)]
+    pub margin: u32,
+}
+
+impl Default for PlacementConstraints {
+    fn default() -> Self {
+        Self {
+            count: 1,
+            requires_rules: Vec::new(),
+            min_arena_size: 0,
+            margin: 0,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_count() -> u32 {
+    1
+}
+
+/// Quarter turns and mirroring applied to a template before stamping
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemplateTransform {
+    pub quarter_turns: u8,
+    pub mirrored: bool,
+}
+
+impl Template {
+    /// Build a template from text rows. Row 0 is y = 0, a space leaves the cell to WFC.
+    ///
+    /// Legend: `F` floor, `G` large floor, `f` fragile floor, `R` low ramp, `S` steep ramp, `w` low wall,
+    /// `W` high wall, `P` glass panel, `L` lava, `Z` static laser, `O` orb, `B` floor button,
+    /// `V` lever, `E` energy barrier, `T` teleporter in, `t` teleporter out, `C` climb surface
+    pub fn from_rows(name: &str, rows: &[&str]) -> GenerationResult<Self> {
+        let mut cells = Vec::new();
+
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                let module_id = match c {
+                    ' ' => continue,
+                    'F' => ModuleId::FloorStd,
+                    'G' => ModuleId::FloorLarge,
+                    'f' => ModuleId::FloorFragile,
+                    'R' => ModuleId::RampLow,
+                    'S' => ModuleId::RampSteep,
+                    'w' => ModuleId::WallLow,
+                    'W' => ModuleId::WallHigh,
+                    'P' => ModuleId::PanelGlass,
+                    'L' => ModuleId::HazardLavaPit,
+                    'Z' => ModuleId::HazardLaserEmitterStatic,
+                    'O' => ModuleId::OrbEnergy,
+                    'B' => ModuleId::InteractButtonFloor,
+                    'V' => ModuleId::InteractLever,
+                    'E' => ModuleId::InteractBarrierEnergy,
+                    'T' => ModuleId::MoveTeleporterIn,
+                    't' => ModuleId::MoveTeleporterOut,
+                    'C' => ModuleId::MoveClimbSurface,
+                    other => return Err(ArenaGenerationError::InvalidConfiguration {
+                        details: format!("template '{}': unknown cell '{}' at ({}, {})", name, other, x, y),
+                    }),
+                };
+                cells.push(TemplateCell { x: x as i32, y: y as i32, module_id, params: None });
+            }
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            width: rows.iter().map(|row| row.chars().count()).max().unwrap_or(0) as u32,
+            height: rows.len() as u32,
+            cells,
+            anchor: TemplateAnchor::Anywhere,
+            allow_rotation: true,
+            allow_mirror: true,
+            constraints: PlacementConstraints::default(),
+        })
+    }
+
+    pub fn from_json(json: &str) -> GenerationResult<Self> {
+        serde_json::from_str(json).map_err(|e| ArenaGenerationError::InvalidConfiguration {
+            details: format!("malformed template: {}", e),
+        })
+    }
+
+    pub fn with_anchor(mut self, anchor: TemplateAnchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    pub fn with_constraints(mut self, constraints: PlacementConstraints) -> Self {
+        self.constraints = constraints;
+        self
+    }
+
+    /// Every transform this template allows
+    pub fn transforms(&self) -> Vec<TemplateTransform> {
+        let turns: &[u8] = if self.allow_rotation { &[0, 1, 2, 3] } else { &[0] };
+        let mirrors: &[bool] = if self.allow_mirror { &[false, true] } else { &[false] };
+
+        turns.iter()
+            .flat_map(|&quarter_turns| mirrors.iter().map(move |&mirrored| TemplateTransform { quarter_turns, mirrored }))
+            .collect()
+    }
+
+    /// Size of the bounding box once transformed
+    pub fn transformed_size(&self, transform: TemplateTransform) -> (u32, u32) {
+        if transform.quarter_turns % 2 == 1 {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        }
+    }
+
+    /// Cells after mirroring (along x) then rotating clockwise, still relative to the top-left corner
+    pub fn transformed_cells(&self, transform: TemplateTransform) -> Vec<TemplateCell> {
+        let (w, h) = (self.width as i32, self.height as i32);
+
+        self.cells.iter()
+            .map(|cell| {
+                let x = if transform.mirrored { w - 1 - cell.x } else { cell.x };
+                let y = cell.y;
+                let (x, y) = match transform.quarter_turns % 4 {
+                    0 => (x, y),
+                    1 => (h - 1 - y, x),
+                    2 => (w - 1 - x, h - 1 - y),
+                    _ => (y, w - 1 - x),
+                };
+                TemplateCell { x, y, ..cell.clone() }
+            })
+            .collect()
+    }
+
+    fn applies_to(&self, arena: &Arena) -> bool {
+        let constraints = &self.constraints;
+        let big_enough = arena.width >= constraints.min_arena_size && arena.height >= constraints.min_arena_size;
+        let rules_active = constraints.requires_rules.iter()
+            .all(|rule| arena.active_rules.iter().any(|r| &r.id == rule));
+
+        big_enough && rules_active
+    }
+}
+
+/// Built-in set pieces
+pub struct TemplateLibrary;
+
+impl TemplateLibrary {
+    /// Lava ring around an orb with a single floor bridge
+    pub fn lava_moat() -> Template {
+        Template::from_rows("lava_moat", &[
+            "LLLLL",
+            "LFFFL",
+            "LFOFL",
+            "LFFFL",
+            "LLFLL",
+        ])
+        .expect("built-in template is valid")
+        .with_constraints(PlacementConstraints { min_arena_size: 9, margin: 1, ..Default::default() })
+    }
+
+    /// Teleporter pair on both sides of a high wall, with a floor button beside the entry;
+    /// the button is not linked to the teleporters, which always work
+    pub fn teleporter_puzzle() -> Template {
+        Template::from_rows("teleporter_puzzle", &[
+            "FTWtF",
+            "FBWFO",
+            "FFWFF",
+        ])
+        .expect("built-in template is valid")
+        .with_anchor(TemplateAnchor::Edge)
+        .with_constraints(PlacementConstraints { min_arena_size: 8, ..Default::default() })
+    }
+
+    pub fn all() -> Vec<Template> {
+        vec![Self::lava_moat(), Self::teleporter_puzzle()]
+    }
+}
+
+impl ArenaGenerator {
+    /// Stamp every applicable template, returning the cells they claimed so WFC leaves them alone
+    pub(crate) fn stamp_templates(&mut self, arena: &mut Arena, monitor: &mut AnomalyMonitor) -> HashSet<(i32, i32)> {
+        let mut claimed = HashSet::new();
+
+        for template in self.templates.clone() {
+            if !template.applies_to(arena) {
+                continue;
+            }
+
+            for _ in 0..template.constraints.count {
+                match self.place_template(arena, &template, &claimed) {
+                    Some(cells) => {
+                        claimed.extend(cells);
+                        arena.generation_metadata.constraints_applied.push(format!("template:{}", template.name));
+                    }
+                    None => {
+                        monitor.report_anomaly(
+                            AnomalyCode::TemplatePlacementFailed,
+                            format!("No room for template '{}' ({:?} anchor) in {}x{} arena",
+                                   template.name, template.anchor, arena.width, arena.height),
+                            AnomalySeverity::Warning,
+                            AnomalyLocation::none(),
+                        );
+                        break;
+                    }
+                }
+            }
+        }
+
+        claimed
+    }
+
+    fn place_template(&mut self, arena: &mut Arena, template: &Template, claimed: &HashSet<(i32, i32)>) -> Option<Vec<(i32, i32)>> {
+        let mut transforms = template.transforms();
+        transforms.shuffle(&mut self.rng);
+
+        for transform in transforms {
+            let cells = template.transformed_cells(transform);
+            let size = template.transformed_size(transform);
+
+            let mut origins = anchor_origins(arena, size, template.anchor, template.constraints.margin);
+            origins.retain(|&(ox, oy)| {
+                cells.iter().all(|c| {
+                    let (x, y) = (ox + c.x, oy + c.y);
+                    arena.is_valid_position(x, y) && !claimed.contains(&(x, y)) && arena.get_cell(x, y).is_none()
+                })
+            });
+
+            if let Some(&(ox, oy)) = origins.choose(&mut self.rng) {
+                let mut stamped = Vec::with_capacity(cells.len());
+                for cell in cells {
+                    let (x, y) = (ox + cell.x, oy + cell.y);
+                    arena.add_module(x, y, cell.module_id, cell.params);
+                    stamped.push((x, y));
+                }
+                return Some(stamped);
+            }
+        }
+
+        None
+    }
+}
+
+/// Top-left corners where a `size` box satisfies the anchor inside the arena bounds
+fn anchor_origins(arena: &Arena, size: (u32, u32), anchor: TemplateAnchor, margin: u32) -> Vec<(i32, i32)> {
+    let margin = margin as i32;
+    let min = (margin, margin);
+    let max = (
+        arena.width as i32 - size.0 as i32 - margin,
+        arena.height as i32 - size.1 as i32 - margin,
+    );
+    if max.0 < min.0 || max.1 < min.1 {
+        return Vec::new();
+    }
+
+    let mut origins = Vec::new();
+    for oy in min.1..=max.1 {
+        for ox in min.0..=max.0 {
+            let on_x_edge = ox == min.0 || ox == max.0;
+            let on_y_edge = oy == min.1 || oy == max.1;
+            let keep = match anchor {
+                TemplateAnchor::Anywhere => true,
+                TemplateAnchor::Center => ox == (min.0 + max.0) / 2 && oy == (min.1 + max.1) / 2,
+                TemplateAnchor::Edge => on_x_edge || on_y_edge,
+                TemplateAnchor::Corner => on_x_edge && on_y_edge,
+            };
+            if keep {
+                origins.push((ox, oy));
+            }
+        }
+    }
+
+    origins
+}
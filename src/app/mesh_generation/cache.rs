@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+use crate::app::mesh_generation::*;
+
+// ============================================================================
+// CACHE DE MAILLAGES
+// ============================================================================
+
+/// Forme et dimensions d'un maillage de module. Les flottants sont comparés bit à bit :
+/// deux cellules aux paramètres identiques partagent le même maillage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MeshKey {
+    /// Pavé, arêtes du dessus chanfreinées de `bevel`
+    Cube { width: u32, height: u32, depth: u32, bevel: u32 },
+    Sphere { radius: u32, resolution: usize },
+    Cylinder { radius: u32, height: u32, resolution: usize },
+    Ramp { size: u32, angle: u32 },
+    Arch { width: u32, height: u32, depth: u32 },
+    Octahedron { radius: u32 },
+    /// Bandes parallèles posées à plat
+    Stripes { width: u32, depth: u32 },
+    /// Carré plat tourné vers le haut, texture étirée une seule fois
+    Decal { size: u32 },
+}
+
+impl MeshKey {
+    pub fn cube(width: f32, height: f32, depth: f32) -> Self {
+        Self::beveled(Vec3::new(width, height, depth), 0.0)
+    }
+
+    pub fn beveled(size: Vec3, bevel: f32) -> Self {
+        MeshKey::Cube { width: size.x.to_bits(), height: size.y.to_bits(), depth: size.z.to_bits(), bevel: bevel.to_bits() }
+    }
+
+    pub fn sphere(radius: f32, resolution: usize) -> Self {
+        MeshKey::Sphere { radius: radius.to_bits(), resolution }
+    }
+
+    pub fn cylinder(radius: f32, height: f32, resolution: usize) -> Self {
+        MeshKey::Cylinder { radius: radius.to_bits(), height: height.to_bits(), resolution }
+    }
+
+    pub fn ramp(size: f32, angle: f32) -> Self {
+        MeshKey::Ramp { size: size.to_bits(), angle: angle.to_bits() }
+    }
+
+    pub fn arch(width: f32, height: f32, depth: f32) -> Self {
+        MeshKey::Arch { width: width.to_bits(), height: height.to_bits(), depth: depth.to_bits() }
+    }
+
+    pub fn octahedron(radius: f32) -> Self {
+        MeshKey::Octahedron { radius: radius.to_bits() }
+    }
+
+    pub fn stripes(width: f32, depth: f32) -> Self {
+        MeshKey::Stripes { width: width.to_bits(), depth: depth.to_bits() }
+    }
+
+    pub fn decal(size: f32) -> Self {
+        MeshKey::Decal { size: size.to_bits() }
+    }
+
+    /// Construit le maillage décrit par la clé
+    pub fn build(&self) -> Mesh {
+        let f = f32::from_bits;
+        match *self {
+            MeshKey::Cube { width, height, depth, bevel } => box_mesh(Vec3::new(f(width), f(height), f(depth)), f(bevel)),
+            MeshKey::Sphere { radius, resolution } => sphere_mesh(f(radius), resolution),
+            MeshKey::Cylinder { radius, height, resolution } => cylinder_mesh(f(radius), f(height), resolution),
+            MeshKey::Ramp { size, angle } => ramp_mesh(f(size), f(angle)),
+            MeshKey::Arch { width, height, depth } => arch_mesh(f(width), f(height), f(depth)),
+            MeshKey::Octahedron { radius } => octahedron_mesh(f(radius)),
+            MeshKey::Stripes { width, depth } => hazard_stripes_mesh(f(width), f(depth)),
+            MeshKey::Decal { size } => decal_mesh(f(size)),
+        }
+    }
+}
+
+/// Un seul asset `Mesh` par forme : les cellules partagent leurs handles, et Bevy
+/// regroupe en un seul appel de rendu (instancing automatique) les entités qui ont
+/// le même maillage et le même matériau.
+#[derive(Resource, Default)]
+pub struct MeshCache {
+    handles: HashMap<MeshKey, Handle<Mesh>>,
+}
+
+impl MeshCache {
+    /// Handle du maillage de `key`, créé au premier appel
+    pub fn get_or_create(&mut self, meshes: &mut Assets<Mesh>, key: MeshKey) -> Handle<Mesh> {
+        self.handles.entry(key)
+            .or_insert_with(|| meshes.add(key.build()))
+            .clone()
+    }
+
+    pub fn cube(&mut self, meshes: &mut Assets<Mesh>, width: f32, height: f32, depth: f32) -> Handle<Mesh> {
+        self.get_or_create(meshes, MeshKey::cube(width, height, depth))
+    }
+
+    /// Nombre de maillages distincts créés
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+
+    /// Oublie les handles ; les assets sont libérés une fois plus aucune entité ne les utilise
+    pub fn clear(&mut self) {
+        self.handles.clear();
+    }
+}
@@ -0,0 +1,170 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use crate::app::game::{ArenaModule, TriggerButton, Lever, LeverHandle, EnergyBarrier};
+use crate::app::core::MaterialSlot;
+use crate::app::mesh_generation::*;
+use log::info;
+
+// ============================================================================
+// ÉTATS DES MODULES INTERACTIFS
+// ============================================================================
+
+/// Dimensions de la poignée d'un levier
+pub const LEVER_HANDLE_SIZE: Vec3 = Vec3::new(0.06, 0.5, 0.06);
+
+/// Inclinaison de la poignée, vers l'avant (allumé) ou l'arrière (éteint)
+const LEVER_TILT: f32 = 0.6;
+
+/// Enfoncement d'un bouton au sol pressé
+const BUTTON_PRESS_DEPTH: f32 = 0.04;
+
+/// Position de la poignée relative au socle de hauteur `base_height` : elle pivote sur le dessus du socle
+pub fn lever_handle_transform(base_height: f32, is_on: bool) -> Transform {
+    let rotation = Quat::from_rotation_x(if is_on { LEVER_TILT } else { -LEVER_TILT });
+    let pivot = Vec3::Y * base_height * 0.5;
+    Transform::from_translation(pivot + rotation * Vec3::Y * LEVER_HANDLE_SIZE.y * 0.5)
+        .with_rotation(rotation)
+}
+
+/// Les boutons réarmables se relèvent après `reset_delay`
+pub fn button_reset_system(
+    time: Res<Time>,
+    mut buttons: Query<&mut TriggerButton>,
+) {
+    let dt = time.delta_secs();
+
+    for mut button in buttons.iter_mut() {
+        if !button.is_pressed || button.one_time {
+            continue;
+        }
+        button.reset_timer += dt;
+        if button.reset_timer >= button.reset_delay {
+            button.is_pressed = false;
+            button.reset_timer = 0.0;
+        }
+    }
+}
+
+/// Un bouton pressé ou un levier allumé coupe les barrières de même identifiant
+pub fn barrier_trigger_system(
+    buttons: Query<&TriggerButton, Changed<TriggerButton>>,
+    levers: Query<&Lever, Changed<Lever>>,
+    mut barriers: Query<&mut EnergyBarrier>,
+) {
+    let triggered: Vec<&String> = buttons.iter()
+        .filter(|button| button.is_pressed)
+        .filter_map(|button| button.trigger_id.as_ref())
+        .chain(levers.iter().filter(|lever| lever.is_on).filter_map(|lever| lever.trigger_id.as_ref()))
+        .collect();
+    if triggered.is_empty() {
+        return;
+    }
+
+    for mut barrier in barriers.iter_mut() {
+        if barrier.disable_on_trigger_id.as_ref().is_some_and(|id| triggered.contains(&id)) {
+            barrier.is_active = false;
+            barrier.disabled_timer = barrier.disable_duration;
+            info!("⚡ Barrière coupée pour {}s", barrier.disable_duration);
+        }
+    }
+}
+
+/// Une barrière coupée par un déclencheur se rallume à la fin de `disable_duration`.
+/// Une barrière éteinte dès le départ (`disabled_timer` nul) le reste.
+pub fn barrier_timer_system(
+    time: Res<Time>,
+    mut barriers: Query<&mut EnergyBarrier>,
+) {
+    let dt = time.delta_secs();
+
+    for mut barrier in barriers.iter_mut() {
+        if barrier.is_active || barrier.disabled_timer <= 0.0 {
+            continue;
+        }
+        barrier.disabled_timer -= dt;
+        if barrier.disabled_timer <= 0.0 {
+            barrier.disabled_timer = 0.0;
+            barrier.is_active = true;
+        }
+    }
+}
+
+// ============================================================================
+// RENDU DES ÉTATS
+// ============================================================================
+
+pub fn button_visual_system(
+    materials: Res<ModuleMaterials>,
+    mut buttons: Query<(&TriggerButton, &ArenaModule, &mut MeshMaterial3d<StandardMaterial>, &mut Transform), Changed<TriggerButton>>,
+) {
+    for (button, module, mut material, mut transform) in buttons.iter_mut() {
+        material.0 = if button.is_pressed {
+            materials.button_pressed.clone()
+        } else {
+            materials.button.clone()
+        };
+
+        if !button.wall_mounted {
+            let depth = if button.is_pressed { BUTTON_PRESS_DEPTH } else { 0.0 };
+            transform.translation.y = module.original_position.y - depth;
+        }
+    }
+}
+
+pub fn lever_visual_system(
+    levers: Query<(&Lever, &ArenaModule, &Children), Changed<Lever>>,
+    mut handles: Query<&mut Transform, With<LeverHandle>>,
+) {
+    for (lever, module, children) in levers.iter() {
+        // Le socle est centré sur sa hauteur : `original_position.y` en vaut la moitié
+        let base_height = module.original_position.y * 2.0;
+        for &child in children.iter() {
+            if let Ok(mut transform) = handles.get_mut(child) {
+                *transform = lever_handle_transform(base_height, lever.is_on);
+            }
+        }
+    }
+}
+
+/// Une barrière éteinte disparaît et ne bloque plus rien
+pub fn barrier_visual_system(
+    mut commands: Commands,
+    mut barriers: Query<(Entity, &EnergyBarrier, &mut Visibility), Changed<EnergyBarrier>>,
+) {
+    for (entity, barrier, mut visibility) in barriers.iter_mut() {
+        if barrier.is_active {
+            *visibility = Visibility::Inherited;
+            commands.entity(entity).remove::<ColliderDisabled>();
+        } else {
+            *visibility = Visibility::Hidden;
+            commands.entity(entity).insert(ColliderDisabled);
+        }
+    }
+}
+
+/// Lueur pulsée des téléporteurs et miroitement des barrières, autour des valeurs du thème actif.
+/// Les matériaux étant partagés, une seule mise à jour anime tous les modules du même type.
+pub fn animate_module_materials_system(
+    time: Res<Time>,
+    materials: Res<ModuleMaterials>,
+    theme: Res<ActiveTheme>,
+    mut assets: ResMut<Assets<StandardMaterial>>,
+) {
+    let t = time.elapsed_secs();
+
+    // Entrée et sortie en opposition de phase
+    let glow_in = 0.6 + 0.4 * (t * 2.5).sin();
+    let glow_out = 0.6 - 0.4 * (t * 2.5).sin();
+    for (slot, glow) in [(MaterialSlot::TeleporterIn, glow_in), (MaterialSlot::TeleporterOut, glow_out)] {
+        if let Some(material) = assets.get_mut(materials.handle(slot)) {
+            let [r, g, b] = theme.current.spec(slot).emissive;
+            material.emissive = LinearRgba::from(Color::srgb(r, g, b)) * glow;
+        }
+    }
+
+    if let Some(material) = assets.get_mut(&materials.barrier_energy) {
+        let alpha = theme.current.spec(MaterialSlot::BarrierEnergy).base_color[3];
+        let shimmer = 0.8 + 0.15 * (t * 7.0).sin() + 0.05 * (t * 13.0).sin();
+        material.base_color.set_alpha(alpha * shimmer);
+    }
+}
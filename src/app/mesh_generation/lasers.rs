@@ -0,0 +1,137 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use crate::app::game::{ArenaModule, LaserEmitter, LaserBeam, BeamPassThrough, Player, PlayerDeathEvent, DeathCause};
+use log::info;
+
+// ============================================================================
+// RAYONS LASER
+// ============================================================================
+
+/// Hauteur des rayons au-dessus du sol : à mi-corps du joueur, au-dessus des dalles
+pub const BEAM_HEIGHT: f32 = 0.6;
+
+/// Section du maillage de rayon (longueur 1, étiré selon X)
+pub const BEAM_THICKNESS: f32 = 0.06;
+
+/// Émetteur tiré des paramètres du module ; angles donnés en degrés dans les données
+pub fn laser_emitter(beam_length: f32, damage_per_second: f32, rotation_speed_deg: f32, arc_deg: f32, direction_deg: f32) -> LaserEmitter {
+    LaserEmitter {
+        beam_length,
+        damage_per_second,
+        rotation_speed: rotation_speed_deg.to_radians(),
+        arc: arc_deg.clamp(0.0, 360.0).to_radians(),
+        direction: direction_deg.to_radians(),
+        sweep_offset: 0.0,
+        sweep_sign: 1.0,
+        current_length: beam_length,
+        hitting_player: false,
+    }
+}
+
+/// Fait avancer le balayage de `dt` : aller-retour dans l'arc, tour complet si l'arc fait 360°.
+/// Renvoie le cap courant du rayon.
+pub fn advance_sweep(emitter: &mut LaserEmitter, dt: f32) -> f32 {
+    if emitter.rotation_speed > 0.0 {
+        let half_arc = emitter.arc * 0.5;
+        emitter.sweep_offset += emitter.sweep_sign * emitter.rotation_speed * dt;
+
+        if emitter.arc >= std::f32::consts::TAU {
+            emitter.sweep_offset = emitter.sweep_offset.rem_euclid(std::f32::consts::TAU);
+        } else if emitter.sweep_offset.abs() > half_arc {
+            // Rebond sur le bord de l'arc
+            emitter.sweep_offset = emitter.sweep_sign * half_arc * 2.0 - emitter.sweep_offset;
+            emitter.sweep_offset = emitter.sweep_offset.clamp(-half_arc, half_arc);
+            emitter.sweep_sign = -emitter.sweep_sign;
+        }
+    }
+    emitter.direction + emitter.sweep_offset
+}
+
+/// Position du rayon relative à l'émetteur, pour une longueur visible donnée
+pub fn beam_transform(emitter_height: f32, length: f32) -> Transform {
+    Transform::from_xyz(length * 0.5, BEAM_HEIGHT - emitter_height * 0.5, 0.0)
+        .with_scale(Vec3::new(length.max(0.001), 1.0, 1.0))
+}
+
+/// Oriente chaque émetteur, lance son rayon et le coupe au premier obstacle.
+/// Les vitres (`BeamPassThrough`) et les capteurs (orbes) sont traversés.
+pub fn laser_beam_system(
+    time: Res<Time>,
+    rapier_context: ReadRapierContext,
+    pass_through: Query<(), With<BeamPassThrough>>,
+    players: Query<(), With<Player>>,
+    mut emitters: Query<(Entity, &mut LaserEmitter, &mut Transform)>,
+) {
+    let Ok(context) = rapier_context.single() else {
+        return;
+    };
+    let dt = time.delta_secs();
+    let blocks_beam = |entity: Entity| !pass_through.contains(entity);
+
+    for (entity, mut emitter, mut transform) in emitters.iter_mut() {
+        let heading = advance_sweep(&mut emitter, dt);
+        transform.rotation = Quat::from_rotation_y(heading);
+
+        // Départ à hauteur de rayon, depuis l'axe de l'émetteur
+        let origin = transform.translation.with_y(BEAM_HEIGHT);
+        let direction = transform.rotation * Vec3::X;
+        let filter = QueryFilter::default()
+            .exclude_sensors()
+            .exclude_collider(entity)
+            .predicate(&blocks_beam);
+
+        match context.cast_ray(origin, direction, emitter.beam_length, true, filter) {
+            Some((hit, distance)) => {
+                emitter.current_length = distance;
+                emitter.hitting_player = players.contains(hit);
+            }
+            None => {
+                emitter.current_length = emitter.beam_length;
+                emitter.hitting_player = false;
+            }
+        }
+    }
+}
+
+/// Dégâts continus tant qu'un rayon touche le joueur
+pub fn laser_damage_system(
+    time: Res<Time>,
+    emitters: Query<&LaserEmitter>,
+    mut player_query: Query<&mut Player>,
+    mut player_death_events: EventWriter<PlayerDeathEvent>,
+) {
+    let damage: f32 = emitters.iter()
+        .filter(|emitter| emitter.hitting_player)
+        .map(|emitter| emitter.damage_per_second * time.delta_secs())
+        .sum();
+    if damage <= 0.0 {
+        return;
+    }
+
+    for mut player in player_query.iter_mut() {
+        // Un seul événement de mort, au moment où la santé passe à zéro
+        let was_alive = player.health > 0.0;
+        player.health -= damage;
+        if was_alive && player.health <= 0.0 {
+            info!("🔴 Joueur abattu par un laser");
+            player_death_events.write(PlayerDeathEvent {
+                cause: DeathCause::Hazard("Laser Beam".to_string()),
+            });
+        }
+    }
+}
+
+/// Le maillage du rayon suit la longueur coupée par le lancer de rayon
+pub fn laser_beam_visual_system(
+    emitters: Query<(&LaserEmitter, &ArenaModule, &Children), Changed<LaserEmitter>>,
+    mut beams: Query<&mut Transform, With<LaserBeam>>,
+) {
+    for (emitter, module, children) in emitters.iter() {
+        let height = module.original_position.y * 2.0;
+        for &child in children.iter() {
+            if let Ok(mut transform) = beams.get_mut(child) {
+                *transform = beam_transform(height, emitter.current_length);
+            }
+        }
+    }
+}
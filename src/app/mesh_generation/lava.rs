@@ -0,0 +1,124 @@
+use bevy::prelude::*;
+use crate::app::core::*;
+use crate::app::core::gameplay::{DangerPressureSystem, ThreatType};
+use crate::app::game::{ArenaManager, ArenaModule, DynamicHazard, LavaPit, Player, PlayerDeathEvent, DeathCause};
+use crate::app::mesh_generation::*;
+use std::collections::HashSet;
+use uuid::Uuid;
+use log::info;
+
+// ============================================================================
+// LAVE DYNAMIQUE
+// ============================================================================
+
+/// Coulées de lave de l'arène en cours
+#[derive(Resource, Default)]
+pub struct LavaDynamics {
+    /// Arène dont les coulées sont suivies
+    arena_id: Option<Uuid>,
+    pub flow: Option<LavaFlow>,
+}
+
+/// Intensité de propagation : celle de la règle `LavaFloor`, plus les menaces `LavaSpread` en cours
+pub fn lava_spread_intensity(arena: &Arena, pressure: &DangerPressureSystem) -> f32 {
+    let threats: f32 = pressure.active_threats.iter()
+        .filter(|threat| matches!(threat.threat_type, ThreatType::LavaSpread))
+        .map(|threat| threat.intensity)
+        .sum();
+    LavaFlow::rule_intensity(arena) + threats
+}
+
+/// Fait avancer les coulées dans les données de l'arène, puis refait les seules cellules modifiées.
+/// Une cellule prise dans un bloc fusionné fait reconstruire tout le bloc, sans elle.
+pub fn lava_flow_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    pressure: Res<DangerPressureSystem>,
+    materials: Res<ModuleMaterials>,
+    settings: Res<ArenaVisualSettings>,
+    mut dynamics: ResMut<LavaDynamics>,
+    mut arena_manager: ResMut<ArenaManager>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut cache: ResMut<MeshCache>,
+    modules: Query<(Entity, &ArenaModule)>,
+    merged: Query<(Entity, &MergedStaticGeometry)>,
+) {
+    let arena_manager = &mut *arena_manager;
+    let Some(arena) = arena_manager.current_arena.as_mut() else {
+        return;
+    };
+    if dynamics.arena_id != Some(arena.id) {
+        dynamics.arena_id = Some(arena.id);
+        dynamics.flow = Some(LavaFlow::new(arena));
+    }
+    let Some(flow) = dynamics.flow.as_mut() else {
+        return;
+    };
+
+    let intensity = lava_spread_intensity(arena, &pressure);
+    let changes = flow.tick(arena, intensity, time.delta_secs());
+    if changes.is_empty() {
+        return;
+    }
+    let changed: HashSet<(i32, i32)> = changes.iter().map(LavaChange::cell).collect();
+
+    // Entités périmées : les modules des cellules modifiées et les blocs fusionnés qui les contiennent
+    let mut stale: Vec<Entity> = modules.iter()
+        .filter(|(_, module)| changed.contains(&ArenaManager::world_to_cell(module.original_position)))
+        .map(|(entity, _)| entity)
+        .collect();
+    let mut regrouped: Vec<(ModuleId, (i32, i32))> = Vec::new();
+    for (entity, chunk) in merged.iter() {
+        if chunk.cells.iter().any(|cell| changed.contains(cell)) {
+            stale.push(entity);
+            regrouped.extend(chunk.cells.iter()
+                .filter(|cell| !changed.contains(cell))
+                .map(|&cell| (chunk.module_id.clone(), cell)));
+        }
+    }
+
+    for &entity in &stale {
+        commands.entity(entity).despawn();
+    }
+    arena_manager.spawned_modules.retain(|entity| !stale.contains(entity));
+
+    // Refaites d'après l'arène à jour : ce que voient la validation et la minimap
+    let cells: Vec<&ArenaCell> = arena.modules.iter()
+        .filter(|cell| changed.contains(&(cell.x, cell.y))
+            || regrouped.iter().any(|(module_id, position)| *module_id == cell.module_id && *position == (cell.x, cell.y)))
+        .collect();
+    let spawned = spawn_cells(&mut commands, &cells, &mut meshes, &mut cache, &materials, &settings);
+    arena_manager.spawned_modules.extend(spawned);
+
+    let spread = changes.iter().filter(|change| matches!(change, LavaChange::Spread(..))).count();
+    info!("🌋 Lave : {} cellule(s) en fusion, {} refroidie(s)", spread, changes.len() - spread);
+}
+
+/// Dégâts continus tant que le joueur touche la lave ; deux dalles à la fois ne brûlent pas deux fois plus
+pub fn lava_damage_system(
+    time: Res<Time>,
+    lava_query: Query<(&LavaPit, &DynamicHazard)>,
+    mut player_query: Query<&mut Player>,
+    mut player_death_events: EventWriter<PlayerDeathEvent>,
+) {
+    let damage_per_second = lava_query.iter()
+        .filter(|(lava, _)| lava.touching_player)
+        .map(|(lava, hazard)| lava.damage_per_second * hazard.intensity)
+        .fold(0.0, f32::max);
+    let damage = damage_per_second * time.delta_secs();
+    if damage <= 0.0 {
+        return;
+    }
+
+    for mut player in player_query.iter_mut() {
+        // Un seul événement de mort, au moment où la santé passe à zéro
+        let was_alive = player.health > 0.0;
+        player.health -= damage;
+        if was_alive && player.health <= 0.0 {
+            info!("🔴 Joueur brûlé par la lave");
+            player_death_events.write(PlayerDeathEvent {
+                cause: DeathCause::Hazard("Lava Pit".to_string()),
+            });
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use indexmap::IndexMap;
+use crate::app::core::*;
+use crate::app::mesh_generation::*;
+use log::warn;
+
+// ============================================================================
+// GÉOMÉTRIE STATIQUE FUSIONNÉE
+// ============================================================================
+
+/// Réglages du rendu de l'arène
+#[derive(Resource, Debug, Clone)]
+pub struct ArenaVisualSettings {
+    /// Fusionner sols et murs statiques en quelques grands maillages (moins d'appels de rendu)
+    pub merge_static_geometry: bool,
+    /// Côté, en cellules, des blocs fusionnés
+    pub merge_chunk_size: u32,
+    /// Chanfrein des arêtes du dessus des dalles de sol (0.0 = arêtes vives)
+    pub floor_bevel: f32,
+    /// Palette daltonienne : dangers et collectibles se distinguent aussi par la forme
+    pub colour_blind: bool,
+    /// Durée du fondu entre deux thèmes après une mutation, en secondes
+    pub theme_fade_duration: f32,
+    /// Côté demandé des textures procédurales, ramené au budget mémoire
+    pub texture_size: u32,
+}
+
+impl ArenaVisualSettings {
+    /// Chanfrein appliqué au maillage d'un module
+    pub fn bevel_for(&self, module_id: &ModuleId) -> f32 {
+        match module_id {
+            ModuleId::FloorStd | ModuleId::FloorLarge | ModuleId::FloorFragile
+            | ModuleId::Player | ModuleId::InteractButtonFloor => self.floor_bevel,
+            _ => 0.0,
+        }
+    }
+}
+
+impl Default for ArenaVisualSettings {
+    fn default() -> Self {
+        Self {
+            // Sur mobile, chaque appel de rendu compte
+            merge_static_geometry: cfg!(target_os = "android"),
+            merge_chunk_size: 8,
+            floor_bevel: 0.02,
+            colour_blind: false,
+            theme_fade_duration: 1.5,
+            texture_size: if cfg!(target_os = "android") { 128 } else { 256 },
+        }
+    }
+}
+
+/// Bloc de cellules statiques d'un même type, rendu et simulé comme une seule entité
+#[derive(Component, Debug, Clone)]
+pub struct MergedStaticGeometry {
+    pub module_id: ModuleId,
+    pub cells: Vec<(i32, i32)>,
+}
+
+/// Dimensions des modules qui ne bougent ni ne réagissent jamais, et peuvent donc être fusionnés
+pub fn static_module_size(module_id: &ModuleId) -> Option<Vec3> {
+    match module_id {
+        ModuleId::FloorStd => Some(Vec3::new(1.0, 0.1, 1.0)),
+        ModuleId::FloorLarge => Some(Vec3::new(2.0, 0.1, 2.0)),
+        ModuleId::WallLow => Some(Vec3::new(1.0, 1.0, 1.0)),
+        ModuleId::WallHigh => Some(Vec3::new(1.0, 2.5, 1.0)),
+        _ => None,
+    }
+}
+
+/// Fusionne `cells` (toutes statiques) par type de module et par bloc de `merge_chunk_size`,
+/// chaque bloc recevant un collider composé d'un pavé par cellule
+pub fn spawn_merged_static_geometry(
+    commands: &mut Commands,
+    cells: &[&ArenaCell],
+    meshes: &mut Assets<Mesh>,
+    materials: &ModuleMaterials,
+    settings: &ArenaVisualSettings,
+) -> Vec<Entity> {
+    let chunk_size = settings.merge_chunk_size.max(1) as i32;
+
+    // IndexMap : blocs créés dans l'ordre des cellules, identique d'une partie à l'autre
+    let mut chunks: IndexMap<(ModuleId, i32, i32), Vec<&ArenaCell>> = IndexMap::new();
+    for cell in cells {
+        let key = (cell.module_id.clone(), cell.x.div_euclid(chunk_size), cell.y.div_euclid(chunk_size));
+        chunks.entry(key).or_default().push(cell);
+    }
+
+    let mut entities = Vec::new();
+    for ((module_id, _, _), chunk) in chunks {
+        let Some(size) = static_module_size(&module_id) else {
+            continue;
+        };
+
+        let bevel = settings.bevel_for(&module_id);
+        let mut mesh: Option<Mesh> = None;
+        let mut shapes = Vec::with_capacity(chunk.len());
+        for cell in &chunk {
+            let center = Vec3::new(cell.x as f32, size.y * 0.5, cell.y as f32);
+            let part = box_mesh(size, bevel).transformed_by(Transform::from_translation(center));
+            match mesh.as_mut() {
+                None => mesh = Some(part),
+                Some(merged) => {
+                    if let Err(e) = merged.merge(&part) {
+                        warn!("⚠️ Fusion impossible pour {:?}: {}", module_id, e);
+                    }
+                }
+            }
+            shapes.push((center, Quat::IDENTITY, Collider::cuboid(size.x * 0.5, size.y * 0.5, size.z * 0.5)));
+        }
+        let Some(mesh) = mesh else {
+            continue;
+        };
+
+        let entity = commands.spawn((
+            MergedStaticGeometry {
+                module_id: module_id.clone(),
+                cells: chunk.iter().map(|c| (c.x, c.y)).collect(),
+            },
+            Name::new(format!("{:?} x{}", module_id, chunk.len())),
+            Mesh3d(meshes.add(mesh)),
+            MeshMaterial3d(materials.for_module(&module_id)),
+            Transform::IDENTITY,
+            Visibility::default(),
+            RigidBody::Fixed,
+            Collider::compound(shapes),
+        )).id();
+        entities.push(entity);
+    }
+
+    entities
+}
@@ -1,17 +1,65 @@
 use bevy::prelude::*;
-use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::ecs::system::EntityCommands;
 use bevy_rapier3d::prelude::*;
 use crate::app::core::*;
-use crate::app::game::{ArenaModule, EnergyOrb, DynamicHazard, HazardType, FragileSurface, ArenaManager};
+use crate::app::game::{ArenaModule, EnergyOrb, DynamicHazard, HazardType, FragileSurface, ArenaManager, GameState};
+use crate::app::game::{PlayerSpawnPad, Teleporter, ClimbSurface, TriggerButton, Lever, LeverHandle, HazardPattern, EnemySpawner, EnergyBarrier};
+use crate::app::game::{LaserEmitter, LaserBeam, BeamPassThrough, LavaPit};
 use log::info;
 use crate::app::game::Player;
 use bevy::pbr::{Mesh3d, MeshMaterial3d};
 
+mod cache;
+mod interactive;
+mod lasers;
+mod lava;
+mod merge;
+mod projectiles;
+mod shapes;
+mod textures;
+mod theme;
+
+pub use cache::*;
+pub use interactive::*;
+pub use lasers::*;
+pub use lava::*;
+pub use merge::*;
+pub use projectiles::*;
+pub use shapes::*;
+pub use textures::*;
+pub use theme::*;
+
 pub struct MeshGenerationPlugin;
 
 impl Plugin for MeshGenerationPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<ModuleMaterials>();
+        // Les réglages d'abord : les matériaux en dépendent (mode daltonien)
+        app.init_resource::<ArenaVisualSettings>()
+            .init_resource::<ModuleMaterials>()
+            .init_resource::<MeshCache>()
+            .init_resource::<ActiveTheme>()
+            .init_resource::<ProceduralTextures>()
+            .init_resource::<ProjectileRain>()
+            .init_resource::<LavaDynamics>()
+            .add_systems(OnEnter(GameState::Playing), (prepare_arena_textures, apply_arena_theme).chain())
+            .add_systems(OnExit(GameState::Playing), reset_projectile_rain)
+            .add_systems(Update, (
+                animate_orbs_system,
+                hazard_effects_system,
+                (laser_beam_system, laser_damage_system, laser_beam_visual_system).chain(),
+                (projectile_schedule_system, projectile_spawn_system, projectile_fall_system).chain(),
+                (lava_flow_system, lava_damage_system).chain(),
+                fragile_surface_system,
+                button_reset_system,
+                barrier_trigger_system,
+                barrier_timer_system,
+                button_visual_system,
+                lever_visual_system,
+                barrier_visual_system,
+                theme_mutation_system,
+                theme_transition_system,
+                animate_module_materials_system,
+            ).chain().run_if(in_state(GameState::Playing)));
     }
 }
 
@@ -20,6 +68,8 @@ pub struct ModuleMaterials {
     pub floor_std: Handle<StandardMaterial>,
     pub floor_large: Handle<StandardMaterial>,
     pub floor_fragile: Handle<StandardMaterial>,
+    /// Dalle fragile sur le point de céder
+    pub floor_fragile_cracked: Handle<StandardMaterial>,
     pub wall_low: Handle<StandardMaterial>,
     pub wall_high: Handle<StandardMaterial>,
     pub panel_glass: Handle<StandardMaterial>,
@@ -27,88 +77,79 @@ pub struct ModuleMaterials {
     pub orb_energy: Handle<StandardMaterial>,
     pub hazard_lava: Handle<StandardMaterial>,
     pub hazard_laser: Handle<StandardMaterial>,
+    /// Rayon laser, lumineux et légèrement transparent
+    pub laser_beam: Handle<StandardMaterial>,
+    /// Bandes posées sur les dangers en mode daltonien
+    pub hazard_stripes: Handle<StandardMaterial>,
+    /// Décalque de brûlure autour des lasers
+    pub scorch_decal: Handle<StandardMaterial>,
+    /// Projectiles de la pluie, partagés par tout le pool
+    pub projectile: Handle<StandardMaterial>,
+    /// Marque au sol du point d'impact, transparente
+    pub impact_indicator: Handle<StandardMaterial>,
     pub decor_metallic: Handle<StandardMaterial>,
+    /// Teintes des arches, choisies par leur paramètre `colorVariant`
+    pub decor_metallic_variants: Vec<Handle<StandardMaterial>>,
+    pub player_spawn: Handle<StandardMaterial>,
+    /// Partagés par tous les téléporteurs : leur émission pulse en même temps
+    pub teleporter_in: Handle<StandardMaterial>,
+    pub teleporter_out: Handle<StandardMaterial>,
+    pub climb_surface: Handle<StandardMaterial>,
+    pub button: Handle<StandardMaterial>,
+    pub button_pressed: Handle<StandardMaterial>,
+    pub lever: Handle<StandardMaterial>,
+    pub lever_handle: Handle<StandardMaterial>,
+    pub enemy_spawner: Handle<StandardMaterial>,
+    /// Transparent, son opacité ondule (miroitement)
+    pub barrier_energy: Handle<StandardMaterial>,
+}
+
+impl ModuleMaterials {
+    /// Teinte `variant` des arches (l'index boucle sur les teintes disponibles)
+    pub fn arch_variant(&self, variant: u64) -> Handle<StandardMaterial> {
+        if self.decor_metallic_variants.is_empty() {
+            return self.decor_metallic.clone();
+        }
+        self.decor_metallic_variants[variant as usize % self.decor_metallic_variants.len()].clone()
+    }
+
+    /// Matériau d'un type de module
+    pub fn for_module(&self, module_id: &ModuleId) -> Handle<StandardMaterial> {
+        match module_id {
+            ModuleId::FloorLarge => self.floor_large.clone(),
+            ModuleId::FloorFragile => self.floor_fragile.clone(),
+            ModuleId::WallLow => self.wall_low.clone(),
+            ModuleId::WallHigh => self.wall_high.clone(),
+            ModuleId::PanelGlass => self.panel_glass.clone(),
+            ModuleId::RampLow | ModuleId::RampSteep => self.ramp.clone(),
+            ModuleId::OrbEnergy => self.orb_energy.clone(),
+            ModuleId::HazardLavaPit => self.hazard_lava.clone(),
+            ModuleId::HazardLaserEmitterStatic | ModuleId::HazardLaserTurretRotate => self.hazard_laser.clone(),
+            ModuleId::DecorArchMetallic => self.decor_metallic.clone(),
+            ModuleId::Player => self.player_spawn.clone(),
+            ModuleId::MoveTeleporterIn => self.teleporter_in.clone(),
+            ModuleId::MoveTeleporterOut => self.teleporter_out.clone(),
+            ModuleId::MoveClimbSurface => self.climb_surface.clone(),
+            ModuleId::InteractButtonFloor | ModuleId::InteractButtonWall => self.button.clone(),
+            ModuleId::InteractLever => self.lever.clone(),
+            ModuleId::InteractEnemySpawner => self.enemy_spawner.clone(),
+            ModuleId::InteractBarrierEnergy => self.barrier_energy.clone(),
+            ModuleId::FloorStd => self.floor_std.clone(),
+        }
+    }
 }
 
 impl FromWorld for ModuleMaterials {
     fn from_world(world: &mut World) -> Self {
-        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
-
-        Self {
-            // Couleurs pastel désaturées pour les structures de base
-            floor_std: materials.add(StandardMaterial {
-                base_color: Color::srgb(0.63, 0.73, 0.85), // #A0BBD8 - gris-bleu pâle
-                roughness: 0.8,
-                metallic: 0.1,
-                ..default()
-            }),
-            floor_large: materials.add(StandardMaterial {
-                base_color: Color::srgb(0.85, 0.79, 0.63), // #D8C9A0 - sable doux
-                roughness: 0.7,
-                metallic: 0.1,
-                ..default()
-            }),
-            floor_fragile: materials.add(StandardMaterial {
-                base_color: Color::srgb(0.85, 0.71, 0.42), // #D8B56A - moutarde claire
-                roughness: 0.9,
-                metallic: 0.0,
-                emissive: Color::srgb(0.1, 0.05, 0.0).into(),
-                ..default()
-            }),
-            wall_low: materials.add(StandardMaterial {
-                base_color: Color::srgb(0.55, 0.60, 0.70),
-                roughness: 0.9,
-                metallic: 0.2,
-                ..default()
-            }),
-            wall_high: materials.add(StandardMaterial {
-                base_color: Color::srgb(0.50, 0.55, 0.65),
-                roughness: 0.8,
-                metallic: 0.3,
-                ..default()
-            }),
-            panel_glass: materials.add(StandardMaterial {
-                base_color: Color::srgba(0.7, 0.8, 0.9, 0.3),
-                alpha_mode: AlphaMode::Blend,
-                roughness: 0.1,
-                metallic: 0.0,
-                ..default()
-            }),
-            ramp: materials.add(StandardMaterial {
-                base_color: Color::srgb(0.60, 0.65, 0.75),
-                roughness: 0.7,
-                metallic: 0.15,
-                ..default()
-            }),
-            // Couleurs vives et saturées pour les éléments interactifs
-            orb_energy: materials.add(StandardMaterial {
-                base_color: Color::srgb(0.0, 0.9, 1.0), // #00E5FF - cyan électrique
-                emissive: Color::srgb(0.0, 0.2, 0.3).into(),
-                roughness: 0.2,
-                metallic: 0.0,
-                ..default()
-            }),
-            hazard_lava: materials.add(StandardMaterial {
-                base_color: Color::srgb(1.0, 0.0, 0.67), // #FF00AA - magenta fluo
-                emissive: Color::srgb(0.3, 0.0, 0.2).into(),
-                roughness: 0.8,
-                metallic: 0.0,
-                ..default()
-            }),
-            hazard_laser: materials.add(StandardMaterial {
-                base_color: Color::srgb(1.0, 0.1, 0.1),
-                emissive: Color::srgb(0.5, 0.0, 0.0).into(),
-                roughness: 0.3,
-                metallic: 0.7,
-                ..default()
-            }),
-            decor_metallic: materials.add(StandardMaterial {
-                base_color: Color::srgb(0.8, 0.8, 0.9),
-                roughness: 0.2,
-                metallic: 0.9,
-                ..default()
-            }),
+        // Thème par défaut ; celui de l'arène est appliqué à l'entrée en partie
+        let colour_blind = world.get_resource::<ArenaVisualSettings>().is_some_and(|s| s.colour_blind);
+        let mut palette = ThemePalette::builtin(ThemeId::Pastel);
+        if colour_blind {
+            palette = palette.colour_blind();
         }
+
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        Self::from_palette(&palette, &mut materials)
     }
 }
 
@@ -116,173 +157,403 @@ pub fn spawn_arena_visuals(
     mut commands: Commands,
     mut arena_manager: ResMut<ArenaManager>,
     mut meshes: ResMut<Assets<Mesh>>,
+    mut cache: ResMut<MeshCache>,
     materials: Res<ModuleMaterials>,
+    settings: Res<ArenaVisualSettings>,
 ) {
     if let Some(ref arena) = arena_manager.current_arena {
         info!("🏗️ Génération visuelle de l'arène avec {} modules", arena.modules.len());
 
-        let mut spawned_entities = Vec::new();
+        let cells: Vec<&ArenaCell> = arena.modules.iter().collect();
+        let spawned_entities = spawn_cells(&mut commands, &cells, &mut meshes, &mut cache, &materials, &settings);
 
-        for module in &arena.modules {
-            if let Some(entity) = spawn_module_visual(
-                &mut commands,
-                module,
-                &mut meshes,
-                &materials,
-            ) {
-                spawned_entities.push(entity);
+        arena_manager.spawned_modules = spawned_entities;
+        info!("✅ {} entités visuelles créées, {} maillages partagés", arena_manager.spawned_modules.len(), cache.len());
+    }
+}
+
+/// Sols et murs fusionnés par blocs, le reste module par module
+fn spawn_cells(
+    commands: &mut Commands,
+    cells: &[&ArenaCell],
+    meshes: &mut Assets<Mesh>,
+    cache: &mut MeshCache,
+    materials: &ModuleMaterials,
+    settings: &ArenaVisualSettings,
+) -> Vec<Entity> {
+    let mut spawned_entities = Vec::new();
+
+    let (merged, individual): (Vec<&ArenaCell>, Vec<&ArenaCell>) = cells.iter().copied()
+        .partition(|cell| settings.merge_static_geometry && static_module_size(&cell.module_id).is_some());
+
+    if !merged.is_empty() {
+        spawned_entities.extend(spawn_merged_static_geometry(commands, &merged, meshes, materials, settings));
+    }
+
+    for module in individual {
+        if let Some(entity) = spawn_module_visual(commands, module, meshes, cache, materials, settings) {
+            spawned_entities.push(entity);
+        }
+    }
+
+    spawned_entities
+}
+
+/// Composants de gameplay propres à un type de module.
+/// Chaque variante est insérée avec son type concret, ce qu'un `Box<dyn Component>` ne permet pas.
+pub enum ModuleGameplay {
+    None,
+    Fragile(FragileSurface),
+    Orb(EnergyOrb),
+    Hazard(DynamicHazard),
+    Lava(LavaPit),
+    Laser(HazardType, LaserEmitter),
+    /// Laissé traverser par les rayons laser
+    BeamPassThrough,
+    SpawnPad,
+    Teleporter(Teleporter),
+    Climb(ClimbSurface),
+    Button(TriggerButton),
+    Lever(Lever),
+    Spawner(EnemySpawner),
+    Barrier(EnergyBarrier),
+}
+
+impl ModuleGameplay {
+    fn insert_into(self, entity: &mut EntityCommands) {
+        match self {
+            ModuleGameplay::None => {}
+            ModuleGameplay::Fragile(fragile) => {
+                entity.insert(fragile);
+            }
+            ModuleGameplay::Orb(orb) => {
+                entity.insert(orb);
+            }
+            ModuleGameplay::Hazard(hazard) => {
+                entity.insert(hazard);
+            }
+            ModuleGameplay::Lava(lava) => {
+                entity.insert((permanent_hazard(HazardType::LavaPit), lava));
+            }
+            ModuleGameplay::Laser(hazard_type, emitter) => {
+                entity.insert((permanent_hazard(hazard_type), emitter));
+            }
+            ModuleGameplay::BeamPassThrough => {
+                entity.insert(BeamPassThrough);
+            }
+            ModuleGameplay::SpawnPad => {
+                entity.insert(PlayerSpawnPad);
+            }
+            ModuleGameplay::Teleporter(teleporter) => {
+                entity.insert(teleporter);
+            }
+            ModuleGameplay::Climb(climb) => {
+                entity.insert(climb);
+            }
+            ModuleGameplay::Button(button) => {
+                entity.insert(button);
+            }
+            ModuleGameplay::Lever(lever) => {
+                entity.insert(lever);
+            }
+            ModuleGameplay::Spawner(spawner) => {
+                entity.insert(spawner);
+            }
+            ModuleGameplay::Barrier(barrier) => {
+                // Barrière éteinte dès le départ : ni visible, ni bloquante
+                if !barrier.is_active {
+                    entity.insert((Visibility::Hidden, ColliderDisabled));
+                }
+                entity.insert(barrier);
             }
         }
+    }
+}
 
-        arena_manager.spawned_modules = spawned_entities;
-        info!("✅ {} entités visuelles créées", arena_manager.spawned_modules.len());
+fn permanent_hazard(hazard_type: HazardType) -> DynamicHazard {
+    DynamicHazard {
+        hazard_type,
+        intensity: 1.0,
+        lifetime: 0.0,
+        max_lifetime: f32::MAX,
+    }
+}
+
+/// Tout ce qu'il faut pour faire apparaître un module
+struct ModuleSpawn {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+    /// Dimensions du maillage, le module est posé sur le sol
+    size: Vec3,
+    collider: Collider,
+    /// Traversable : déclenche les événements de collision sans bloquer le joueur
+    sensor: bool,
+    gameplay: ModuleGameplay,
+    /// Éléments enfants : poignée de levier, motif de danger
+    parts: Vec<ModulePart>,
+}
+
+/// Élément visuel enfant d'un module, sans collider propre
+struct ModulePart {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+    transform: Transform,
+    kind: ModulePartKind,
+}
+
+enum ModulePartKind {
+    LeverHandle,
+    HazardPattern,
+    LaserBeam,
+    Decal,
+}
+
+/// Hauteur des décalques : juste au-dessus des dalles de sol voisines (0.1)
+const DECAL_HEIGHT: f32 = 0.102;
+
+/// Côté des brûlures : elles débordent sur les cellules voisines
+const SCORCH_DECAL_SIZE: f32 = 1.8;
+
+impl ModuleSpawn {
+    fn solid(mesh: Handle<Mesh>, material: Handle<StandardMaterial>, size: Vec3, collider: Collider) -> Self {
+        Self { mesh, material, size, collider, sensor: false, gameplay: ModuleGameplay::None, parts: Vec::new() }
+    }
+
+    fn sensor(mut self) -> Self {
+        self.sensor = true;
+        self
+    }
+
+    fn with_gameplay(mut self, gameplay: ModuleGameplay) -> Self {
+        self.gameplay = gameplay;
+        self
+    }
+
+    fn with_part(mut self, mesh: Handle<Mesh>, material: Handle<StandardMaterial>, transform: Transform, kind: ModulePartKind) -> Self {
+        self.parts.push(ModulePart { mesh, material, transform, kind });
+        self
+    }
+
+    /// En mode daltonien, les dangers portent des bandes sombres en plus de leur couleur
+    fn with_hazard_pattern(
+        self,
+        settings: &ArenaVisualSettings,
+        meshes: &mut Assets<Mesh>,
+        cache: &mut MeshCache,
+        materials: &ModuleMaterials,
+        top_width: f32,
+    ) -> Self {
+        if !settings.colour_blind {
+            return self;
+        }
+        let mesh = cache.get_or_create(meshes, MeshKey::stripes(top_width, top_width));
+        let on_top = Transform::from_xyz(0.0, self.size.y * 0.5, 0.0);
+        self.with_part(mesh, materials.hazard_stripes.clone(), on_top, ModulePartKind::HazardPattern)
+    }
+
+    /// Émetteur laser et son rayon visible, étiré ensuite à la longueur coupée par le lancer de rayon
+    fn with_laser(
+        self,
+        hazard_type: HazardType,
+        emitter: LaserEmitter,
+        meshes: &mut Assets<Mesh>,
+        cache: &mut MeshCache,
+        materials: &ModuleMaterials,
+    ) -> Self {
+        let mesh = cache.cube(meshes, 1.0, BEAM_THICKNESS, BEAM_THICKNESS);
+        let transform = beam_transform(self.size.y, emitter.current_length);
+        self.with_gameplay(ModuleGameplay::Laser(hazard_type, emitter))
+            .with_part(mesh, materials.laser_beam.clone(), transform, ModulePartKind::LaserBeam)
+    }
+
+    /// Brûlure au sol autour d'un émetteur fixe ; son orientation dépend de la cellule,
+    /// donc deux émetteurs voisins ne portent pas la même tache
+    fn with_scorch_decal(self, cell: &ArenaCell, meshes: &mut Assets<Mesh>, cache: &mut MeshCache, materials: &ModuleMaterials) -> Self {
+        let mesh = cache.get_or_create(meshes, MeshKey::decal(SCORCH_DECAL_SIZE));
+        let turn = (cell.x * 7 + cell.y * 13).rem_euclid(8) as f32 * std::f32::consts::FRAC_PI_4;
+        let on_ground = Transform::from_xyz(0.0, DECAL_HEIGHT - self.size.y * 0.5, 0.0)
+            .with_rotation(Quat::from_rotation_y(turn));
+        self.with_part(mesh, materials.scorch_decal.clone(), on_ground, ModulePartKind::Decal)
     }
 }
 
+/// Paramètre `key` de la cellule ; absent ou `null` : la valeur par défaut du module s'applique
+fn module_param<'a>(cell: &'a ArenaCell, key: &str) -> Option<&'a serde_json::Value> {
+    cell.module_params.as_ref()?.get(key).filter(|v| !v.is_null())
+}
+
+fn param_f32(cell: &ArenaCell, key: &str, default: f32) -> f32 {
+    module_param(cell, key).and_then(|v| v.as_f64()).map_or(default, |v| v as f32)
+}
+
+fn param_bool(cell: &ArenaCell, key: &str, default: bool) -> bool {
+    module_param(cell, key).and_then(|v| v.as_bool()).unwrap_or(default)
+}
+
+/// Identifiant de liaison ou de déclencheur, écrit en texte ou en nombre
+fn param_id(cell: &ArenaCell, key: &str) -> Option<String> {
+    module_param(cell, key).map(|v| match v.as_str() {
+        Some(s) => s.to_string(),
+        None => v.to_string(),
+    })
+}
+
+/// Dalle au ras du sol, chanfreinée comme les sols
+fn floor_tile_spawn(
+    cell: &ArenaCell,
+    meshes: &mut Assets<Mesh>,
+    cache: &mut MeshCache,
+    materials: &ModuleMaterials,
+    settings: &ArenaVisualSettings,
+) -> ModuleSpawn {
+    let size = Vec3::new(1.0, 0.1, 1.0);
+    ModuleSpawn::solid(
+        cache.get_or_create(meshes, MeshKey::beveled(size, settings.bevel_for(&cell.module_id))),
+        materials.for_module(&cell.module_id),
+        size,
+        Collider::cuboid(0.5, 0.05, 0.5),
+    )
+}
+
+/// Sol ou mur : un pavé partagé par toutes les cellules du même type.
+/// Le collider reste un pavé : le chanfrein des dalles est purement visuel.
+fn static_module_spawn(
+    cell: &ArenaCell,
+    meshes: &mut Assets<Mesh>,
+    cache: &mut MeshCache,
+    materials: &ModuleMaterials,
+    settings: &ArenaVisualSettings,
+) -> Option<ModuleSpawn> {
+    let size = static_module_size(&cell.module_id)?;
+    Some(ModuleSpawn::solid(
+        cache.get_or_create(meshes, MeshKey::beveled(size, settings.bevel_for(&cell.module_id))),
+        materials.for_module(&cell.module_id),
+        size,
+        Collider::cuboid(size.x * 0.5, size.y * 0.5, size.z * 0.5),
+    ))
+}
+
 fn spawn_module_visual(
     commands: &mut Commands,
     cell: &ArenaCell,
     meshes: &mut Assets<Mesh>,
+    cache: &mut MeshCache,
     materials: &ModuleMaterials,
+    settings: &ArenaVisualSettings,
 ) -> Option<Entity> {
     let position = Vec3::new(cell.x as f32, 0.0, cell.y as f32);
 
-    let (mesh, material, scale, collider, additional_components) = match cell.module_id {
-        ModuleId::FloorStd => (
-            create_cube_mesh(meshes, 1.0, 0.1, 1.0),
-            materials.floor_std.clone(),
-            Vec3::new(1.0, 0.1, 1.0),
-            Collider::cuboid(0.5, 0.05, 0.5),
-            vec![]
-        ),
-
-        ModuleId::FloorLarge => (
-            create_cube_mesh(meshes, 2.0, 0.1, 2.0),
-            materials.floor_large.clone(),
-            Vec3::new(2.0, 0.1, 2.0),
-            Collider::cuboid(1.0, 0.05, 1.0),
-            vec![]
-        ),
-
-        ModuleId::FloorFragile => {
-            let params = cell.module_params.as_ref()
-                .and_then(|p| p.as_object())
-                .map(|obj| (
-                    obj.get("breakDelay").and_then(|v| v.as_f64()).unwrap_or(0.5) as f32,
-                    obj.get("respawnDelay").and_then(|v| v.as_f64()).unwrap_or(5.0) as f32,
-                )).unwrap_or((0.5, 5.0));
-
-            (
-                create_cube_mesh(meshes, 1.0, 0.1, 1.0),
-                materials.floor_fragile.clone(),
-                Vec3::new(1.0, 0.1, 1.0),
-                Collider::cuboid(0.5, 0.05, 0.5),
-                vec![Box::new(FragileSurface {
-                    break_delay: params.0,
-                    respawn_delay: params.1,
-                    is_breaking: false,
-                    break_timer: 0.0,
-                    is_broken: false,
-                    respawn_timer: 0.0,
-                }) as Box<dyn Component>]
-            )
-        },
+    let spawn = match cell.module_id {
+        ModuleId::FloorStd | ModuleId::FloorLarge => static_module_spawn(cell, meshes, cache, materials, settings)?,
+
+        ModuleId::FloorFragile => floor_tile_spawn(cell, meshes, cache, materials, settings)
+            .with_gameplay(ModuleGameplay::Fragile(FragileSurface {
+                break_delay: param_f32(cell, "breakDelay", 0.5),
+                respawn_delay: param_f32(cell, "respawnDelay", 5.0),
+                is_breaking: false,
+                break_timer: 0.0,
+                is_broken: false,
+                respawn_timer: 0.0,
+            })),
+
+        ModuleId::WallLow | ModuleId::WallHigh => static_module_spawn(cell, meshes, cache, materials, settings)?,
 
-        ModuleId::WallLow => (
-            create_cube_mesh(meshes, 1.0, 1.0, 1.0),
-            materials.wall_low.clone(),
-            Vec3::new(1.0, 1.0, 1.0),
-            Collider::cuboid(0.5, 0.5, 0.5),
-            vec![]
-        ),
-
-        ModuleId::WallHigh => (
-            create_cube_mesh(meshes, 1.0, 2.5, 1.0),
-            materials.wall_high.clone(),
-            Vec3::new(1.0, 2.5, 1.0),
-            Collider::cuboid(0.5, 1.25, 0.5),
-            vec![]
-        ),
-
-        ModuleId::PanelGlass => (
-            create_cube_mesh(meshes, 1.0, 2.0, 0.1),
+        ModuleId::PanelGlass => ModuleSpawn::solid(
+            cache.cube(meshes, 1.0, 2.0, 0.1),
             materials.panel_glass.clone(),
             Vec3::new(1.0, 2.0, 0.1),
             Collider::cuboid(0.5, 1.0, 0.05),
-            vec![]
-        ),
+        ).with_gameplay(ModuleGameplay::BeamPassThrough),
 
         ModuleId::RampLow | ModuleId::RampSteep => {
-            let angle = cell.module_params.as_ref()
+            // Pente par défaut de la définition du module
+            let default_angle = if cell.module_id == ModuleId::RampSteep { 45.0 } else { 30.0 };
+            let angle = (cell.module_params.as_ref()
                 .and_then(|p| p.get("angle"))
                 .and_then(|v| v.as_f64())
-                .unwrap_or(30.0) as f32;
+                .unwrap_or(default_angle) as f32)
+                .to_radians();
 
-            (
-                create_ramp_mesh(meshes, 1.0, angle.to_radians()),
+            ModuleSpawn::solid(
+                cache.get_or_create(meshes, MeshKey::ramp(1.0, angle)),
                 materials.ramp.clone(),
-                Vec3::new(1.0, 0.5, 1.0),
-                Collider::cuboid(0.5, 0.25, 0.5), // Simplifié pour le moment
-                vec![]
+                Vec3::new(1.0, ramp_height(1.0, angle), 1.0),
+                ramp_collider(1.0, angle),
             )
         },
 
+        // L'orbe se traverse : le contact le collecte
         ModuleId::OrbEnergy => {
             let time_value = cell.module_params.as_ref()
                 .and_then(|p| p.get("timeValue"))
                 .and_then(|v| v.as_f64())
                 .unwrap_or(5.0) as f32;
 
-            (
-                create_sphere_mesh(meshes, 0.3, 16),
+            // En mode daltonien, l'orbe devient un diamant : sa forme le distingue des dangers
+            let orb_mesh = if settings.colour_blind {
+                MeshKey::octahedron(0.35)
+            } else {
+                MeshKey::sphere(0.3, 16)
+            };
+
+            ModuleSpawn::solid(
+                cache.get_or_create(meshes, orb_mesh),
                 materials.orb_energy.clone(),
-                Vec3::new(0.3, 0.3, 0.3),
+                Vec3::new(0.6, 0.6, 0.6),
                 Collider::ball(0.3),
-                vec![Box::new(EnergyOrb {
-                    time_value,
-                    collected: false,
-                }) as Box<dyn Component>]
-            )
+            ).sensor().with_gameplay(ModuleGameplay::Orb(EnergyOrb {
+                time_value,
+                collected: false,
+            }))
         },
 
-        ModuleId::HazardLavaPit => (
-            create_cube_mesh(meshes, 1.0, 0.05, 1.0),
+        ModuleId::HazardLavaPit => ModuleSpawn::solid(
+            cache.cube(meshes, 1.0, 0.05, 1.0),
             materials.hazard_lava.clone(),
             Vec3::new(1.0, 0.05, 1.0),
             Collider::cuboid(0.5, 0.025, 0.5),
-            vec![Box::new(DynamicHazard {
-                hazard_type: HazardType::LavaPit,
-                intensity: 1.0,
-                lifetime: 0.0,
-                max_lifetime: f32::MAX,
-            }) as Box<dyn Component>]
-        ),
-
-        ModuleId::HazardLaserEmitterStatic => (
-            create_cube_mesh(meshes, 0.5, 1.5, 0.5),
-            materials.hazard_laser.clone(),
-            Vec3::new(0.5, 1.5, 0.5),
-            Collider::cuboid(0.25, 0.75, 0.25),
-            vec![Box::new(DynamicHazard {
-                hazard_type: HazardType::LaserBeam,
-                intensity: 1.0,
-                lifetime: 0.0,
-                max_lifetime: f32::MAX,
-            }) as Box<dyn Component>]
-        ),
-
-        ModuleId::HazardLaserTurretRotate => (
-            create_cylinder_mesh(meshes, 0.4, 1.0, 12),
-            materials.hazard_laser.clone(),
-            Vec3::new(0.4, 1.0, 0.4),
-            Collider::cylinder(0.5, 0.4),
-            vec![Box::new(DynamicHazard {
-                hazard_type: HazardType::RotatingTurret,
-                intensity: 1.0,
-                lifetime: 0.0,
-                max_lifetime: f32::MAX,
-            }) as Box<dyn Component>]
-        ),
+        ).with_gameplay(ModuleGameplay::Lava(LavaPit {
+            damage_per_second: param_f32(cell, "damagePerSecond", 25.0),
+            touching_player: false,
+        }))
+            .with_hazard_pattern(settings, meshes, cache, materials, 1.0),
+
+        // Rayon fixe dans la direction du paramètre `direction` (degrés, 0 = +X)
+        ModuleId::HazardLaserEmitterStatic => {
+            let emitter = laser_emitter(
+                param_f32(cell, "beamLength", 20.0),
+                param_f32(cell, "damagePerSecond", 30.0),
+                0.0,
+                0.0,
+                param_f32(cell, "direction", 0.0),
+            );
+            ModuleSpawn::solid(
+                cache.cube(meshes, 0.5, 1.5, 0.5),
+                materials.hazard_laser.clone(),
+                Vec3::new(0.5, 1.5, 0.5),
+                Collider::cuboid(0.25, 0.75, 0.25),
+            ).with_laser(HazardType::LaserBeam, emitter, meshes, cache, materials)
+                .with_hazard_pattern(settings, meshes, cache, materials, 0.5)
+                .with_scorch_decal(cell, meshes, cache, materials)
+        },
+
+        // Balaie `arc` degrés autour de `direction`, à `rotationSpeed` degrés par seconde
+        ModuleId::HazardLaserTurretRotate => {
+            let emitter = laser_emitter(
+                param_f32(cell, "beamLength", 20.0),
+                param_f32(cell, "damagePerSecond", 40.0),
+                param_f32(cell, "rotationSpeed", 45.0),
+                param_f32(cell, "arc", 180.0),
+                param_f32(cell, "direction", 0.0),
+            );
+            ModuleSpawn::solid(
+                cache.get_or_create(meshes, MeshKey::cylinder(0.4, 1.0, 12)),
+                materials.hazard_laser.clone(),
+                Vec3::new(0.8, 1.0, 0.8),
+                Collider::cylinder(0.5, 0.4),
+            ).with_laser(HazardType::RotatingTurret, emitter, meshes, cache, materials)
+                .with_hazard_pattern(settings, meshes, cache, materials, 0.5)
+        },
 
         ModuleId::DecorArchMetallic => {
             let color_variant = cell.module_params.as_ref()
@@ -290,261 +561,154 @@ fn spawn_module_visual(
                 .and_then(|v| v.as_u64())
                 .unwrap_or(3);
 
-            (
-                create_arch_mesh(meshes, 2.0, 3.0),
-                materials.decor_metallic.clone(),
+            ModuleSpawn::solid(
+                cache.get_or_create(meshes, MeshKey::arch(2.0, 3.0, 0.3)),
+                materials.arch_variant(color_variant),
                 Vec3::new(2.0, 3.0, 0.3),
-                Collider::cuboid(1.0, 1.5, 0.15),
-                vec![]
+                arch_collider(2.0, 3.0, 0.3),
+            )
+        },
+
+        ModuleId::Player => floor_tile_spawn(cell, meshes, cache, materials, settings)
+            .with_gameplay(ModuleGameplay::SpawnPad),
+
+        ModuleId::MoveTeleporterIn | ModuleId::MoveTeleporterOut => ModuleSpawn::solid(
+            cache.get_or_create(meshes, MeshKey::cylinder(0.45, 0.15, 24)),
+            materials.for_module(&cell.module_id),
+            Vec3::new(0.9, 0.15, 0.9),
+            Collider::cylinder(0.075, 0.45),
+        ).with_gameplay(ModuleGameplay::Teleporter(Teleporter {
+            link_id: param_id(cell, "linkId"),
+            is_entry: cell.module_id == ModuleId::MoveTeleporterIn,
+        })),
+
+        ModuleId::MoveClimbSurface => ModuleSpawn::solid(
+            cache.cube(meshes, 1.0, 2.5, 0.2),
+            materials.climb_surface.clone(),
+            Vec3::new(1.0, 2.5, 0.2),
+            Collider::cuboid(0.5, 1.25, 0.1),
+        ).with_gameplay(ModuleGameplay::Climb(ClimbSurface {
+            climb_speed: param_f32(cell, "climbSpeed", 3.0),
+        })),
+
+        // Le bouton au sol s'enfonce sous le joueur
+        ModuleId::InteractButtonFloor => floor_tile_spawn(cell, meshes, cache, materials, settings)
+            .with_gameplay(ModuleGameplay::Button(TriggerButton {
+                trigger_id: param_id(cell, "triggerId"),
+                wall_mounted: false,
+                one_time: param_bool(cell, "oneTime", false),
+                reset_delay: param_f32(cell, "resetDelay", 0.5),
+                is_pressed: false,
+                reset_timer: 0.0,
+            })),
+
+        // Le bouton mural s'actionne par interaction ou par un tir, il ne revient pas
+        ModuleId::InteractButtonWall => ModuleSpawn::solid(
+            cache.cube(meshes, 0.3, 1.2, 0.3),
+            materials.button.clone(),
+            Vec3::new(0.3, 1.2, 0.3),
+            Collider::cuboid(0.15, 0.6, 0.15),
+        ).with_gameplay(ModuleGameplay::Button(TriggerButton {
+            trigger_id: param_id(cell, "triggerId"),
+            wall_mounted: true,
+            one_time: true,
+            reset_delay: 0.0,
+            is_pressed: false,
+            reset_timer: 0.0,
+        })),
+
+        ModuleId::InteractLever => {
+            let is_on = param_bool(cell, "startsOn", false);
+            ModuleSpawn::solid(
+                cache.cube(meshes, 0.4, 0.2, 0.4),
+                materials.lever.clone(),
+                Vec3::new(0.4, 0.2, 0.4),
+                Collider::cuboid(0.2, 0.1, 0.2),
+            ).with_gameplay(ModuleGameplay::Lever(Lever {
+                trigger_id: param_id(cell, "triggerId"),
+                is_on,
+            })).with_part(
+                cache.cube(meshes, LEVER_HANDLE_SIZE.x, LEVER_HANDLE_SIZE.y, LEVER_HANDLE_SIZE.z),
+                materials.lever_handle.clone(),
+                lever_handle_transform(0.2, is_on),
+                ModulePartKind::LeverHandle,
             )
         },
 
-        _ => return None, // Module non supporté pour l'instant
+        // Socle hexagonal : un cylindre à six pans
+        ModuleId::InteractEnemySpawner => ModuleSpawn::solid(
+            cache.get_or_create(meshes, MeshKey::cylinder(0.45, 0.3, 6)),
+            materials.enemy_spawner.clone(),
+            Vec3::new(0.9, 0.3, 0.9),
+            Collider::cylinder(0.15, 0.45),
+        ).with_gameplay(ModuleGameplay::Spawner(EnemySpawner {
+            enemy_type: module_param(cell, "enemyType")
+                .and_then(|v| v.as_str())
+                .unwrap_or("ENEMY_TYPE_BASIC_ROBOT")
+                .to_string(),
+            spawn_limit: module_param(cell, "spawnLimit").and_then(|v| v.as_u64()).unwrap_or(3) as u32,
+            spawn_radius: param_f32(cell, "spawnRadius", 2.0),
+            activation_delay: param_f32(cell, "activationDelay", 0.5),
+            trigger_id: param_id(cell, "triggerId"),
+        })),
+
+        ModuleId::InteractBarrierEnergy => ModuleSpawn::solid(
+            cache.cube(meshes, 1.0, 2.0, 0.1),
+            materials.barrier_energy.clone(),
+            Vec3::new(1.0, 2.0, 0.1),
+            Collider::cuboid(0.5, 1.0, 0.05),
+        ).with_gameplay(ModuleGameplay::Barrier(EnergyBarrier {
+            health: param_f32(cell, "health", 100.0),
+            disable_on_trigger_id: param_id(cell, "disableOnTriggerId"),
+            disable_duration: param_f32(cell, "disableDuration", 5.0),
+            is_active: param_bool(cell, "startActive", true),
+            disabled_timer: 0.0,
+        })),
     };
 
-    let final_position = position + Vec3::new(0.0, scale.y * 0.5, 0.0);
+    // Le maillage et le collider sont déjà à la bonne taille : pas d'échelle sur le Transform,
+    // sinon Rapier l'appliquerait une seconde fois au collider
+    let final_position = position + Vec3::new(0.0, spawn.size.y * 0.5, 0.0);
 
     let mut entity_commands = commands.spawn((
-        Mesh3d(mesh_handle.clone()),
-        MeshMaterial3d(material_handle.clone()),
-        Transform::from_translation(final_position).with_scale(scale),
-        GlobalTransform::IDENTITY,
+        ArenaModule {
+            module_id: cell.module_id.clone(),
+            original_position: final_position,
+        },
+        Name::new(format!("{:?} ({}, {})", cell.module_id, cell.x, cell.y)),
+        Mesh3d(spawn.mesh),
+        MeshMaterial3d(spawn.material),
+        Transform::from_translation(final_position),
         Visibility::default(),
-        ComputedVisibility::default(),
+        RigidBody::Fixed,
+        spawn.collider,
     ));
 
-    // Ajouter les composants supplémentaires
-    for component in additional_components {
-        // Note: En Rust, nous ne pouvons pas facilement ajouter des composants dynamiquement
-        // Cette partie nécessiterait une approche différente avec des enums ou traits
-        // Pour l'instant, on va gérer cela manuellement dans les cas spécifiques
+    if spawn.sensor {
+        entity_commands.insert(Sensor);
     }
 
-    Some(entity_commands.id())
-}
-
-// ============================================================================
-// FONCTIONS DE CRÉATION DE MAILLAGES
-// ============================================================================
-
-fn create_cube_mesh(meshes: &mut Assets<Mesh>, width: f32, height: f32, depth: f32) -> Handle<Mesh> {
-    let w = width * 0.5;
-    let h = height * 0.5;
-    let d = depth * 0.5;
-
-    let vertices = vec![
-        // Face avant (z+)
-        [-w, -h,  d], [ w, -h,  d], [ w,  h,  d], [-w,  h,  d],
-        // Face arrière (z-)
-        [ w, -h, -d], [-w, -h, -d], [-w,  h, -d], [ w,  h, -d],
-        // Face droite (x+)
-        [ w, -h,  d], [ w, -h, -d], [ w,  h, -d], [ w,  h,  d],
-        // Face gauche (x-)
-        [-w, -h, -d], [-w, -h,  d], [-w,  h,  d], [-w,  h, -d],
-        // Face haut (y+)
-        [-w,  h,  d], [ w,  h,  d], [ w,  h, -d], [-w,  h, -d],
-        // Face bas (y-)
-        [-w, -h, -d], [ w, -h, -d], [ w, -h,  d], [-w, -h,  d],
-    ];
-
-    let indices = vec![
-        0,1,2, 0,2,3,       // avant
-        4,5,6, 4,6,7,       // arrière
-        8,9,10, 8,10,11,    // droite
-        12,13,14, 12,14,15, // gauche
-        16,17,18, 16,18,19, // haut
-        20,21,22, 20,22,23, // bas
-    ];
-
-    let normals = vec![
-        // Face avant
-        [0., 0., 1.], [0., 0., 1.], [0., 0., 1.], [0., 0., 1.],
-        // Face arrière
-        [0., 0., -1.], [0., 0., -1.], [0., 0., -1.], [0., 0., -1.],
-        // Face droite
-        [1., 0., 0.], [1., 0., 0.], [1., 0., 0.], [1., 0., 0.],
-        // Face gauche
-        [-1., 0., 0.], [-1., 0., 0.], [-1., 0., 0.], [-1., 0., 0.],
-        // Face haut
-        [0., 1., 0.], [0., 1., 0.], [0., 1., 0.], [0., 1., 0.],
-        // Face bas
-        [0., -1., 0.], [0., -1., 0.], [0., -1., 0.], [0., -1., 0.],
-    ];
-
-    let uvs = vec![
-        // Répéter les UVs pour chaque face
-        [0., 0.], [1., 0.], [1., 1.], [0., 1.],
-        [0., 0.], [1., 0.], [1., 1.], [0., 1.],
-        [0., 0.], [1., 0.], [1., 1.], [0., 1.],
-        [0., 0.], [1., 0.], [1., 1.], [0., 1.],
-        [0., 0.], [1., 0.], [1., 1.], [0., 1.],
-        [0., 0.], [1., 0.], [1., 1.], [0., 1.],
-    ];
-
-    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, default());
-    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertices);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
-    mesh.insert_indices(Indices::U32(indices));
-
-    meshes.add(mesh)
-}
-
-fn create_sphere_mesh(meshes: &mut Assets<Mesh>, radius: f32, resolution: usize) -> Handle<Mesh> {
-    let mut vertices = Vec::new();
-    let mut indices = Vec::new();
-    let mut normals = Vec::new();
-    let mut uvs = Vec::new();
-
-    // Génération des vertices de la sphère
-    for i in 0..=resolution {
-        let theta = i as f32 * std::f32::consts::PI / resolution as f32;
-        let sin_theta = theta.sin();
-        let cos_theta = theta.cos();
-
-        for j in 0..=resolution {
-            let phi = j as f32 * 2.0 * std::f32::consts::PI / resolution as f32;
-            let sin_phi = phi.sin();
-            let cos_phi = phi.cos();
-
-            let x = radius * sin_theta * cos_phi;
-            let y = radius * cos_theta;
-            let z = radius * sin_theta * sin_phi;
-
-            vertices.push([x, y, z]);
-            normals.push([x / radius, y / radius, z / radius]);
-            uvs.push([j as f32 / resolution as f32, i as f32 / resolution as f32]);
-        }
-    }
-
-    // Génération des indices
-    for i in 0..resolution {
-        for j in 0..resolution {
-            let first = i * (resolution + 1) + j;
-            let second = first + resolution + 1;
-
-            indices.extend_from_slice(&[
-                first as u32, second as u32, (first + 1) as u32,
-                second as u32, (second + 1) as u32, (first + 1) as u32,
-            ]);
-        }
-    }
-
-    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, default());
-    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertices);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
-    mesh.insert_indices(Indices::U32(indices));
-
-    meshes.add(mesh)
-}
-
-fn create_cylinder_mesh(meshes: &mut Assets<Mesh>, radius: f32, height: f32, resolution: usize) -> Handle<Mesh> {
-    let mut vertices = Vec::new();
-    let mut indices = Vec::new();
-    let mut normals = Vec::new();
-    let mut uvs = Vec::new();
-
-    let half_height = height * 0.5;
-
-    // Vertices du haut et du bas
-    for i in 0..resolution {
-        let angle = i as f32 * 2.0 * std::f32::consts::PI / resolution as f32;
-        let x = radius * angle.cos();
-        let z = radius * angle.sin();
-
-        // Vertex du haut
-        vertices.push([x, half_height, z]);
-        normals.push([x / radius, 0.0, z / radius]);
-        uvs.push([angle / (2.0 * std::f32::consts::PI), 1.0]);
-
-        // Vertex du bas
-        vertices.push([x, -half_height, z]);
-        normals.push([x / radius, 0.0, z / radius]);
-        uvs.push([angle / (2.0 * std::f32::consts::PI), 0.0]);
-    }
-
-    // Indices pour les faces latérales
-    for i in 0..resolution {
-        let next = (i + 1) % resolution;
-
-        let top_current = i * 2;
-        let bottom_current = i * 2 + 1;
-        let top_next = next * 2;
-        let bottom_next = next * 2 + 1;
-
-        // Triangles de la face latérale
-        indices.extend_from_slice(&[
-            top_current as u32, bottom_current as u32, top_next as u32,
-            bottom_current as u32, bottom_next as u32, top_next as u32,
-        ]);
+    if !spawn.parts.is_empty() {
+        entity_commands.with_children(|parent| {
+            for part in spawn.parts {
+                let mut child = parent.spawn((
+                    Mesh3d(part.mesh),
+                    MeshMaterial3d(part.material),
+                    part.transform,
+                    Visibility::default(),
+                ));
+                match part.kind {
+                    ModulePartKind::LeverHandle => { child.insert(LeverHandle); },
+                    ModulePartKind::HazardPattern => { child.insert(HazardPattern); },
+                    ModulePartKind::LaserBeam => { child.insert(LaserBeam); },
+                    ModulePartKind::Decal => {},
+                }
+            }
+        });
     }
+    spawn.gameplay.insert_into(&mut entity_commands);
 
-    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, default());
-    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertices);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
-    mesh.insert_indices(Indices::U32(indices));
-
-    meshes.add(mesh)
-}
-
-fn create_ramp_mesh(meshes: &mut Assets<Mesh>, size: f32, angle: f32) -> Handle<Mesh> {
-    let half_size = size * 0.5;
-    let height = (size * angle.tan()).max(0.1);
-
-    let vertices = vec![
-        // Face inclinée (dessus)
-        [-half_size, 0.0, -half_size],
-        [half_size, 0.0, -half_size],
-        [half_size, height, half_size],
-        [-half_size, height, half_size],
-        // Face avant (verticale)
-        [-half_size, 0.0, half_size],
-        [half_size, 0.0, half_size],
-        [half_size, height, half_size],
-        [-half_size, height, half_size],
-    ];
-
-    let indices = vec![
-        // Face inclinée
-        0, 1, 2, 0, 2, 3,
-        // Face avant
-        4, 6, 5, 4, 7, 6,
-    ];
-
-    let inclined_normal = [0.0, angle.cos(), -angle.sin()];
-    let front_normal = [0.0, 0.0, 1.0];
-    
-    let normals = vec![
-        // Face inclinée (4 vertices)
-        inclined_normal, inclined_normal, inclined_normal, inclined_normal,
-        // Face avant (4 vertices)
-        front_normal, front_normal, front_normal, front_normal,
-    ];
-
-    let uvs = vec![
-        [0., 0.], [1., 0.], [1., 1.], [0., 1.],
-        [0., 0.], [1., 0.], [1., 1.], [0., 1.],
-    ];
-
-    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, default());
-    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertices);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
-    mesh.insert_indices(Indices::U32(indices));
-
-    meshes.add(mesh)
-}
-
-fn create_arch_mesh(meshes: &mut Assets<Mesh>, width: f32, height: f32) -> Handle<Mesh> {
-    // Maillage simplifié d'une arche (deux piliers + arc)
-    let pillar_width = width * 0.1;
-    let arch_thickness = width * 0.05;
-
-    // Pour l'instant, on crée un maillage rectangulaire simple
-    // Dans une version plus avancée, on pourrait créer une vraie forme d'arche
-    create_cube_mesh(meshes, width, height, arch_thickness)
+    Some(entity_commands.id())
 }
 
 // ============================================================================
@@ -553,15 +717,15 @@ fn create_arch_mesh(meshes: &mut Assets<Mesh>, width: f32, height: f32) -> Handl
 
 pub fn animate_orbs_system(
     time: Res<Time>,
-    mut orb_query: Query<(&mut Transform, &EnergyOrb), (With<EnergyOrb>, Without<Player>)>,
+    mut orb_query: Query<(&mut Transform, &EnergyOrb, &ArenaModule), (With<EnergyOrb>, Without<Player>)>,
 ) {
     let time_secs = time.elapsed_seconds();
 
-    for (mut transform, orb) in orb_query.iter_mut() {
+    for (mut transform, orb, module) in orb_query.iter_mut() {
         if !orb.collected {
-            // Rotation et flottement
+            // Rotation et flottement autour de sa position d'origine
             transform.rotation = Quat::from_rotation_y(time_secs * 2.0);
-            transform.translation.y = orb.time_value * 0.5 + (time_secs * 3.0).sin() * 0.2;
+            transform.translation.y = module.original_position.y + (time_secs * 3.0).sin() * 0.2;
         }
     }
 }
@@ -572,18 +736,12 @@ pub fn hazard_effects_system(
 ) {
     let time_secs = time.elapsed_seconds();
 
+    // Les tourelles laser sont orientées par `laser_beam_system`
     for (mut transform, hazard) in hazard_query.iter_mut() {
-        match hazard.hazard_type {
-            HazardType::LavaPit => {
-                // Effet de pulsation pour la lave
-                let pulse = (time_secs * 4.0).sin() * 0.1 + 1.0;
-                transform.scale.y = pulse;
-            },
-            HazardType::RotatingTurret => {
-                // Rotation constante
-                transform.rotation = Quat::from_rotation_y(time_secs * 1.5);
-            },
-            _ => {}
+        if matches!(hazard.hazard_type, HazardType::LavaPit) {
+            // Effet de pulsation pour la lave
+            let pulse = (time_secs * 4.0).sin() * 0.1 + 1.0;
+            transform.scale.y = pulse;
         }
     }
 }
@@ -591,37 +749,43 @@ pub fn hazard_effects_system(
 pub fn fragile_surface_system(
     time: Res<Time>,
     mut commands: Commands,
-    mut fragile_query: Query<(Entity, &mut FragileSurface, &mut Visibility), With<FragileSurface>>,
-    player_query: Query<&Transform, With<Player>>,
+    materials: Res<ModuleMaterials>,
+    mut fragile_query: Query<(Entity, &mut FragileSurface, &ArenaModule, &mut Transform, &mut Visibility, &mut MeshMaterial3d<StandardMaterial>)>,
 ) {
-    let dt = time.delta_seconds();
-
-    if let Ok(player_transform) = player_query.get_single() {
-        for (entity, mut fragile, mut visibility) in fragile_query.iter_mut() {
-            if fragile.is_broken {
-                fragile.respawn_timer += dt;
-                if fragile.respawn_timer >= fragile.respawn_delay {
-                    // Réapparaître
-                    fragile.is_broken = false;
-                    fragile.respawn_timer = 0.0;
-                    fragile.is_breaking = false;
-                    fragile.break_timer = 0.0;
-                    *visibility = Visibility::Visible;
-                }
-            } else if fragile.is_breaking {
-                fragile.break_timer += dt;
-                if fragile.break_timer >= fragile.break_delay {
-                    // Casser
-                    fragile.is_broken = true;
-                    fragile.respawn_timer = 0.0;
-                    *visibility = Visibility::Hidden;
-
-                    // Retirer le collider
-                    commands.entity(entity).remove::<Collider>();
+    let dt = time.delta_secs();
+
+    for (entity, mut fragile, module, mut transform, mut visibility, mut material) in fragile_query.iter_mut() {
+        if fragile.is_broken {
+            fragile.respawn_timer += dt;
+            if fragile.respawn_timer >= fragile.respawn_delay {
+                // Réapparaître, intacte et de nouveau solide
+                fragile.is_broken = false;
+                fragile.respawn_timer = 0.0;
+                fragile.is_breaking = false;
+                fragile.break_timer = 0.0;
+                *visibility = Visibility::Inherited;
+                material.0 = materials.floor_fragile.clone();
+                commands.entity(entity).remove::<ColliderDisabled>();
+            }
+        } else if fragile.is_breaking {
+            fragile.break_timer += dt;
+            if fragile.break_timer >= fragile.break_delay {
+                // Casser : invisible et traversable jusqu'à la réapparition
+                fragile.is_broken = true;
+                fragile.respawn_timer = 0.0;
+                transform.translation = module.original_position;
+                *visibility = Visibility::Hidden;
+                commands.entity(entity).insert(ColliderDisabled);
+            } else {
+                // Fissurée : elle tremble de plus en plus fort avant de céder
+                let progress = (fragile.break_timer / fragile.break_delay.max(f32::EPSILON)).min(1.0);
+                let shake = (fragile.break_timer * 60.0).sin() * 0.03 * progress;
+                transform.translation = module.original_position + Vec3::new(shake, 0.0, -shake);
+                if material.0 != materials.floor_fragile_cracked {
+                    material.0 = materials.floor_fragile_cracked.clone();
                 }
             }
-            // Note: La détection de collision avec le joueur serait gérée
-            // par un système de détection de collision séparé
         }
+        // Le contact du joueur déclenche la rupture (`player_collision_system`)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,255 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use crate::app::core::*;
+use crate::app::core::gameplay::DifficultySystem;
+use crate::app::game::{ArenaManager, Projectile, ImpactIndicator};
+use crate::app::mesh_generation::*;
+use uuid::Uuid;
+use log::info;
+
+// ============================================================================
+// PLUIE DE PROJECTILES
+// ============================================================================
+
+/// Altitude de largage au-dessus du point d'impact
+pub const DROP_HEIGHT: f32 = 12.0;
+
+pub const PROJECTILE_RADIUS: f32 = 0.3;
+
+/// Côté de la marque au sol, une fois l'impact imminent
+pub const IMPACT_INDICATOR_SIZE: f32 = 0.9;
+
+/// Au-dessus des décalques de brûlure, pour ne pas scintiller avec eux
+const IMPACT_INDICATOR_HEIGHT: f32 = DECAL_HEIGHT + 0.002;
+
+/// Rangement des projectiles au repos, sous l'arène
+const PARKED_POSITION: Vec3 = Vec3::new(0.0, -50.0, 0.0);
+
+/// Calendrier de l'arène en cours et pool de projectiles réutilisés d'une volée à l'autre
+#[derive(Resource, Default)]
+pub struct ProjectileRain {
+    /// Arène dont le calendrier est en cours
+    arena_id: Option<Uuid>,
+    pub schedule: Option<ProjectileSchedule>,
+    /// Projectiles au repos, avec leur marque au sol
+    free: Vec<(Entity, Entity)>,
+    /// Projectiles créés depuis le lancement, en vol ou au repos
+    pub pool_size: usize,
+}
+
+impl ProjectileRain {
+    pub fn free_count(&self) -> usize {
+        self.free.len()
+    }
+}
+
+/// Projectile au repos et sa marque : cachés, sans collisions
+fn spawn_pooled_projectile(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    cache: &mut MeshCache,
+    materials: &ModuleMaterials,
+) -> (Entity, Entity) {
+    let indicator = commands.spawn((
+        ImpactIndicator,
+        Name::new("Impact Indicator"),
+        Mesh3d(cache.get_or_create(meshes, MeshKey::decal(IMPACT_INDICATOR_SIZE))),
+        MeshMaterial3d(materials.impact_indicator.clone()),
+        Transform::from_translation(PARKED_POSITION),
+        Visibility::Hidden,
+    )).id();
+
+    let projectile = commands.spawn((
+        Projectile {
+            damage: 0.0,
+            target: PARKED_POSITION,
+            time_to_impact: 0.0,
+            telegraph: 0.0,
+            active: false,
+            indicator,
+        },
+        Name::new("Projectile"),
+        Mesh3d(cache.get_or_create(meshes, MeshKey::sphere(PROJECTILE_RADIUS, 12))),
+        MeshMaterial3d(materials.projectile.clone()),
+        Transform::from_translation(PARKED_POSITION),
+        Visibility::Hidden,
+        RigidBody::KinematicPositionBased,
+        Collider::ball(PROJECTILE_RADIUS),
+        Sensor,
+        ActiveEvents::COLLISION_EVENTS,
+        ColliderDisabled,
+    )).id();
+
+    (projectile, indicator)
+}
+
+/// Point d'impact d'une cellule, à la surface des dalles
+fn impact_point(cell: (i32, i32)) -> Vec3 {
+    Vec3::new(cell.0 as f32, DECAL_HEIGHT, cell.1 as f32)
+}
+
+/// Position du projectile et échelle de sa marque selon l'avancement de la chute (0.0 → 1.0)
+pub fn fall_state(target: Vec3, progress: f32) -> (Vec3, f32) {
+    let progress = progress.clamp(0.0, 1.0);
+    // Chute accélérée : lente au départ, brutale à l'arrivée
+    let height = DROP_HEIGHT * (1.0 - progress * progress);
+    (target + Vec3::Y * height, 0.3 + 0.7 * progress)
+}
+
+fn park(
+    commands: &mut Commands,
+    entity: Entity,
+    transform: &mut Transform,
+    visibility: &mut Visibility,
+    indicator: Option<(Mut<Transform>, Mut<Visibility>)>,
+) {
+    transform.translation = PARKED_POSITION;
+    *visibility = Visibility::Hidden;
+    commands.entity(entity).insert(ColliderDisabled);
+    if let Some((mut transform, mut visibility)) = indicator {
+        transform.translation = PARKED_POSITION;
+        *visibility = Visibility::Hidden;
+    }
+}
+
+// ============================================================================
+// SYSTÈMES DE PROJECTILES
+// ============================================================================
+
+/// Nouveau calendrier à chaque nouvelle arène ; le pool grandit d'avance pour la pire volée
+pub fn projectile_schedule_system(
+    mut commands: Commands,
+    arena_manager: Res<ArenaManager>,
+    difficulty: Res<DifficultySystem>,
+    materials: Res<ModuleMaterials>,
+    mut rain: ResMut<ProjectileRain>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut cache: ResMut<MeshCache>,
+) {
+    let Some(ref arena) = arena_manager.current_arena else {
+        return;
+    };
+    if rain.arena_id == Some(arena.id) {
+        return;
+    }
+
+    rain.arena_id = Some(arena.id);
+    rain.schedule = ProjectileSchedule::for_arena(arena, difficulty.current_level);
+
+    let Some(needed) = rain.schedule.as_ref().map(ProjectileSchedule::max_projectiles_in_flight) else {
+        return;
+    };
+    while rain.pool_size < needed {
+        let pair = spawn_pooled_projectile(&mut commands, &mut meshes, &mut cache, &materials);
+        rain.free.push(pair);
+        rain.pool_size += 1;
+    }
+
+    if let Some(ref schedule) = rain.schedule {
+        info!("☄️ Pluie de projectiles : {} par volée toutes les {:.1}s, annonce {:.1}s (pool {})",
+            schedule.volley_size, schedule.interval, schedule.telegraph, rain.pool_size);
+    }
+}
+
+/// Lance les volées dues : chaque projectile sort du pool au-dessus de sa cellule,
+/// sa marque apparaît au sol pour toute la durée de l'annonce
+pub fn projectile_spawn_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    materials: Res<ModuleMaterials>,
+    mut rain: ResMut<ProjectileRain>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut cache: ResMut<MeshCache>,
+) {
+    let Some(schedule) = rain.schedule.as_mut() else {
+        return;
+    };
+    let volleys = schedule.tick(time.delta_secs());
+
+    for volley in volleys {
+        for &cell in &volley.targets {
+            let (projectile, indicator) = match rain.free.pop() {
+                Some(pair) => pair,
+                None => {
+                    // Pool trop petit : il grandit, rien n'est jamais détruit
+                    rain.pool_size += 1;
+                    spawn_pooled_projectile(&mut commands, &mut meshes, &mut cache, &materials)
+                }
+            };
+
+            let target = impact_point(cell);
+            let (position, indicator_scale) = fall_state(target, 0.0);
+            commands.entity(projectile)
+                .insert((
+                    Projectile {
+                        damage: volley.damage,
+                        target,
+                        time_to_impact: volley.telegraph,
+                        telegraph: volley.telegraph,
+                        active: true,
+                        indicator,
+                    },
+                    Transform::from_translation(position),
+                    Visibility::Inherited,
+                ))
+                .remove::<ColliderDisabled>();
+            commands.entity(indicator).insert((
+                Transform::from_xyz(target.x, IMPACT_INDICATOR_HEIGHT, target.z).with_scale(Vec3::splat(indicator_scale)),
+                Visibility::Inherited,
+            ));
+        }
+    }
+}
+
+/// Fait tomber les projectiles en vol ; ceux qui ont touché le sol ou le joueur retournent au pool
+pub fn projectile_fall_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut rain: ResMut<ProjectileRain>,
+    mut projectiles: Query<(Entity, &mut Projectile, &mut Transform, &mut Visibility), Without<ImpactIndicator>>,
+    mut indicators: Query<(&mut Transform, &mut Visibility), (With<ImpactIndicator>, Without<Projectile>)>,
+) {
+    let dt = time.delta_secs();
+
+    for (entity, mut projectile, mut transform, mut visibility) in projectiles.iter_mut() {
+        if *visibility == Visibility::Hidden {
+            continue;
+        }
+
+        if projectile.active {
+            projectile.time_to_impact -= dt;
+            if projectile.time_to_impact > 0.0 {
+                let progress = 1.0 - projectile.time_to_impact / projectile.telegraph.max(f32::EPSILON);
+                let (position, indicator_scale) = fall_state(projectile.target, progress);
+                transform.translation = position;
+                if let Ok((mut indicator_transform, _)) = indicators.get_mut(projectile.indicator) {
+                    indicator_transform.scale = Vec3::splat(indicator_scale);
+                }
+                continue;
+            }
+            // Impact au sol
+            projectile.active = false;
+        }
+
+        park(&mut commands, entity, &mut transform, &mut visibility, indicators.get_mut(projectile.indicator).ok());
+        rain.free.push((entity, projectile.indicator));
+    }
+}
+
+/// En quittant la partie, tout ce qui est en vol retourne au pool ; le calendrier repart de zéro
+pub fn reset_projectile_rain(
+    mut commands: Commands,
+    mut rain: ResMut<ProjectileRain>,
+    mut projectiles: Query<(Entity, &mut Projectile, &mut Transform, &mut Visibility), Without<ImpactIndicator>>,
+    mut indicators: Query<(&mut Transform, &mut Visibility), (With<ImpactIndicator>, Without<Projectile>)>,
+) {
+    rain.arena_id = None;
+    rain.schedule = None;
+    rain.free.clear();
+
+    for (entity, mut projectile, mut transform, mut visibility) in projectiles.iter_mut() {
+        projectile.active = false;
+        park(&mut commands, entity, &mut transform, &mut visibility, indicators.get_mut(projectile.indicator).ok());
+        rain.free.push((entity, projectile.indicator));
+    }
+}
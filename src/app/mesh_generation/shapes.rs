@@ -0,0 +1,428 @@
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy_rapier3d::prelude::*;
+use std::f32::consts::PI;
+use log::warn;
+
+// ============================================================================
+// ASSEMBLAGE DES MAILLAGES
+// ============================================================================
+
+/// Sommets et triangles d'un maillage en cours de construction.
+/// Les faces sont données dans le sens antihoraire vues de l'extérieur (convention Bevy).
+#[derive(Default)]
+pub struct GeometryBuffer {
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    uvs: Vec<[f32; 2]>,
+    indices: Vec<u32>,
+}
+
+impl GeometryBuffer {
+    pub fn vertex(&mut self, position: Vec3, normal: Vec3, uv: Vec2) -> u32 {
+        self.positions.push(position.to_array());
+        self.normals.push(normal.normalize_or_zero().to_array());
+        self.uvs.push(uv.to_array());
+        (self.positions.len() - 1) as u32
+    }
+
+    pub fn triangle_indices(&mut self, a: u32, b: u32, c: u32) {
+        self.indices.extend_from_slice(&[a, b, c]);
+    }
+
+    /// Quadrilatère plan ; UV en unités monde pour que les textures se répètent à la même échelle partout
+    pub fn quad(&mut self, corners: [Vec3; 4]) {
+        let normal = (corners[1] - corners[0]).cross(corners[2] - corners[0]);
+        self.quad_with_normals(corners, [normal; 4]);
+    }
+
+    /// Quadrilatère à normales par sommet, pour les surfaces courbes
+    pub fn quad_with_normals(&mut self, corners: [Vec3; 4], normals: [Vec3; 4]) {
+        let u = corners[0].distance(corners[1]);
+        let v = corners[0].distance(corners[3]);
+        let uvs = [Vec2::ZERO, Vec2::new(u, 0.0), Vec2::new(u, v), Vec2::new(0.0, v)];
+
+        let base = self.positions.len() as u32;
+        for i in 0..4 {
+            self.vertex(corners[i], normals[i], uvs[i]);
+        }
+        self.triangle_indices(base, base + 1, base + 2);
+        self.triangle_indices(base, base + 2, base + 3);
+    }
+
+    pub fn triangle(&mut self, corners: [Vec3; 3]) {
+        let normal = (corners[1] - corners[0]).cross(corners[2] - corners[0]);
+        let u = corners[0].distance(corners[1]);
+        let along = (corners[1] - corners[0]).normalize_or_zero();
+        let offset = corners[2] - corners[0];
+        let uvs = [Vec2::ZERO, Vec2::new(u, 0.0), Vec2::new(offset.dot(along), offset.reject_from(along).length())];
+
+        let base = self.positions.len() as u32;
+        for i in 0..3 {
+            self.vertex(corners[i], normal, uvs[i]);
+        }
+        self.triangle_indices(base, base + 1, base + 2);
+    }
+
+    /// Pavé centré sur `center`, arêtes du dessus chanfreinées de `bevel` (0.0 = arêtes vives)
+    pub fn cuboid(&mut self, center: Vec3, size: Vec3, bevel: f32) {
+        let half = size * 0.5;
+        let bevel = bevel.clamp(0.0, 0.45 * half.x.min(half.z).min(size.y));
+        let (bottom, top) = (center.y - half.y, center.y + half.y);
+        let shoulder = top - bevel;
+        let flat = Vec3::new(center.x, 0.0, center.z);
+
+        // Côtés, puis chanfreins qui se rejoignent en onglet aux coins
+        for normal in [Vec3::Z, Vec3::X, Vec3::NEG_Z, Vec3::NEG_X] {
+            let tangent = Vec3::Y.cross(normal);
+            let across = normal.abs().dot(half);
+            let along = tangent.abs().dot(half);
+            let at = |n: f32, t: f32, y: f32| flat + normal * n + tangent * t + Vec3::Y * y;
+
+            self.quad([at(across, -along, bottom), at(across, along, bottom), at(across, along, shoulder), at(across, -along, shoulder)]);
+            if bevel > 0.0 {
+                self.quad([
+                    at(across, -along, shoulder),
+                    at(across, along, shoulder),
+                    at(across - bevel, along - bevel, top),
+                    at(across - bevel, -(along - bevel), top),
+                ]);
+            }
+        }
+
+        let (x, z) = (half.x - bevel, half.z - bevel);
+        self.quad([
+            flat + Vec3::new(-x, top, z),
+            flat + Vec3::new(x, top, z),
+            flat + Vec3::new(x, top, -z),
+            flat + Vec3::new(-x, top, -z),
+        ]);
+        self.quad([
+            flat + Vec3::new(-half.x, bottom, -half.z),
+            flat + Vec3::new(half.x, bottom, -half.z),
+            flat + Vec3::new(half.x, bottom, half.z),
+            flat + Vec3::new(-half.x, bottom, half.z),
+        ]);
+    }
+
+    /// Maillage final, tangentes comprises (nécessaires aux cartes de normales)
+    pub fn build(self) -> Mesh {
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, default());
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs);
+        mesh.insert_indices(Indices::U32(self.indices));
+
+        if let Err(e) = mesh.generate_tangents() {
+            warn!("⚠️ Tangentes non générées: {}", e);
+        }
+        mesh
+    }
+}
+
+// ============================================================================
+// FONCTIONS DE CRÉATION DE MAILLAGES
+// ============================================================================
+
+pub fn cube_mesh(width: f32, height: f32, depth: f32) -> Mesh {
+    box_mesh(Vec3::new(width, height, depth), 0.0)
+}
+
+/// Pavé centré à l'origine, arêtes du dessus chanfreinées (dalles de sol)
+pub fn box_mesh(size: Vec3, bevel: f32) -> Mesh {
+    let mut buffer = GeometryBuffer::default();
+    buffer.cuboid(Vec3::ZERO, size, bevel);
+    buffer.build()
+}
+
+pub fn sphere_mesh(radius: f32, resolution: usize) -> Mesh {
+    let mut buffer = GeometryBuffer::default();
+    let resolution = resolution.max(3);
+
+    // Une colonne de plus que de segments : la couture des UV a ses propres sommets
+    for i in 0..=resolution {
+        let theta = i as f32 * PI / resolution as f32;
+        for j in 0..=resolution {
+            let phi = j as f32 * 2.0 * PI / resolution as f32;
+            let normal = Vec3::new(theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin());
+            let uv = Vec2::new(j as f32 / resolution as f32, i as f32 / resolution as f32);
+            buffer.vertex(normal * radius, normal, uv);
+        }
+    }
+
+    for i in 0..resolution {
+        for j in 0..resolution {
+            let first = (i * (resolution + 1) + j) as u32;
+            let second = first + resolution as u32 + 1;
+
+            buffer.triangle_indices(first, first + 1, second);
+            buffer.triangle_indices(second, first + 1, second + 1);
+        }
+    }
+
+    buffer.build()
+}
+
+pub fn cylinder_mesh(radius: f32, height: f32, resolution: usize) -> Mesh {
+    let mut buffer = GeometryBuffer::default();
+    let resolution = resolution.max(3);
+    let half_height = height * 0.5;
+
+    // Flanc : normales lissées, couture des UV dédoublée
+    let mut columns = Vec::with_capacity(resolution + 1);
+    for i in 0..=resolution {
+        let angle = i as f32 * 2.0 * PI / resolution as f32;
+        let normal = Vec3::new(angle.cos(), 0.0, angle.sin());
+        let u = i as f32 / resolution as f32;
+        let top = buffer.vertex(normal * radius + Vec3::Y * half_height, normal, Vec2::new(u, 0.0));
+        let bottom = buffer.vertex(normal * radius - Vec3::Y * half_height, normal, Vec2::new(u, 1.0));
+        columns.push((top, bottom));
+    }
+    for pair in columns.windows(2) {
+        let ((top, bottom), (top_next, bottom_next)) = (pair[0], pair[1]);
+        buffer.triangle_indices(top, top_next, bottom);
+        buffer.triangle_indices(bottom, top_next, bottom_next);
+    }
+
+    // Couvercles en éventail
+    for (y, normal) in [(half_height, Vec3::Y), (-half_height, Vec3::NEG_Y)] {
+        let center = buffer.vertex(Vec3::Y * y, normal, Vec2::splat(0.5));
+        let rim: Vec<u32> = (0..resolution)
+            .map(|i| {
+                let angle = i as f32 * 2.0 * PI / resolution as f32;
+                let (sin, cos) = angle.sin_cos();
+                buffer.vertex(Vec3::new(cos * radius, y, sin * radius), normal, Vec2::new(0.5 + cos * 0.5, 0.5 + sin * 0.5))
+            })
+            .collect();
+        for i in 0..resolution {
+            let (a, b) = (rim[i], rim[(i + 1) % resolution]);
+            if normal.y > 0.0 {
+                buffer.triangle_indices(center, b, a);
+            } else {
+                buffer.triangle_indices(center, a, b);
+            }
+        }
+    }
+
+    buffer.build()
+}
+
+// ============================================================================
+// FORMES DE LISIBILITÉ (MODE DALTONIEN, DÉCALQUES)
+// ============================================================================
+
+/// Diamant à huit faces planes : un collectible ne se confond plus avec une sphère de danger
+pub fn octahedron_mesh(radius: f32) -> Mesh {
+    let mut buffer = GeometryBuffer::default();
+    let (top, bottom) = (Vec3::Y * radius, Vec3::NEG_Y * radius);
+    let equator = [Vec3::X, Vec3::Z, Vec3::NEG_X, Vec3::NEG_Z].map(|direction| direction * radius);
+
+    for i in 0..4 {
+        let (a, b) = (equator[i], equator[(i + 1) % 4]);
+        buffer.triangle([top, b, a]);
+        buffer.triangle([bottom, a, b]);
+    }
+
+    buffer.build()
+}
+
+/// Nombre de bandes d'un motif de danger
+const HAZARD_STRIPE_COUNT: usize = 4;
+
+/// Bandes plates et espacées, posées sur le dessus d'un danger (base à y = 0)
+pub fn hazard_stripes_mesh(width: f32, depth: f32) -> Mesh {
+    let mut buffer = GeometryBuffer::default();
+    let pitch = width / HAZARD_STRIPE_COUNT as f32;
+    let bar = Vec3::new(pitch * 0.5, 0.01, depth);
+
+    for i in 0..HAZARD_STRIPE_COUNT {
+        let x = -width * 0.5 + pitch * (i as f32 + 0.5);
+        buffer.cuboid(Vec3::new(x, bar.y * 0.5, 0.0), bar, 0.0);
+    }
+
+    buffer.build()
+}
+
+/// Carré horizontal de côté `size`, UV de 0 à 1 : un décalque couvre sa texture une fois
+pub fn decal_mesh(size: f32) -> Mesh {
+    let mut buffer = GeometryBuffer::default();
+    let half = size * 0.5;
+    let corners = [
+        (Vec3::new(-half, 0.0, half), Vec2::new(0.0, 1.0)),
+        (Vec3::new(half, 0.0, half), Vec2::new(1.0, 1.0)),
+        (Vec3::new(half, 0.0, -half), Vec2::new(1.0, 0.0)),
+        (Vec3::new(-half, 0.0, -half), Vec2::new(0.0, 0.0)),
+    ];
+
+    let indices = corners.map(|(position, uv)| buffer.vertex(position, Vec3::Y, uv));
+    buffer.triangle_indices(indices[0], indices[1], indices[2]);
+    buffer.triangle_indices(indices[0], indices[2], indices[3]);
+    buffer.build()
+}
+
+// ============================================================================
+// RAMPES
+// ============================================================================
+
+/// Pente acceptée pour une rampe, en radians
+const RAMP_ANGLE_RANGE: (f32, f32) = (5.0 * PI / 180.0, 60.0 * PI / 180.0);
+
+/// Hauteur du point haut d'une rampe de côté `size`
+pub fn ramp_height(size: f32, angle: f32) -> f32 {
+    (size * angle.clamp(RAMP_ANGLE_RANGE.0, RAMP_ANGLE_RANGE.1).tan()).max(0.05)
+}
+
+/// Les six coins du coin (prisme) d'une rampe centrée à l'origine, qui monte vers +Z :
+/// arête basse (2), arête haute (2), arrière au sol (2)
+fn ramp_corners(size: f32, angle: f32) -> [Vec3; 6] {
+    let half = size * 0.5;
+    let half_height = ramp_height(size, angle) * 0.5;
+    [
+        Vec3::new(-half, -half_height, -half),
+        Vec3::new(half, -half_height, -half),
+        Vec3::new(-half, half_height, half),
+        Vec3::new(half, half_height, half),
+        Vec3::new(-half, -half_height, half),
+        Vec3::new(half, -half_height, half),
+    ]
+}
+
+/// Prisme fermé : pente, dos vertical, dessous et deux flancs triangulaires
+pub fn ramp_mesh(size: f32, angle: f32) -> Mesh {
+    let [low_left, low_right, high_left, high_right, back_left, back_right] = ramp_corners(size, angle);
+    let mut buffer = GeometryBuffer::default();
+
+    buffer.quad([low_right, low_left, high_left, high_right]);
+    buffer.quad([back_left, back_right, high_right, high_left]);
+    buffer.quad([low_left, low_right, back_right, back_left]);
+    buffer.triangle([low_right, high_right, back_right]);
+    buffer.triangle([low_left, back_left, high_left]);
+
+    buffer.build()
+}
+
+/// Enveloppe convexe exactement superposée au maillage de la rampe
+pub fn ramp_collider(size: f32, angle: f32) -> Collider {
+    let corners = ramp_corners(size, angle);
+    Collider::convex_hull(&corners).unwrap_or_else(|| {
+        let height = ramp_height(size, angle);
+        Collider::cuboid(size * 0.5, height * 0.5, size * 0.5)
+    })
+}
+
+// ============================================================================
+// ARCHES
+// ============================================================================
+
+/// Segments du demi-cercle de l'arche
+const ARCH_SEGMENTS: usize = 16;
+
+/// Largeur des piliers relative à la largeur de l'arche
+const ARCH_PILLAR_RATIO: f32 = 0.15;
+
+/// Piliers et voûte d'une arche centrée à l'origine, ouverte selon Z
+struct ArchShape {
+    half_depth: f32,
+    pillar_width: f32,
+    pillar_height: f32,
+    /// Hauteur des naissances de la voûte (haut des piliers)
+    spring: f32,
+    outer_radius: f32,
+    inner_radius: f32,
+    bottom: f32,
+}
+
+impl ArchShape {
+    fn new(width: f32, height: f32, depth: f32) -> Self {
+        let outer_radius = width * 0.5;
+        let pillar_width = width * ARCH_PILLAR_RATIO;
+        let bottom = -height * 0.5;
+        // La voûte ne descend jamais sous le sol, quitte à raccourcir les piliers
+        let spring = (height * 0.5 - outer_radius).max(bottom);
+        Self {
+            half_depth: depth * 0.5,
+            pillar_width,
+            pillar_height: spring - bottom,
+            spring,
+            outer_radius,
+            inner_radius: outer_radius - pillar_width,
+            bottom,
+        }
+    }
+
+    fn pillar_centers(&self) -> [Vec3; 2] {
+        let x = self.outer_radius - self.pillar_width * 0.5;
+        let y = self.bottom + self.pillar_height * 0.5;
+        [Vec3::new(-x, y, 0.0), Vec3::new(x, y, 0.0)]
+    }
+
+    fn pillar_size(&self) -> Vec3 {
+        Vec3::new(self.pillar_width, self.pillar_height, self.half_depth * 2.0)
+    }
+
+    /// Point de la voûte à l'angle `theta` (0 à droite, PI à gauche) sur le rayon donné
+    fn ring(&self, radius: f32, theta: f32, z: f32) -> Vec3 {
+        Vec3::new(radius * theta.cos(), self.spring + radius * theta.sin(), z)
+    }
+
+    fn segment_angles(&self) -> impl Iterator<Item = (f32, f32)> {
+        (0..ARCH_SEGMENTS).map(|i| {
+            let step = PI / ARCH_SEGMENTS as f32;
+            (i as f32 * step, (i + 1) as f32 * step)
+        })
+    }
+}
+
+/// Deux piliers surmontés d'une voûte en demi-cercle à normales lissées
+pub fn arch_mesh(width: f32, height: f32, depth: f32) -> Mesh {
+    let arch = ArchShape::new(width, height, depth);
+    let mut buffer = GeometryBuffer::default();
+
+    for center in arch.pillar_centers() {
+        buffer.cuboid(center, arch.pillar_size(), 0.0);
+    }
+
+    let (front, back) = (arch.half_depth, -arch.half_depth);
+    let (outer, inner) = (arch.outer_radius, arch.inner_radius);
+    for (t0, t1) in arch.segment_angles() {
+        let radial = |theta: f32| Vec3::new(theta.cos(), theta.sin(), 0.0);
+
+        // Faces avant et arrière de la voûte
+        buffer.quad([arch.ring(inner, t0, front), arch.ring(outer, t0, front), arch.ring(outer, t1, front), arch.ring(inner, t1, front)]);
+        buffer.quad([arch.ring(inner, t1, back), arch.ring(outer, t1, back), arch.ring(outer, t0, back), arch.ring(inner, t0, back)]);
+
+        // Extrados (vers l'extérieur) et intrados (vers le passage)
+        buffer.quad_with_normals(
+            [arch.ring(outer, t0, front), arch.ring(outer, t0, back), arch.ring(outer, t1, back), arch.ring(outer, t1, front)],
+            [radial(t0), radial(t0), radial(t1), radial(t1)],
+        );
+        buffer.quad_with_normals(
+            [arch.ring(inner, t0, back), arch.ring(inner, t0, front), arch.ring(inner, t1, front), arch.ring(inner, t1, back)],
+            [-radial(t0), -radial(t0), -radial(t1), -radial(t1)],
+        );
+    }
+
+    buffer.build()
+}
+
+/// Piliers en pavés et voûte en enveloppes convexes, segment par segment : le passage reste libre
+pub fn arch_collider(width: f32, height: f32, depth: f32) -> Collider {
+    let arch = ArchShape::new(width, height, depth);
+    let half_pillar = arch.pillar_size() * 0.5;
+
+    let mut shapes: Vec<(Vec3, Quat, Collider)> = arch.pillar_centers().into_iter()
+        .map(|center| (center, Quat::IDENTITY, Collider::cuboid(half_pillar.x, half_pillar.y, half_pillar.z)))
+        .collect();
+
+    for (t0, t1) in arch.segment_angles() {
+        let points: Vec<Vec3> = [arch.inner_radius, arch.outer_radius].into_iter()
+            .flat_map(|radius| [t0, t1].map(|theta| (radius, theta)))
+            .flat_map(|(radius, theta)| [arch.half_depth, -arch.half_depth].map(|z| arch.ring(radius, theta, z)))
+            .collect();
+        if let Some(hull) = Collider::convex_hull(&points) {
+            shapes.push((Vec3::ZERO, Quat::IDENTITY, hull));
+        }
+    }
+
+    Collider::compound(shapes)
+}
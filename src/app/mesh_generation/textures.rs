@@ -0,0 +1,239 @@
+use bevy::prelude::*;
+use bevy::image::{ImageAddressMode, ImageSampler, ImageSamplerDescriptor};
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use noise::{Fbm, MultiFractal, NoiseFn, Perlin};
+use crate::app::game::ArenaManager;
+use crate::app::mesh_generation::*;
+use std::f64::consts::TAU;
+use log::info;
+
+// ============================================================================
+// TEXTURES PROCÉDURALES (CPU)
+// ============================================================================
+
+/// Textures générées au chargement. Les motifs sont en niveaux de gris (ou en alpha pour
+/// les décalques) : la couleur reste celle du thème, qui les multiplie.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextureKind {
+    /// Joints entre dalles
+    Grid,
+    /// Bandes diagonales des émetteurs de danger
+    HazardStripes,
+    /// Lave bouillonnante, bordée de bandes pour lire ses contours
+    LavaNoise,
+    /// Brûlure radiale, posée en décalque autour des lasers
+    Scorch,
+}
+
+impl TextureKind {
+    pub const ALL: [TextureKind; 4] = [TextureKind::Grid, TextureKind::HazardStripes, TextureKind::LavaNoise, TextureKind::Scorch];
+
+    /// Les décalques se voient de loin et petits : demi-résolution
+    fn resolution(&self, base: u32) -> u32 {
+        match self {
+            TextureKind::Scorch => (base / 2).max(MIN_TEXTURE_SIZE),
+            _ => base,
+        }
+    }
+
+    /// Pixels RGBA (sRGB) de côté `size`, identiques pour une même graine
+    pub fn generate(&self, size: u32, seed: u64) -> TexturePixels {
+        let noise = Fbm::<Perlin>::new(seed as u32 ^ (seed >> 32) as u32).set_octaves(4);
+        let mut data = Vec::with_capacity((size * size * 4) as usize);
+
+        for y in 0..size {
+            for x in 0..size {
+                // Centre du pixel, dans [0, 1[
+                let u = (x as f64 + 0.5) / size as f64;
+                let v = (y as f64 + 0.5) / size as f64;
+                let [r, g, b, a] = match self {
+                    TextureKind::Grid => grey(grid(u, v)),
+                    TextureKind::HazardStripes => grey(if stripe(u + v, 4.0) { 1.0 } else { 0.3 }),
+                    TextureKind::LavaNoise => grey(lava(&noise, u, v)),
+                    TextureKind::Scorch => [1.0, 1.0, 1.0, scorch(&noise, u, v)],
+                };
+                data.extend([r, g, b, a].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8));
+            }
+        }
+
+        TexturePixels { size, data }
+    }
+}
+
+/// Image carrée RGBA8
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TexturePixels {
+    pub size: u32,
+    pub data: Vec<u8>,
+}
+
+impl TexturePixels {
+    /// Les motifs se répètent : les UV des maillages sont en unités monde, une répétition par cellule
+    pub fn into_image(self) -> Image {
+        let mut image = Image::new(
+            Extent3d { width: self.size, height: self.size, depth_or_array_layers: 1 },
+            TextureDimension::D2,
+            self.data,
+            TextureFormat::Rgba8UnormSrgb,
+            default(),
+        );
+        image.sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
+            address_mode_u: ImageAddressMode::Repeat,
+            address_mode_v: ImageAddressMode::Repeat,
+            ..ImageSamplerDescriptor::linear()
+        });
+        image
+    }
+}
+
+fn grey(value: f64) -> [f64; 4] {
+    [value, value, value, 1.0]
+}
+
+/// Distance au bord le plus proche d'une cellule de texture
+fn edge_distance(u: f64, v: f64) -> f64 {
+    u.min(1.0 - u).min(v).min(1.0 - v)
+}
+
+fn stripe(t: f64, count: f64) -> bool {
+    (t * count).rem_euclid(1.0) < 0.5
+}
+
+/// Joint sombre sur le pourtour, léger liseré clair juste à l'intérieur
+fn grid(u: f64, v: f64) -> f64 {
+    match edge_distance(u, v) {
+        d if d < 0.025 => 0.45,
+        d if d < 0.05 => 1.0,
+        _ => 0.85,
+    }
+}
+
+/// Bruit périodique : échantillonné sur un tore en 4D, il se raccorde aux bords de la texture
+fn tileable(noise: &Fbm<Perlin>, u: f64, v: f64, scale: f64) -> f64 {
+    let (su, cu) = (u * TAU).sin_cos();
+    let (sv, cv) = (v * TAU).sin_cos();
+    let n = noise.get([cu * scale, su * scale, cv * scale, sv * scale]);
+    (n * 0.5 + 0.5).clamp(0.0, 1.0)
+}
+
+fn lava(noise: &Fbm<Perlin>, u: f64, v: f64) -> f64 {
+    // Bandes sombres sur les bords : la limite du danger reste lisible sur petit écran
+    if edge_distance(u, v) < 0.08 {
+        return if stripe(u + v, 8.0) { 0.15 } else { 1.0 };
+    }
+    0.5 + 0.5 * tileable(noise, u, v, 1.5)
+}
+
+/// Opacité de la brûlure : tache ronde aux bords déchiquetés, nulle sur le pourtour
+fn scorch(noise: &Fbm<Perlin>, u: f64, v: f64) -> f64 {
+    let r = ((u - 0.5).powi(2) + (v - 0.5).powi(2)).sqrt();
+    let t = ((r - 0.15) / (0.48 - 0.15)).clamp(0.0, 1.0);
+    let falloff = 1.0 - t * t * (3.0 - 2.0 * t);
+    falloff * (0.3 + 0.7 * tileable(noise, u, v, 2.5))
+}
+
+// ============================================================================
+// BUDGET MÉMOIRE
+// ============================================================================
+
+const MIN_TEXTURE_SIZE: u32 = 16;
+const MAX_TEXTURE_SIZE: u32 = 512;
+
+/// Mémoire totale des textures procédurales : 1 Mio tient dans le budget d'un mobile d'entrée de gamme
+pub const TEXTURE_BUDGET_BYTES: usize = 1 << 20;
+
+/// Côté retenu : puissance de deux, bornée, réduite de moitié tant que le jeu dépasse le budget
+pub fn budgeted_texture_size(requested: u32) -> u32 {
+    let mut size = requested.clamp(MIN_TEXTURE_SIZE, MAX_TEXTURE_SIZE).next_power_of_two().min(MAX_TEXTURE_SIZE);
+    while size > MIN_TEXTURE_SIZE && texture_set_bytes(size) > TEXTURE_BUDGET_BYTES {
+        size /= 2;
+    }
+    size
+}
+
+/// Mémoire du jeu complet de textures pour un côté de base `size`
+pub fn texture_set_bytes(size: u32) -> usize {
+    TextureKind::ALL.iter()
+        .map(|kind| kind.resolution(size).pow(2) as usize * 4)
+        .sum()
+}
+
+// ============================================================================
+// RESSOURCE ET ASSIGNATION
+// ============================================================================
+
+/// Textures de l'arène en cours ; régénérées seulement quand la graine change
+#[derive(Resource, Default)]
+pub struct ProceduralTextures {
+    /// Graine et côté de base des textures actuelles
+    generated_for: Option<(u64, u32)>,
+    handles: Vec<(TextureKind, Handle<Image>)>,
+}
+
+impl ProceduralTextures {
+    pub fn get(&self, kind: TextureKind) -> Option<&Handle<Image>> {
+        self.handles.iter().find(|(k, _)| *k == kind).map(|(_, handle)| handle)
+    }
+
+    /// Génère (ou remplace sur place) le jeu de textures de `seed` ; `false` s'il était déjà à jour
+    pub fn prepare(&mut self, seed: u64, requested_size: u32, images: &mut Assets<Image>) -> bool {
+        let size = budgeted_texture_size(requested_size);
+        if self.generated_for == Some((seed, size)) {
+            return false;
+        }
+
+        for kind in TextureKind::ALL {
+            let image = kind.generate(kind.resolution(size), seed).into_image();
+            match self.get(kind) {
+                // Même handle : les matériaux qui la référencent suivent
+                Some(handle) => images.insert(handle, image),
+                None => self.handles.push((kind, images.add(image))),
+            }
+        }
+        self.generated_for = Some((seed, size));
+        true
+    }
+}
+
+impl ModuleMaterials {
+    /// Texture de base de chaque matériau texturé ; les couleurs du thème restent intactes
+    pub fn apply_textures(&self, textures: &ProceduralTextures, assets: &mut Assets<StandardMaterial>) {
+        let assignments = [
+            (&self.floor_std, TextureKind::Grid),
+            (&self.floor_large, TextureKind::Grid),
+            (&self.floor_fragile, TextureKind::Grid),
+            (&self.floor_fragile_cracked, TextureKind::Grid),
+            (&self.ramp, TextureKind::Grid),
+            (&self.hazard_lava, TextureKind::LavaNoise),
+            (&self.hazard_laser, TextureKind::HazardStripes),
+            (&self.scorch_decal, TextureKind::Scorch),
+        ];
+
+        for (material, kind) in assignments {
+            if let Some(material) = assets.get_mut(material) {
+                material.base_color_texture = textures.get(kind).cloned();
+            }
+        }
+    }
+}
+
+/// À l'entrée en partie : textures de la graine de l'arène, générées une fois et partagées
+pub fn prepare_arena_textures(
+    arena_manager: Res<ArenaManager>,
+    settings: Res<ArenaVisualSettings>,
+    materials: Res<ModuleMaterials>,
+    mut textures: ResMut<ProceduralTextures>,
+    mut images: ResMut<Assets<Image>>,
+    mut assets: ResMut<Assets<StandardMaterial>>,
+) {
+    let Some(ref arena) = arena_manager.current_arena else {
+        return;
+    };
+
+    let seed = arena.generation_metadata.seed;
+    if textures.prepare(seed, settings.texture_size, &mut images) {
+        let size = budgeted_texture_size(settings.texture_size);
+        info!("🖼️ Textures procédurales {}px pour la graine {} ({} Kio)", size, seed, texture_set_bytes(size) / 1024);
+    }
+    materials.apply_textures(&textures, &mut assets);
+}
@@ -0,0 +1,273 @@
+use bevy::prelude::*;
+use crate::app::core::*;
+use crate::app::game::{ArenaManager, MutationAppliedEvent};
+use crate::app::mesh_generation::*;
+use log::info;
+
+// ============================================================================
+// MATÉRIAUX ISSUS D'UNE PALETTE
+// ============================================================================
+
+fn material_from_spec(spec: &MaterialSpec) -> StandardMaterial {
+    let mut material = StandardMaterial::default();
+    write_spec(&mut material, spec);
+    material
+}
+
+/// Réécrit les paramètres de surface d'un matériau existant : les handles restent valides
+fn write_spec(material: &mut StandardMaterial, spec: &MaterialSpec) {
+    let [r, g, b, a] = spec.base_color;
+    let [er, eg, eb] = spec.emissive;
+    material.base_color = Color::srgba(r, g, b, a);
+    material.emissive = Color::srgb(er, eg, eb).into();
+    material.perceptual_roughness = spec.roughness;
+    material.metallic = spec.metallic;
+    material.alpha_mode = if spec.is_transparent() { AlphaMode::Blend } else { AlphaMode::Opaque };
+}
+
+impl ModuleMaterials {
+    pub fn from_palette(palette: &ThemePalette, assets: &mut Assets<StandardMaterial>) -> Self {
+        let mut add = |slot: MaterialSlot| assets.add(material_from_spec(&palette.spec(slot)));
+
+        let materials = Self {
+            floor_std: add(MaterialSlot::FloorStd),
+            floor_large: add(MaterialSlot::FloorLarge),
+            floor_fragile: add(MaterialSlot::FloorFragile),
+            floor_fragile_cracked: add(MaterialSlot::FloorFragileCracked),
+            wall_low: add(MaterialSlot::WallLow),
+            wall_high: add(MaterialSlot::WallHigh),
+            panel_glass: add(MaterialSlot::PanelGlass),
+            ramp: add(MaterialSlot::Ramp),
+            orb_energy: add(MaterialSlot::OrbEnergy),
+            hazard_lava: add(MaterialSlot::HazardLava),
+            hazard_laser: add(MaterialSlot::HazardLaser),
+            laser_beam: add(MaterialSlot::LaserBeam),
+            hazard_stripes: add(MaterialSlot::HazardStripes),
+            scorch_decal: add(MaterialSlot::ScorchDecal),
+            projectile: add(MaterialSlot::Projectile),
+            impact_indicator: add(MaterialSlot::ImpactIndicator),
+            decor_metallic: add(MaterialSlot::DecorMetallic),
+            decor_metallic_variants: MaterialSlot::DECOR_VARIANTS.iter().map(|&slot| add(slot)).collect(),
+            player_spawn: add(MaterialSlot::PlayerSpawn),
+            teleporter_in: add(MaterialSlot::TeleporterIn),
+            teleporter_out: add(MaterialSlot::TeleporterOut),
+            climb_surface: add(MaterialSlot::ClimbSurface),
+            button: add(MaterialSlot::Button),
+            button_pressed: add(MaterialSlot::ButtonPressed),
+            lever: add(MaterialSlot::Lever),
+            lever_handle: add(MaterialSlot::LeverHandle),
+            enemy_spawner: add(MaterialSlot::EnemySpawner),
+            barrier_energy: add(MaterialSlot::BarrierEnergy),
+        };
+
+        // La barrière se voit des deux côtés et ne reçoit pas d'éclairage
+        if let Some(barrier) = assets.get_mut(&materials.barrier_energy) {
+            barrier.unlit = true;
+            barrier.double_sided = true;
+            barrier.cull_mode = None;
+        }
+        // Le rayon et les marques d'impact brillent par eux-mêmes, quelle que soit la lumière
+        for handle in [&materials.laser_beam, &materials.impact_indicator] {
+            if let Some(material) = assets.get_mut(handle) {
+                material.unlit = true;
+            }
+        }
+        materials
+    }
+
+    /// Handle partagé d'un emplacement de la palette
+    pub fn handle(&self, slot: MaterialSlot) -> &Handle<StandardMaterial> {
+        match slot {
+            MaterialSlot::FloorStd => &self.floor_std,
+            MaterialSlot::FloorLarge => &self.floor_large,
+            MaterialSlot::FloorFragile => &self.floor_fragile,
+            MaterialSlot::FloorFragileCracked => &self.floor_fragile_cracked,
+            MaterialSlot::WallLow => &self.wall_low,
+            MaterialSlot::WallHigh => &self.wall_high,
+            MaterialSlot::PanelGlass => &self.panel_glass,
+            MaterialSlot::Ramp => &self.ramp,
+            MaterialSlot::OrbEnergy => &self.orb_energy,
+            MaterialSlot::HazardLava => &self.hazard_lava,
+            MaterialSlot::HazardLaser => &self.hazard_laser,
+            MaterialSlot::LaserBeam => &self.laser_beam,
+            MaterialSlot::HazardStripes => &self.hazard_stripes,
+            MaterialSlot::ScorchDecal => &self.scorch_decal,
+            MaterialSlot::Projectile => &self.projectile,
+            MaterialSlot::ImpactIndicator => &self.impact_indicator,
+            MaterialSlot::DecorMetallic => &self.decor_metallic,
+            MaterialSlot::DecorSteel => &self.decor_metallic_variants[0],
+            MaterialSlot::DecorBrass => &self.decor_metallic_variants[1],
+            MaterialSlot::DecorCobalt => &self.decor_metallic_variants[2],
+            MaterialSlot::DecorCopper => &self.decor_metallic_variants[3],
+            MaterialSlot::PlayerSpawn => &self.player_spawn,
+            MaterialSlot::TeleporterIn => &self.teleporter_in,
+            MaterialSlot::TeleporterOut => &self.teleporter_out,
+            MaterialSlot::ClimbSurface => &self.climb_surface,
+            MaterialSlot::Button => &self.button,
+            MaterialSlot::ButtonPressed => &self.button_pressed,
+            MaterialSlot::Lever => &self.lever,
+            MaterialSlot::LeverHandle => &self.lever_handle,
+            MaterialSlot::EnemySpawner => &self.enemy_spawner,
+            MaterialSlot::BarrierEnergy => &self.barrier_energy,
+        }
+    }
+
+    /// Repeint tous les matériaux partagés : chaque module de l'arène change de couleur d'un coup
+    pub fn apply_palette(&self, palette: &ThemePalette, assets: &mut Assets<StandardMaterial>) {
+        for slot in MaterialSlot::ALL {
+            if let Some(material) = assets.get_mut(self.handle(slot)) {
+                write_spec(material, &palette.spec(slot));
+            }
+        }
+    }
+}
+
+// ============================================================================
+// THÈME ACTIF ET FONDUS
+// ============================================================================
+
+/// Fondu en cours entre deux palettes
+#[derive(Debug, Clone)]
+struct ThemeTransition {
+    from: ThemePalette,
+    to: ThemePalette,
+    elapsed: f32,
+    duration: f32,
+}
+
+/// Palette appliquée aux matériaux partagés de l'arène
+#[derive(Resource, Debug, Clone)]
+pub struct ActiveTheme {
+    /// Palette affichée en ce moment (intermédiaire pendant un fondu)
+    pub current: ThemePalette,
+    transition: Option<ThemeTransition>,
+}
+
+impl Default for ActiveTheme {
+    fn default() -> Self {
+        Self { current: ThemePalette::builtin(ThemeId::Pastel), transition: None }
+    }
+}
+
+impl ActiveTheme {
+    /// Palette visée : celle du fondu en cours, sinon la palette affichée
+    pub fn target(&self) -> &ThemePalette {
+        self.transition.as_ref().map_or(&self.current, |transition| &transition.to)
+    }
+
+    pub fn is_transitioning(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    /// Remplace la palette sans fondu
+    pub fn set(&mut self, palette: ThemePalette) {
+        self.current = palette;
+        self.transition = None;
+    }
+
+    /// Démarre un fondu depuis la palette affichée, même au milieu d'un autre fondu
+    pub fn start_transition(&mut self, to: ThemePalette, duration: f32) {
+        if duration <= 0.0 {
+            self.set(to);
+            return;
+        }
+        self.transition = Some(ThemeTransition { from: self.current.clone(), to, elapsed: 0.0, duration });
+    }
+
+    /// Avance le fondu de `dt` ; renvoie `true` si la palette affichée a changé
+    pub fn advance(&mut self, dt: f32) -> bool {
+        let Some(transition) = self.transition.as_mut() else {
+            return false;
+        };
+
+        transition.elapsed += dt;
+        let progress = (transition.elapsed / transition.duration).clamp(0.0, 1.0);
+        // Départ et arrivée en douceur
+        let eased = progress * progress * (3.0 - 2.0 * progress);
+        self.current = transition.from.lerp(&transition.to, eased);
+
+        if progress >= 1.0 {
+            self.current = transition.to.clone();
+            self.transition = None;
+        }
+        true
+    }
+}
+
+/// Palette de l'arène : palette intégrée de son thème, sans accès disque
+pub fn load_arena_theme(arena: &Arena, settings: &ArenaVisualSettings) -> ThemePalette {
+    let palette = ThemePalette::builtin(ThemeId::for_arena(arena));
+
+    if settings.colour_blind {
+        palette.colour_blind()
+    } else {
+        palette
+    }
+}
+
+fn background_color(palette: &ThemePalette) -> ClearColor {
+    let [r, g, b] = palette.background;
+    ClearColor(Color::srgb(r, g, b))
+}
+
+// ============================================================================
+// SYSTÈMES DE THÈME
+// ============================================================================
+
+/// À l'entrée en partie, le thème de l'arène s'applique sans fondu
+pub fn apply_arena_theme(
+    mut commands: Commands,
+    arena_manager: Res<ArenaManager>,
+    settings: Res<ArenaVisualSettings>,
+    materials: Res<ModuleMaterials>,
+    mut theme: ResMut<ActiveTheme>,
+    mut assets: ResMut<Assets<StandardMaterial>>,
+) {
+    let Some(ref arena) = arena_manager.current_arena else {
+        return;
+    };
+
+    let palette = load_arena_theme(arena, &settings);
+    info!("🎨 Thème {}", palette.theme);
+    materials.apply_palette(&palette, &mut assets);
+    commands.insert_resource(background_color(&palette));
+    theme.set(palette);
+}
+
+/// Une mutation peut changer les règles, donc le thème : fondu vers la nouvelle palette.
+/// Basculer le mode daltonien passe par le même fondu.
+pub fn theme_mutation_system(
+    mut mutation_events: EventReader<MutationAppliedEvent>,
+    arena_manager: Res<ArenaManager>,
+    settings: Res<ArenaVisualSettings>,
+    mut theme: ResMut<ActiveTheme>,
+) {
+    let mutated = mutation_events.read().count() > 0;
+    if !mutated && !settings.is_changed() {
+        return;
+    }
+    let Some(ref arena) = arena_manager.current_arena else {
+        return;
+    };
+
+    let palette = load_arena_theme(arena, &settings);
+    if palette != *theme.target() {
+        info!("🎨 Fondu vers le thème {}", palette.theme);
+        theme.start_transition(palette, settings.theme_fade_duration);
+    }
+}
+
+pub fn theme_transition_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    materials: Res<ModuleMaterials>,
+    mut theme: ResMut<ActiveTheme>,
+    mut assets: ResMut<Assets<StandardMaterial>>,
+) {
+    if !theme.advance(time.delta_secs()) {
+        return;
+    }
+
+    materials.apply_palette(&theme.current, &mut assets);
+    commands.insert_resource(background_color(&theme.current));
+}
@@ -0,0 +1,203 @@
+//monitoring/codes.rs
+use crate::app::core::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Stable identifier for every anomaly the monitor can report.
+///
+/// The string form returned by `as_str` is part of the reporting contract:
+/// dashboards key on it, so existing codes must never be renumbered. Codes also sort
+/// by it, so reports keep the same order whatever the declaration order of the variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AnomalyCode {
+    // Structural
+    MissingPlayerSpawn,
+    InsufficientWalkableSurfaces,
+    MissingEnergyOrbs,
+    OutOfBounds,
+    OverlappingModules,
+    // Rules
+    IncompatibleRules,
+    RuleEnvironmentMismatch,
+    MissingRuleHazards,
+    RuleSelectionShortfall,
+    MissingRuleRequirement,
+    // Balance
+    LowOrbDensity,
+    HighOrbDensity,
+    ExcessiveHazardDensity,
+    LowWalkableRatio,
+    WalkableAreaPadded,
+    DifficultyOffTarget,
+    // Modules
+    MissingPlayerModule,
+    DuplicatePlayerSpawn,
+    UnmatchedTeleporters,
+    // Spatial
+    HazardClustering,
+    IsolatedElement,
+    UnreachableOrb,
+    TemporaryPathOnly,
+    // Environment
+    EnvVariableOutOfRange,
+    MissingEnvVariable,
+    // Performance
+    SlowGeneration,
+    // Repair
+    ArenaRepaired,
+    // Templates
+    TemplatePlacementFailed,
+    // Retry
+    FallbackArenaUsed,
+}
+
+impl AnomalyCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnomalyCode::MissingPlayerSpawn => "STR-001",
+            AnomalyCode::InsufficientWalkableSurfaces => "STR-002",
+            AnomalyCode::MissingEnergyOrbs => "STR-003",
+            AnomalyCode::OutOfBounds => "STR-004",
+            AnomalyCode::OverlappingModules => "STR-005",
+            AnomalyCode::IncompatibleRules => "RUL-001",
+            AnomalyCode::RuleEnvironmentMismatch => "RUL-002",
+            AnomalyCode::MissingRuleHazards => "RUL-003",
+            AnomalyCode::RuleSelectionShortfall => "RUL-004",
+            AnomalyCode::MissingRuleRequirement => "RUL-005",
+            AnomalyCode::LowOrbDensity => "BAL-001",
+            AnomalyCode::HighOrbDensity => "BAL-002",
+            AnomalyCode::ExcessiveHazardDensity => "BAL-003",
+            AnomalyCode::LowWalkableRatio => "BAL-004",
+            AnomalyCode::WalkableAreaPadded => "BAL-005",
+            AnomalyCode::DifficultyOffTarget => "BAL-006",
+            AnomalyCode::MissingPlayerModule => "MOD-001",
+            AnomalyCode::DuplicatePlayerSpawn => "MOD-002",
+            AnomalyCode::UnmatchedTeleporters => "MOD-003",
+            AnomalyCode::HazardClustering => "SPA-001",
+            AnomalyCode::IsolatedElement => "SPA-002",
+            AnomalyCode::UnreachableOrb => "SPA-003",
+            AnomalyCode::TemporaryPathOnly => "SPA-004",
+            AnomalyCode::EnvVariableOutOfRange => "ENV-001",
+            AnomalyCode::MissingEnvVariable => "ENV-002",
+            AnomalyCode::SlowGeneration => "PRF-001",
+            AnomalyCode::ArenaRepaired => "REP-001",
+            AnomalyCode::TemplatePlacementFailed => "TPL-001",
+            AnomalyCode::FallbackArenaUsed => "RET-001",
+        }
+    }
+
+    /// Legacy category string, kept so existing `by_category` consumers still work
+    pub fn category(&self) -> &'static str {
+        match self {
+            AnomalyCode::MissingPlayerSpawn |
+            AnomalyCode::InsufficientWalkableSurfaces |
+            AnomalyCode::MissingEnergyOrbs |
+            AnomalyCode::OverlappingModules => "STRUCTURAL",
+            AnomalyCode::OutOfBounds => "BOUNDS",
+            AnomalyCode::IncompatibleRules |
+            AnomalyCode::RuleEnvironmentMismatch |
+            AnomalyCode::MissingRuleHazards |
+            AnomalyCode::RuleSelectionShortfall |
+            AnomalyCode::MissingRuleRequirement => "RULES",
+            AnomalyCode::LowOrbDensity |
+            AnomalyCode::HighOrbDensity |
+            AnomalyCode::ExcessiveHazardDensity |
+            AnomalyCode::LowWalkableRatio |
+            AnomalyCode::WalkableAreaPadded |
+            AnomalyCode::DifficultyOffTarget => "BALANCE",
+            AnomalyCode::MissingPlayerModule |
+            AnomalyCode::DuplicatePlayerSpawn |
+            AnomalyCode::UnmatchedTeleporters => "MODULES",
+            AnomalyCode::HazardClustering |
+            AnomalyCode::IsolatedElement => "SPATIAL",
+            AnomalyCode::UnreachableOrb |
+            AnomalyCode::TemporaryPathOnly => "REACHABILITY",
+            AnomalyCode::EnvVariableOutOfRange |
+            AnomalyCode::MissingEnvVariable => "ENVIRONMENT",
+            AnomalyCode::SlowGeneration => "PERFORMANCE",
+            AnomalyCode::ArenaRepaired => "REPAIR",
+            AnomalyCode::TemplatePlacementFailed => "TEMPLATES",
+            AnomalyCode::FallbackArenaUsed => "RETRY",
+        }
+    }
+}
+
+impl Ord for AnomalyCode {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl PartialOrd for AnomalyCode {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for AnomalyCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Inclusive bounding box of the cells involved in an anomaly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CellRegion {
+    pub min: (i32, i32),
+    pub max: (i32, i32),
+}
+
+impl CellRegion {
+    pub fn from_cells(cells: &[(i32, i32)]) -> Option<Self> {
+        let (first, rest) = cells.split_first()?;
+        let mut region = CellRegion { min: *first, max: *first };
+
+        for &(x, y) in rest {
+            region.min = (region.min.0.min(x), region.min.1.min(y));
+            region.max = (region.max.0.max(x), region.max.1.max(y));
+        }
+
+        Some(region)
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.min.0 && x <= self.max.0 && y >= self.min.1 && y <= self.max.1
+    }
+}
+
+/// Where an anomaly happened and which modules were involved
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnomalyLocation {
+    pub cells: Vec<(i32, i32)>,
+    pub region: Option<CellRegion>,
+    pub modules: Vec<ModuleId>,
+}
+
+impl AnomalyLocation {
+    /// Arena-wide anomaly with no specific cell
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn at(x: i32, y: i32) -> Self {
+        Self::cells(vec![(x, y)])
+    }
+
+    pub fn cells(cells: Vec<(i32, i32)>) -> Self {
+        let region = CellRegion::from_cells(&cells);
+        Self {
+            cells,
+            region,
+            modules: Vec::new(),
+        }
+    }
+
+    pub fn with_modules(mut self, modules: Vec<ModuleId>) -> Self {
+        self.modules = modules;
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty() && self.modules.is_empty()
+    }
+}
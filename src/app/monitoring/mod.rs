@@ -1,21 +1,44 @@
 use crate::app::core::*;
+use crate::app::data::{RuleGraph, RulesDatabase};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+mod codes;
+mod report;
+
+pub use codes::*;
+pub use report::*;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum AnomalySeverity {
     Critical,  // Arena unplayable/broken
     Warning,   // Suboptimal but playable
     Info,      // Unusual but not problematic
 }
 
+impl fmt::Display for AnomalySeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AnomalySeverity::Critical => "CRITICAL",
+            AnomalySeverity::Warning => "WARNING",
+            AnomalySeverity::Info => "INFO",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Anomaly {
+    pub code: AnomalyCode,
     pub category: String,
     pub message: String,
     pub severity: AnomalySeverity,
     pub detected_at: Instant,
-    pub context: Option<serde_json::Value>,
+    pub location: AnomalyLocation,
+    /// Generation stage that was running when the anomaly was reported
+    pub stage: Option<String>,
 }
 
 pub struct AnomalyMonitor {
@@ -23,6 +46,9 @@ pub struct AnomalyMonitor {
     metrics: HashMap<String, f64>,
     rules_applied: Vec<RuleId>,
     generation_start: Option<Instant>,
+    generations: usize,
+    current_stage: Option<String>,
+    stage_timings: Vec<(String, Duration)>,
 }
 
 impl AnomalyMonitor {
@@ -32,11 +58,29 @@ impl AnomalyMonitor {
             metrics: HashMap::new(),
             rules_applied: Vec::new(),
             generation_start: None,
+            generations: 0,
+            current_stage: None,
+            stage_timings: Vec::new(),
         }
     }
 
     pub fn start_generation(&mut self) {
         self.generation_start = Some(Instant::now());
+        self.generations += 1;
+        self.current_stage = None;
+    }
+
+    /// Attribute anomalies reported from now on to `stage`
+    pub fn begin_stage(&mut self, stage: &str) {
+        self.current_stage = Some(stage.to_string());
+    }
+
+    /// Close the current stage and record how long it took
+    pub fn end_stage(&mut self, elapsed: Duration) {
+        if let Some(stage) = self.current_stage.take() {
+            self.record_metric(&format!("stage_{}_ms", stage), elapsed.as_secs_f64() * 1000.0);
+            self.stage_timings.push((stage, elapsed));
+        }
     }
 
     pub fn record_rule_application(&mut self, rule_id: RuleId) {
@@ -54,25 +98,30 @@ impl AnomalyMonitor {
 
             if elapsed > Duration::from_secs(5) {
                 self.report_anomaly(
-                    "PERFORMANCE",
+                    AnomalyCode::SlowGeneration,
                     format!("Generation took {:.2}s (expected < 5s)", elapsed.as_secs_f64()),
                     AnomalySeverity::Warning,
-                    None,
+                    AnomalyLocation::none(),
                 );
             } else if elapsed > Duration::from_secs(10) {
                 self.report_anomaly(
-                    "PERFORMANCE",
+                    AnomalyCode::SlowGeneration,
                     format!("Generation took {:.2}s (critically slow)", elapsed.as_secs_f64()),
                     AnomalySeverity::Critical,
-                    None,
+                    AnomalyLocation::none(),
                 );
             }
         }
     }
 
     pub fn validate_arena(&mut self, arena: &Arena) {
+        self.validate_arena_with(arena, RulesDatabase::builtin().relations());
+    }
+
+    /// `validate_arena` against the rule relations of a given database
+    pub fn validate_arena_with(&mut self, arena: &Arena, relations: &RuleGraph) {
         self.check_structural_integrity(arena);
-        self.check_rule_compatibility(arena);
+        self.check_rule_compatibility(arena, relations);
         self.check_gameplay_balance(arena);
         self.check_module_distribution(arena);
         self.check_environmental_variables(arena);
@@ -80,46 +129,89 @@ impl AnomalyMonitor {
     }
 
     fn check_structural_integrity(&mut self, arena: &Arena) {
-        let issues = arena.validate_structural_integrity();
-        for issue in issues {
-            self.report_anomaly(
-                "STRUCTURAL",
-                issue,
-                AnomalySeverity::Critical,
-                None,
-            );
+        for issue in arena.structural_issues() {
+            let (code, location) = match &issue {
+                StructuralIssue::MissingPlayerSpawn => (
+                    AnomalyCode::MissingPlayerSpawn,
+                    AnomalyLocation::none().with_modules(vec![ModuleId::Player]),
+                ),
+                StructuralIssue::InsufficientWalkableSurfaces(walkable) => (
+                    AnomalyCode::InsufficientWalkableSurfaces,
+                    AnomalyLocation::cells(walkable.clone()),
+                ),
+                StructuralIssue::MissingEnergyOrbs => (
+                    AnomalyCode::MissingEnergyOrbs,
+                    AnomalyLocation::none().with_modules(vec![ModuleId::OrbEnergy]),
+                ),
+            };
+            self.report_anomaly(code, issue.to_string(), AnomalySeverity::Critical, location);
         }
 
         // Check arena bounds
+        let footprint = if arena.mask.is_some() { "footprint" } else { "bounds" };
         for cell in &arena.modules {
-            if cell.x < 0 || cell.y < 0 ||
-               cell.x >= arena.width as i32 || cell.y >= arena.height as i32 {
+            if !arena.is_valid_position(cell.x, cell.y) {
                 self.report_anomaly(
-                    "BOUNDS",
-                    format!("Module at ({}, {}) outside arena bounds {}x{}",
-                           cell.x, cell.y, arena.width, arena.height),
+                    AnomalyCode::OutOfBounds,
+                    format!("Module at ({}, {}) outside arena {} {}x{}",
+                           cell.x, cell.y, footprint, arena.width, arena.height),
                     AnomalySeverity::Critical,
-                    None,
+                    AnomalyLocation::at(cell.x, cell.y).with_modules(vec![cell.module_id.clone()]),
                 );
             }
         }
+
+        // Check for cells holding more than one module
+        let mut occupants: HashMap<(i32, i32), Vec<ModuleId>> = HashMap::new();
+        for cell in &arena.modules {
+            occupants.entry((cell.x, cell.y)).or_default().push(cell.module_id.clone());
+        }
+
+        let mut overlapping: Vec<_> = occupants.into_iter()
+            .filter(|(_, modules)| modules.len() > 1)
+            .collect();
+        overlapping.sort_by_key(|(pos, _)| *pos);
+
+        for ((x, y), modules) in overlapping {
+            self.report_anomaly(
+                AnomalyCode::OverlappingModules,
+                format!("{} modules stacked at ({}, {}): {:?}", modules.len(), x, y, modules),
+                AnomalySeverity::Warning,
+                AnomalyLocation::at(x, y).with_modules(modules),
+            );
+        }
     }
 
-    fn check_rule_compatibility(&mut self, arena: &Arena) {
+    fn check_rule_compatibility(&mut self, arena: &Arena, relations: &RuleGraph) {
         // Check for conflicting rules
         for (i, rule1) in arena.active_rules.iter().enumerate() {
             for rule2 in arena.active_rules.iter().skip(i + 1) {
-                if rule1.incompatible_with.contains(&rule2.id) {
+                if relations.conflicts(&rule1.id, &rule2.id) {
                     self.report_anomaly(
-                        "RULES",
+                        AnomalyCode::IncompatibleRules,
                         format!("Incompatible rules active: {:?} and {:?}", rule1.id, rule2.id),
                         AnomalySeverity::Critical,
-                        None,
+                        AnomalyLocation::none(),
                     );
                 }
             }
         }
 
+        // Check that every active rule brings what it requires
+        for rule in &arena.active_rules {
+            let missing: Vec<RuleId> = relations.requirements(&rule.id).into_iter()
+                .filter(|required| !arena.active_rules.iter().any(|r| &r.id == required))
+                .collect();
+            if !missing.is_empty() {
+                self.report_anomaly(
+                    AnomalyCode::MissingRuleRequirement,
+                    format!("{:?} is active without the rules it requires: {:?}", rule.id, missing),
+                    AnomalySeverity::Critical,
+                    AnomalyLocation::none(),
+                );
+            }
+        }
+
         // Check rule-environment consistency
         for rule in &arena.active_rules {
             match rule.id {
@@ -127,10 +219,10 @@ impl AnomalyMonitor {
                     if let Some(&gravity) = arena.env_variables.get(&EnvVarId::Gravity) {
                         if gravity > 0.5 {
                             self.report_anomaly(
-                                "RULES",
+                                AnomalyCode::RuleEnvironmentMismatch,
                                 format!("Moon Gravity rule active but gravity is {:.2} (expected < 0.5)", gravity),
                                 AnomalySeverity::Warning,
-                                None,
+                                AnomalyLocation::none(),
                             );
                         }
                     }
@@ -139,20 +231,28 @@ impl AnomalyMonitor {
                     let lava_count = arena.count_modules_by_type(&ModuleId::HazardLavaPit);
                     if lava_count == 0 {
                         self.report_anomaly(
-                            "RULES",
+                            AnomalyCode::MissingRuleHazards,
                             "Lava Floor rule active but no lava pits found".to_string(),
                             AnomalySeverity::Warning,
-                            None,
+                            AnomalyLocation::none().with_modules(vec![ModuleId::HazardLavaPit]),
                         );
                     }
                 }
+                RuleId::CrumblingFloor if arena.count_modules_by_type(&ModuleId::FloorFragile) == 0 => {
+                    self.report_anomaly(
+                        AnomalyCode::MissingRuleHazards,
+                        "Crumbling Floor rule active but no fragile floors found".to_string(),
+                        AnomalySeverity::Warning,
+                        AnomalyLocation::none().with_modules(vec![ModuleId::FloorFragile]),
+                    );
+                }
                 _ => {}
             }
         }
     }
 
     fn check_gameplay_balance(&mut self, arena: &Arena) {
-        let total_cells = (arena.width * arena.height) as f64;
+        let total_cells = arena.playable_cell_count() as f64;
 
         // Energy orb density
         let orb_count = arena.count_modules_by_type(&ModuleId::OrbEnergy) as f64;
@@ -162,37 +262,38 @@ impl AnomalyMonitor {
 
         if orb_density < 0.05 {
             self.report_anomaly(
-                "BALANCE",
+                AnomalyCode::LowOrbDensity,
                 format!("Low energy orb density: {:.3} (recommended > 0.05)", orb_density),
                 AnomalySeverity::Warning,
-                None,
+                AnomalyLocation::none().with_modules(vec![ModuleId::OrbEnergy]),
             );
         } else if orb_density > 0.3 {
             self.report_anomaly(
-                "BALANCE",
+                AnomalyCode::HighOrbDensity,
                 format!("High energy orb density: {:.3} (recommended < 0.3)", orb_density),
                 AnomalySeverity::Info,
-                None,
+                AnomalyLocation::none().with_modules(vec![ModuleId::OrbEnergy]),
             );
         }
 
         // Hazard density
-        let hazard_count = arena.modules.iter()
+        let hazard_positions: Vec<(i32, i32)> = arena.modules.iter()
             .filter(|cell| matches!(cell.module_id,
                 ModuleId::HazardLavaPit |
                 ModuleId::HazardLaserEmitterStatic |
                 ModuleId::HazardLaserTurretRotate))
-            .count() as f64;
+            .map(|cell| (cell.x, cell.y))
+            .collect();
 
-        let hazard_density = hazard_count / total_cells;
+        let hazard_density = hazard_positions.len() as f64 / total_cells;
         self.record_metric("hazard_density", hazard_density);
 
         if hazard_density > 0.4 {
             self.report_anomaly(
-                "BALANCE",
+                AnomalyCode::ExcessiveHazardDensity,
                 format!("Excessive hazard density: {:.3} (recommended < 0.4)", hazard_density),
                 AnomalySeverity::Warning,
-                None,
+                AnomalyLocation::cells(hazard_positions),
             );
         }
 
@@ -207,10 +308,10 @@ impl AnomalyMonitor {
 
         if walkable_ratio < 0.3 {
             self.report_anomaly(
-                "BALANCE",
+                AnomalyCode::LowWalkableRatio,
                 format!("Insufficient walkable area: {:.3} (recommended > 0.3)", walkable_ratio),
                 AnomalySeverity::Critical,
-                None,
+                AnomalyLocation::none().with_modules(vec![ModuleId::FloorStd, ModuleId::FloorLarge, ModuleId::RampSteep]),
             );
         }
     }
@@ -228,11 +329,25 @@ impl AnomalyMonitor {
         // Check for mandatory modules
         if !module_counts.contains_key(&ModuleId::Player) {
             self.report_anomaly(
-                "MODULES",
+                AnomalyCode::MissingPlayerModule,
                 "Player module missing".to_string(),
                 AnomalySeverity::Critical,
-                None,
+                AnomalyLocation::none().with_modules(vec![ModuleId::Player]),
             );
+        } else if let Some(&count) = module_counts.get(&ModuleId::Player) {
+            if count > 1 {
+                let spawns = arena.modules.iter()
+                    .filter(|cell| matches!(cell.module_id, ModuleId::Player))
+                    .map(|cell| (cell.x, cell.y))
+                    .collect();
+
+                self.report_anomaly(
+                    AnomalyCode::DuplicatePlayerSpawn,
+                    format!("Multiple player spawns found: {}", count),
+                    AnomalySeverity::Warning,
+                    AnomalyLocation::cells(spawns).with_modules(vec![ModuleId::Player]),
+                );
+            }
         }
 
         // Check teleporter pairs
@@ -240,11 +355,17 @@ impl AnomalyMonitor {
         let teleporter_out = module_counts.get(&ModuleId::MoveTeleporterOut).unwrap_or(&0);
 
         if teleporter_in != teleporter_out && (*teleporter_in > 0 || *teleporter_out > 0) {
+            let teleporters = arena.modules.iter()
+                .filter(|cell| matches!(cell.module_id, ModuleId::MoveTeleporterIn | ModuleId::MoveTeleporterOut))
+                .map(|cell| (cell.x, cell.y))
+                .collect();
+
             self.report_anomaly(
-                "MODULES",
+                AnomalyCode::UnmatchedTeleporters,
                 format!("Unmatched teleporters: {} in, {} out", teleporter_in, teleporter_out),
                 AnomalySeverity::Warning,
-                None,
+                AnomalyLocation::cells(teleporters)
+                    .with_modules(vec![ModuleId::MoveTeleporterIn, ModuleId::MoveTeleporterOut]),
             );
         }
     }
@@ -263,10 +384,10 @@ impl AnomalyMonitor {
 
                     if avg_distance < 2.0 {
                         self.report_anomaly(
-                            "SPATIAL",
+                            AnomalyCode::HazardClustering,
                             format!("{:?} modules too clustered (avg distance: {:.1})", module_id, avg_distance),
                             AnomalySeverity::Warning,
-                            None,
+                            AnomalyLocation::cells(positions).with_modules(vec![module_id.clone()]),
                         );
                     }
                 }
@@ -303,11 +424,11 @@ impl AnomalyMonitor {
 
             if value < expected_min || value > expected_max {
                 self.report_anomaly(
-                    "ENVIRONMENT",
+                    AnomalyCode::EnvVariableOutOfRange,
                     format!("{:?} value {:.2} outside expected range [{:.1}, {:.1}]",
                            env_id, value, expected_min, expected_max),
                     AnomalySeverity::Warning,
-                    None,
+                    AnomalyLocation::none(),
                 );
             }
         }
@@ -315,10 +436,10 @@ impl AnomalyMonitor {
         // Check for missing critical environmental variables
         if !arena.env_variables.contains_key(&EnvVarId::Gravity) {
             self.report_anomaly(
-                "ENVIRONMENT",
+                AnomalyCode::MissingEnvVariable,
                 "Gravity environmental variable not set".to_string(),
                 AnomalySeverity::Critical,
-                None,
+                AnomalyLocation::none(),
             );
         }
     }
@@ -331,11 +452,11 @@ impl AnomalyMonitor {
 
                 if adjacent_walkable == 0 {
                     self.report_anomaly(
-                        "SPATIAL",
+                        AnomalyCode::IsolatedElement,
                         format!("{:?} at ({}, {}) is isolated (no adjacent walkable surfaces)",
                                cell.module_id, cell.x, cell.y),
                         AnomalySeverity::Critical,
-                        None,
+                        AnomalyLocation::at(cell.x, cell.y).with_modules(vec![cell.module_id.clone()]),
                     );
                 }
             }
@@ -365,73 +486,57 @@ impl AnomalyMonitor {
     }
 
     fn check_reachability(&mut self, arena: &Arena) {
-        // Simple flood-fill from player position to check reachability
-        let player_pos = arena.modules.iter()
-            .find(|cell| matches!(cell.module_id, ModuleId::Player))
-            .map(|cell| (cell.x, cell.y));
-
-        if let Some(start) = player_pos {
-            let reachable = self.flood_fill_reachable(arena, start);
+        // Flood-fill from player position, same traversal rules as the arena validator
+        if let Some(start) = arena.get_player_position() {
+            let reachable = arena.get_reachable_positions(start);
 
-            let orb_positions: Vec<_> = arena.modules.iter()
+            let unreachable_orbs: Vec<_> = arena.modules.iter()
                 .filter(|cell| matches!(cell.module_id, ModuleId::OrbEnergy))
                 .map(|cell| (cell.x, cell.y))
+                .filter(|pos| !reachable.contains(pos))
                 .collect();
 
-            let unreachable_orbs = orb_positions.iter()
-                .filter(|pos| !reachable.contains(pos))
-                .count();
+            self.record_metric("unreachable_orbs", unreachable_orbs.len() as f64);
 
-            /*if unreachable_orbs > 0 {
+            if !unreachable_orbs.is_empty() {
                 self.report_anomaly(
-                    "REACHABILITY",
-                    format!("{} energy orbs are unreachable from player spawn", unreachable_orbs),
+                    AnomalyCode::UnreachableOrb,
+                    format!("{} energy orbs are unreachable from player spawn", unreachable_orbs.len()),
                     AnomalySeverity::Critical,
-                    None,
+                    AnomalyLocation::cells(unreachable_orbs).with_modules(vec![ModuleId::OrbEnergy]),
                 );
-            }*/
-        }
-    }
+            }
 
-    fn flood_fill_reachable(&self, arena: &Arena, start: (i32, i32)) -> std::collections::HashSet<(i32, i32)> {
-        let mut visited = std::collections::HashSet::new();
-        let mut stack = vec![start];
+            // Fragile floors count as ground only while intact
+            let permanent = arena.get_permanently_reachable_positions(start);
+            let temporary_orbs: Vec<_> = arena.modules.iter()
+                .filter(|cell| matches!(cell.module_id, ModuleId::OrbEnergy))
+                .map(|cell| (cell.x, cell.y))
+                .filter(|pos| reachable.contains(pos) && !permanent.contains(pos))
+                .collect();
 
-        while let Some((x, y)) = stack.pop() {
-            if visited.contains(&(x, y)) {
-                continue;
-            }
-            visited.insert((x, y));
-
-            // Check adjacent cells
-            for (dx, dy) in [(0, 1), (0, -1), (1, 0), (-1, 0)].iter() {
-                let nx = x + dx;
-                let ny = y + dy;
-
-                if !visited.contains(&(nx, ny)) {
-                    if let Some(cell) = arena.get_cell(nx, ny) {
-                        // Consider walkable or collectible surfaces as reachable
-                        if matches!(cell.module_id,
-                            ModuleId::FloorStd | ModuleId::FloorLarge |
-                            ModuleId::RampSteep | ModuleId::OrbEnergy |
-                            ModuleId::InteractButtonFloor) {
-                            stack.push((nx, ny));
-                        }
-                    }
-                }
+            self.record_metric("temporary_path_orbs", temporary_orbs.len() as f64);
+
+            if !temporary_orbs.is_empty() {
+                self.report_anomaly(
+                    AnomalyCode::TemporaryPathOnly,
+                    format!("{} energy orbs are only reachable across fragile floors", temporary_orbs.len()),
+                    AnomalySeverity::Warning,
+                    AnomalyLocation::cells(temporary_orbs).with_modules(vec![ModuleId::OrbEnergy, ModuleId::FloorFragile]),
+                );
             }
         }
-
-        visited
     }
 
-    pub fn report_anomaly(&mut self, category: &str, message: String, severity: AnomalySeverity, context: Option<serde_json::Value>) {
+    pub fn report_anomaly(&mut self, code: AnomalyCode, message: String, severity: AnomalySeverity, location: AnomalyLocation) {
         let anomaly = Anomaly {
-            category: category.to_string(),
+            code,
+            category: code.category().to_string(),
             message,
             severity,
             detected_at: Instant::now(),
-            context,
+            location,
+            stage: self.current_stage.clone(),
         };
         self.anomalies.push(anomaly);
     }
@@ -444,12 +549,52 @@ impl AnomalyMonitor {
         &self.metrics
     }
 
+    pub fn get_anomalies_by_stage(&self, stage: &str) -> Vec<&Anomaly> {
+        self.anomalies.iter()
+            .filter(|a| a.stage.as_deref() == Some(stage))
+            .collect()
+    }
+
+    /// Duration of every stage run, in execution order
+    pub fn get_stage_timings(&self) -> &[(String, Duration)] {
+        &self.stage_timings
+    }
+
+    /// Rules in the order they were applied, without repeats
+    pub fn get_rules_applied(&self) -> Vec<RuleId> {
+        let mut unique: Vec<RuleId> = Vec::new();
+        for rule_id in &self.rules_applied {
+            if !unique.contains(rule_id) {
+                unique.push(rule_id.clone());
+            }
+        }
+        unique
+    }
+
+    pub fn generation_count(&self) -> usize {
+        self.generations
+    }
+
     pub fn has_critical_anomalies(&self) -> bool {
         self.anomalies.iter().any(|a| a.severity == AnomalySeverity::Critical)
     }
 
+    pub fn get_anomalies_by_code(&self, code: AnomalyCode) -> Vec<&Anomaly> {
+        self.anomalies.iter()
+            .filter(|a| a.code == code)
+            .collect()
+    }
+
+    /// Fold another monitor in; its unattributed anomalies go to the current stage
     pub fn merge(&mut self, other: AnomalyMonitor) {
-        self.anomalies.extend(other.anomalies);
+        for mut anomaly in other.anomalies {
+            if anomaly.stage.is_none() {
+                anomaly.stage = self.current_stage.clone();
+            }
+            self.anomalies.push(anomaly);
+        }
+        self.generations += other.generations;
+        self.stage_timings.extend(other.stage_timings);
 
         for (key, value) in other.metrics {
             *self.metrics.entry(key).or_insert(0.0) += value;
@@ -459,16 +604,35 @@ impl AnomalyMonitor {
     pub fn get_summary(&self) -> MonitoringSummary {
         let mut by_severity = HashMap::new();
         let mut by_category = HashMap::new();
+        let mut by_code: HashMap<AnomalyCode, CodeSummary> = HashMap::new();
+        let mut by_stage = HashMap::new();
 
         for anomaly in &self.anomalies {
             *by_severity.entry(anomaly.severity.clone()).or_insert(0) += 1;
             *by_category.entry(anomaly.category.clone()).or_insert(0) += 1;
+            if let Some(stage) = &anomaly.stage {
+                *by_stage.entry(stage.clone()).or_insert(0) += 1;
+            }
+
+            let entry = by_code.entry(anomaly.code).or_default();
+            entry.total += 1;
+            match anomaly.severity {
+                AnomalySeverity::Critical => entry.critical += 1,
+                AnomalySeverity::Warning => entry.warning += 1,
+                AnomalySeverity::Info => entry.info += 1,
+            }
+            if entry.sample_message.is_none() {
+                entry.sample_message = Some(anomaly.message.clone());
+            }
         }
 
         MonitoringSummary {
+            generations: self.generations,
             total_anomalies: self.anomalies.len(),
             by_severity,
             by_category,
+            by_code,
+            by_stage,
             metrics: self.metrics.clone(),
         }
     }
@@ -476,8 +640,20 @@ impl AnomalyMonitor {
 
 #[derive(Debug)]
 pub struct MonitoringSummary {
+    pub generations: usize,
     pub total_anomalies: usize,
     pub by_severity: HashMap<AnomalySeverity, usize>,
     pub by_category: HashMap<String, usize>,
+    pub by_code: HashMap<AnomalyCode, CodeSummary>,
+    pub by_stage: HashMap<String, usize>,
     pub metrics: HashMap<String, f64>,
 }
+
+#[derive(Debug, Clone, Default)]
+pub struct CodeSummary {
+    pub total: usize,
+    pub critical: usize,
+    pub warning: usize,
+    pub info: usize,
+    pub sample_message: Option<String>,
+}
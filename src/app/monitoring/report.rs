@@ -0,0 +1,176 @@
+//monitoring/report.rs
+use super::*;
+use serde_json::json;
+use std::fmt::Write;
+
+impl MonitoringSummary {
+    /// Machine-readable summary, stable across runs (keys are sorted)
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut codes: Vec<_> = self.by_code.iter().collect();
+        codes.sort_by_key(|(code, _)| **code);
+
+        let by_code: serde_json::Map<String, serde_json::Value> = codes.into_iter()
+            .map(|(code, summary)| {
+                (code.as_str().to_string(), json!({
+                    "name": format!("{:?}", code),
+                    "category": code.category(),
+                    "total": summary.total,
+                    "critical": summary.critical,
+                    "warning": summary.warning,
+                    "info": summary.info,
+                    "frequency": self.frequency(summary.total),
+                    "sample_message": summary.sample_message,
+                }))
+            })
+            .collect();
+
+        let by_severity: serde_json::Map<String, serde_json::Value> = [
+            AnomalySeverity::Critical,
+            AnomalySeverity::Warning,
+            AnomalySeverity::Info,
+        ].iter()
+            .map(|severity| (severity.to_string(), json!(self.by_severity.get(severity).copied().unwrap_or(0))))
+            .collect();
+
+        let mut categories: Vec<_> = self.by_category.iter().collect();
+        categories.sort();
+        let by_category: serde_json::Map<String, serde_json::Value> = categories.into_iter()
+            .map(|(category, count)| (category.clone(), json!(count)))
+            .collect();
+
+        let mut stages: Vec<_> = self.by_stage.iter().collect();
+        stages.sort();
+        let by_stage: serde_json::Map<String, serde_json::Value> = stages.into_iter()
+            .map(|(stage, count)| (stage.clone(), json!(count)))
+            .collect();
+
+        let mut metrics: Vec<_> = self.metrics.iter().collect();
+        metrics.sort_by(|a, b| a.0.cmp(b.0));
+        let metrics: serde_json::Map<String, serde_json::Value> = metrics.into_iter()
+            .map(|(name, value)| (name.clone(), json!(value)))
+            .collect();
+
+        json!({
+            "generations": self.generations,
+            "total_anomalies": self.total_anomalies,
+            "by_severity": by_severity,
+            "by_category": by_category,
+            "by_code": by_code,
+            "by_stage": by_stage,
+            "metrics": metrics,
+        })
+    }
+
+    pub fn to_json_string(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&self.to_json())
+    }
+
+    /// JUnit XML report: one test suite per category, one test case per anomaly code.
+    /// A code fails when it produced at least one critical anomaly.
+    pub fn to_junit_xml(&self, suite_name: &str) -> String {
+        let mut by_category: HashMap<&str, Vec<(&AnomalyCode, &CodeSummary)>> = HashMap::new();
+        for (code, summary) in &self.by_code {
+            by_category.entry(code.category()).or_default().push((code, summary));
+        }
+
+        let mut categories: Vec<_> = by_category.into_iter().collect();
+        categories.sort_by_key(|(category, _)| *category);
+
+        let total_tests = self.by_code.len();
+        let total_failures = self.by_code.values().filter(|s| s.critical > 0).count();
+
+        let mut xml = String::new();
+        let _ = writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        let _ = writeln!(
+            xml,
+            r#"<testsuites name="{}" tests="{}" failures="{}">"#,
+            escape_xml(suite_name), total_tests, total_failures
+        );
+
+        for (category, mut codes) in categories {
+            codes.sort_by_key(|(code, _)| **code);
+            let failures = codes.iter().filter(|(_, s)| s.critical > 0).count();
+
+            let _ = writeln!(
+                xml,
+                r#"  <testsuite name="{}" tests="{}" failures="{}">"#,
+                escape_xml(category), codes.len(), failures
+            );
+
+            for (code, summary) in codes {
+                let _ = writeln!(
+                    xml,
+                    r#"    <testcase classname="{}" name="{} {:?}">"#,
+                    escape_xml(category), code.as_str(), code
+                );
+
+                if summary.critical > 0 {
+                    let _ = writeln!(
+                        xml,
+                        r#"      <failure message="{} critical anomalies over {} generations">{}</failure>"#,
+                        summary.critical,
+                        self.generations,
+                        escape_xml(summary.sample_message.as_deref().unwrap_or(""))
+                    );
+                }
+
+                let _ = writeln!(
+                    xml,
+                    "      <system-out>total={} critical={} warning={} info={} frequency={:.4}</system-out>",
+                    summary.total, summary.critical, summary.warning, summary.info,
+                    self.frequency(summary.total)
+                );
+                let _ = writeln!(xml, "    </testcase>");
+            }
+
+            let _ = writeln!(xml, "  </testsuite>");
+        }
+
+        let _ = writeln!(xml, "</testsuites>");
+        xml
+    }
+
+    /// Occurrences per generation run
+    fn frequency(&self, count: usize) -> f64 {
+        if self.generations == 0 {
+            count as f64
+        } else {
+            count as f64 / self.generations as f64
+        }
+    }
+}
+
+impl AnomalyMonitor {
+    /// Every recorded anomaly with its code, cells and modules
+    pub fn anomalies_to_json(&self) -> serde_json::Value {
+        let anomalies: Vec<_> = self.get_anomalies().iter()
+            .map(|anomaly| json!({
+                "code": anomaly.code.as_str(),
+                "category": anomaly.category,
+                "severity": anomaly.severity.to_string(),
+                "stage": anomaly.stage,
+                "message": anomaly.message,
+                "cells": anomaly.location.cells,
+                "region": anomaly.location.region,
+                "modules": anomaly.location.modules,
+            }))
+            .collect();
+
+        serde_json::Value::Array(anomalies)
+    }
+}
+
+fn escape_xml(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
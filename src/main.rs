@@ -16,6 +16,7 @@ use bevy::winit::WinitSettings;
 use shiftropolis::app::{
     camera::ContextualCameraPlugin,
     game::GamePlugin,
+    mesh_generation::MeshGenerationPlugin,
     ui::UIPlugin,
 };
 
@@ -60,6 +61,7 @@ fn main() {
     // Ajoute les plugins de votre jeu.
     app.add_plugins((
         GamePlugin,
+        MeshGenerationPlugin,
         UIPlugin,
         ContextualCameraPlugin,
     ));
@@ -0,0 +1,1075 @@
+//tests/integration.rs
+//
+// Golden snapshots and generator invariants.
+//
+// Snapshots live in tests/golden. A missing or different snapshot fails the test;
+// set UPDATE_GOLDEN=1 to write them after an intended generation change.
+// Property tests run from a fixed seed; set GENERATOR_PROPERTY_SEED to try another.
+
+use shiftropolis::app::core::*;
+use shiftropolis::app::data::*;
+use shiftropolis::app::generation::*;
+use shiftropolis::app::monitoring::*;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const GOLDEN_CASES: &[(u64, u32, u32)] = &[
+    (1, 8, 1),
+    (7, 12, 2),
+    (42, 12, 3),
+    (1234, 16, 3),
+];
+
+const PROPERTY_CASES: usize = 64;
+const PROPERTY_SEED: u64 = 0x5348_4946_5421;
+const PROPERTY_SIZES: &[u32] = &[6, 8, 12, 16, 20];
+
+fn generate(seed: u64, size: u32, rule_count: u32) -> GenerationResult<Arena> {
+    let mut monitor = AnomalyMonitor::new();
+    ArenaGenerator::new(Some(seed)).generate_with_monitoring(size, rule_count, &mut monitor)
+}
+
+/// Arena as JSON, minus the fields that change on every run (uuid, timing)
+fn snapshot(arena: &Arena) -> Value {
+    let mut value = serde_json::to_value(arena).expect("arena serializes");
+    let object = value.as_object_mut().unwrap();
+    object.remove("id");
+    object["generation_metadata"]["generation_time_ms"] = Value::from(0);
+    value
+}
+
+fn golden_path(seed: u64, size: u32, rule_count: u32) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("seed{}_size{}_rules{}.json", seed, size, rule_count))
+}
+
+#[test]
+fn golden_arenas_are_unchanged() {
+    let update = std::env::var("UPDATE_GOLDEN").is_ok();
+
+    for &(seed, size, rule_count) in GOLDEN_CASES {
+        let arena = generate(seed, size, rule_count)
+            .unwrap_or_else(|e| panic!("golden seed {} size {} failed: {}", seed, size, e));
+        // Compared as text: parsing floats back out of JSON is not bit-exact
+        let actual = serde_json::to_string_pretty(&snapshot(&arena)).unwrap() + "\n";
+        let path = golden_path(seed, size, rule_count);
+
+        if update {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, &actual).unwrap();
+            eprintln!("wrote golden snapshot {}", path.display());
+            continue;
+        }
+
+        let expected = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+            panic!("missing golden snapshot {} ({}); rerun with UPDATE_GOLDEN=1 to write it", path.display(), e)
+        });
+        assert!(
+            actual == expected,
+            "arena for seed {} size {} rules {} differs from {} (rerun with UPDATE_GOLDEN=1 if intended)",
+            seed, size, rule_count, path.display()
+        );
+    }
+}
+
+#[test]
+fn same_seed_generates_same_arena() {
+    let mut compared = 0;
+    for seed in 0..8 {
+        let (Ok(a), Ok(b)) = (generate(seed, 12, 2), generate(seed, 12, 2)) else {
+            continue;
+        };
+        assert_eq!(snapshot(&a), snapshot(&b), "seed {} is not deterministic", seed);
+        compared += 1;
+    }
+    assert!(compared > 0, "no seed generated an arena to compare");
+}
+
+/// Seeds for the property tests: fixed by default, overridden through GENERATOR_PROPERTY_SEED
+fn property_cases() -> (u64, Vec<(u64, u32, u32)>) {
+    let run_seed = std::env::var("GENERATOR_PROPERTY_SEED")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(PROPERTY_SEED);
+    let mut rng = StdRng::seed_from_u64(run_seed);
+
+    let cases = (0..PROPERTY_CASES)
+        .map(|_| {
+            let seed = rng.r#gen();
+            let size = PROPERTY_SIZES[rng.gen_range(0..PROPERTY_SIZES.len())];
+            let rule_count = rng.gen_range(0..=3);
+            (seed, size, rule_count)
+        })
+        .collect();
+
+    (run_seed, cases)
+}
+
+#[test]
+fn generated_arenas_hold_invariants() {
+    let (run_seed, cases) = property_cases();
+    let mut generated = 0;
+
+    for (seed, size, rule_count) in cases {
+        // Critical failures are the generator refusing an arena, not an invariant break
+        let Ok(arena) = generate(seed, size, rule_count) else {
+            continue;
+        };
+        generated += 1;
+
+        let case = format!("GENERATOR_PROPERTY_SEED={} seed={} size={} rules={}", run_seed, seed, size, rule_count);
+
+        // All cells within bounds
+        for cell in &arena.modules {
+            assert!(
+                arena.is_valid_position(cell.x, cell.y),
+                "{}: {:?} at ({}, {}) is out of bounds", case, cell.module_id, cell.x, cell.y
+            );
+        }
+
+        // Exactly one player
+        let players = arena.get_modules_by_type(&ModuleId::Player).len();
+        assert_eq!(players, 1, "{}: expected one player spawn, found {}", case, players);
+
+        // No two modules on the same cell
+        let mut occupied = HashSet::new();
+        for cell in &arena.modules {
+            assert!(occupied.insert((cell.x, cell.y)), "{}: two modules on ({}, {})", case, cell.x, cell.y);
+        }
+
+        // Every orb reachable from the spawn
+        let spawn = arena.get_player_position().unwrap();
+        let reachable = arena.get_reachable_positions(spawn);
+        for orb in arena.get_modules_by_type(&ModuleId::OrbEnergy) {
+            assert!(reachable.contains(&(orb.x, orb.y)), "{}: orb at ({}, {}) unreachable", case, orb.x, orb.y);
+        }
+    }
+
+    assert!(
+        generated * 2 >= PROPERTY_CASES,
+        "GENERATOR_PROPERTY_SEED={}: only {}/{} arenas generated", run_seed, generated, PROPERTY_CASES
+    );
+}
+
+#[test]
+fn minimal_repro_replays_its_failure() {
+    // Seed 20 leaves an orb isolated with three rules on a 6x6 arena
+    let report = run_stress_test(&StressConfig::new(20..21).with_sizes(vec![6]).with_rule_counts(vec![3]));
+    let failure = report.failing_seeds.first().expect("seed 20 size 6 with 3 rules fails generation");
+
+    let repro = failure.shrink().remove(0).expect("stress failure reproduces with its rules");
+
+    assert!(repro.size <= failure.case.size);
+    assert!(repro.rules.len() <= failure.rules.len());
+
+    let mut monitor = AnomalyMonitor::new();
+    assert!(repro.replay(&mut monitor).is_err());
+    assert!(!monitor.get_anomalies_by_code(repro.code).is_empty());
+}
+
+#[test]
+fn shaped_arenas_stay_inside_their_footprint() {
+    let shapes = [
+        ArenaShape::Circle,
+        ArenaShape::Cross,
+        ArenaShape::Ring { thickness: 4 },
+        ArenaShape::LShape,
+        ArenaShape::Mask(ArenaMask::from_bitmap(&[
+            "########....",
+            "########....",
+            "############",
+            "############",
+            "....########",
+            "....########",
+        ])),
+    ];
+
+    for shape in shapes {
+        let mut generated = 0;
+
+        for seed in 0..16 {
+            let mut monitor = AnomalyMonitor::new();
+            let Ok(arena) = ArenaGenerator::new(Some(seed))
+                .with_shape(shape.clone())
+                .generate_with_dimensions(16, 12, 2, &mut monitor)
+            else {
+                continue;
+            };
+            generated += 1;
+
+            let mask = arena.mask.as_ref().expect("shaped arena has a mask");
+            for cell in &arena.modules {
+                assert!(
+                    mask.contains(cell.x, cell.y),
+                    "{} seed {}: {:?} at ({}, {}) outside the footprint", shape.name(), seed, cell.module_id, cell.x, cell.y
+                );
+            }
+        }
+
+        assert!(generated >= 8, "{}: only {}/16 arenas generated", shape.name(), generated);
+    }
+}
+
+#[test]
+fn templates_are_stamped_and_recorded() {
+    let moat = TemplateLibrary::lava_moat();
+
+    // Mirroring twice or rotating four times is the identity
+    let identity = moat.transformed_cells(TemplateTransform { quarter_turns: 0, mirrored: false });
+    let full_turn = moat.transformed_cells(TemplateTransform { quarter_turns: 4, mirrored: false });
+    let positions = |cells: &[TemplateCell]| cells.iter().map(|c| (c.x, c.y, c.module_id.clone())).collect::<Vec<_>>();
+    assert_eq!(positions(&identity), positions(&full_turn));
+
+    for seed in 0..16 {
+        let mut monitor = AnomalyMonitor::new();
+        let Ok(arena) = ArenaGenerator::new(Some(seed))
+            .with_templates(vec![moat.clone()])
+            .generate_with_monitoring(12, 1, &mut monitor)
+        else {
+            continue;
+        };
+
+        assert!(
+            arena.generation_metadata.constraints_applied.iter().any(|c| c == "template:lava_moat"),
+            "seed {}: lava moat not recorded in {:?}", seed, arena.generation_metadata.constraints_applied
+        );
+    }
+}
+
+/// Turns every empty playable cell into floor
+struct FillFloorStage;
+
+impl GenerationStage for FillFloorStage {
+    fn name(&self) -> &str {
+        "fill_floor"
+    }
+
+    fn run(&self, ctx: &mut StageContext<'_>) -> GenerationResult<()> {
+        for (x, y) in ctx.arena.playable_positions() {
+            if ctx.arena.get_cell(x, y).is_none() {
+                ctx.arena.add_module(x, y, ModuleId::FloorStd, None);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn pipeline_stages_can_be_customized_and_inspected() {
+    let mut pipeline = GenerationPipeline::standard().with_debug_snapshots(true);
+    pipeline.insert_after("balance", FillFloorStage).unwrap();
+    pipeline.remove("templates").unwrap();
+    assert!(pipeline.remove("missing").is_err());
+
+    let names: Vec<String> = pipeline.stage_names().iter().map(|s| s.to_string()).collect();
+    let mut generator = ArenaGenerator::new(Some(42)).with_pipeline(pipeline);
+    let mut monitor = AnomalyMonitor::new();
+    let arena = generator.generate_with_monitoring(12, 2, &mut monitor).unwrap();
+
+    // One snapshot and one timing per stage, in order
+    let snapshot_names: Vec<&str> = generator.stage_snapshots().iter().map(|s| s.stage.as_str()).collect();
+    assert_eq!(snapshot_names, names);
+    let timing_names: Vec<&str> = monitor.get_stage_timings().iter().map(|(s, _)| s.as_str()).collect();
+    assert_eq!(timing_names, names);
+    assert!(monitor.get_metrics().contains_key("stage_layout_ms"));
+
+    // The custom stage left no empty cell
+    let filled = generator.stage_snapshots().iter().find(|s| s.stage == "fill_floor").unwrap();
+    let occupied: HashSet<(i32, i32)> = filled.arena.modules.iter().map(|c| (c.x, c.y)).collect();
+    assert_eq!(occupied.len(), filled.arena.playable_cell_count());
+    assert_eq!(arena.modules.len(), arena.playable_cell_count());
+
+    // Every anomaly is attributed to the stage that reported it, or to the final validation
+    assert!(monitor.get_anomalies().iter().all(|a| a.stage.as_deref().is_none_or(|s| names.iter().any(|n| n == s))));
+}
+
+#[test]
+fn preset_files_match_builtins_and_configs_are_recorded() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/presets");
+
+    for name in ["easy", "normal", "hard", "chaos"] {
+        let builtin = GenerationConfig::preset(name).unwrap();
+        let from_file = GenerationConfig::from_file(dir.join(format!("{}.json", name))).unwrap();
+        assert_eq!(from_file, builtin, "assets/presets/{}.json drifted from the built-in preset", name);
+    }
+
+    let config = GenerationConfig::hard()
+        .with_size(14, 10)
+        .with_fixed_rules(vec![RuleId::MoonGravity])
+        .with_forced_env(EnvVarId::GameSpeed, 1.5);
+    let mut monitor = AnomalyMonitor::new();
+    let arena = ArenaGenerator::new(Some(3)).generate(&config, &mut monitor).unwrap();
+
+    assert_eq!((arena.width, arena.height), (14, 10));
+    assert_eq!(arena.active_rules.len(), 1);
+    assert_eq!(arena.env_variables[&EnvVarId::GameSpeed], 1.5);
+    assert_eq!(arena.generation_metadata.config.as_ref(), Some(&config));
+
+    let contradictory = GenerationConfig::normal().with_fixed_rules(vec![RuleId::NoJump]);
+    let contradictory = GenerationConfig { forbidden_rules: vec![RuleId::NoJump], ..contradictory };
+    assert!(matches!(contradictory.validate(), Err(ArenaGenerationError::InvalidConfiguration { .. })));
+}
+
+#[test]
+fn difficulty_targeting_lands_near_the_requested_score() {
+    // Harder presets score higher on average
+    let mean_score = |config: &GenerationConfig| {
+        let scores: Vec<f32> = (0..8u64)
+            .filter_map(|seed| {
+                let mut generator = ArenaGenerator::new(Some(seed));
+                let arena = generator.generate(config, &mut AnomalyMonitor::new()).ok()?;
+                Some(generator.estimate_difficulty(&arena).score)
+            })
+            .collect();
+        scores.iter().sum::<f32>() / scores.len() as f32
+    };
+    assert!(mean_score(&GenerationConfig::easy()) < mean_score(&GenerationConfig::hard()));
+    assert!(mean_score(&GenerationConfig::hard()) < mean_score(&GenerationConfig::chaos()));
+
+    for (seed, target) in [(1u64, 0.25f32), (2, 0.5), (3, 0.7)] {
+        let mut monitor = AnomalyMonitor::new();
+        let result = ArenaGenerator::new(Some(seed))
+            .generate_for_difficulty(&GenerationConfig::normal(), target, DEFAULT_DIFFICULTY_TOLERANCE, &mut monitor)
+            .unwrap();
+
+        assert!(result.is_within(DEFAULT_DIFFICULTY_TOLERANCE),
+                "seed {}: scored {:.2} for target {:.2}", seed, result.estimate.score, target);
+        assert!(result.attempts <= MAX_DIFFICULTY_ATTEMPTS);
+        assert_eq!(result.arena.generation_metadata.config.as_ref(), Some(&result.config));
+        assert_eq!(result.config.target_difficulty, Some(target));
+        assert_eq!(monitor.get_metrics()["difficulty_attempts"], result.attempts as f64);
+        assert!(monitor.get_anomalies_by_code(AnomalyCode::DifficultyOffTarget).is_empty());
+
+        // Same seed, same tuning path
+        let again = ArenaGenerator::new(Some(seed))
+            .generate_for_difficulty(&GenerationConfig::normal(), target, DEFAULT_DIFFICULTY_TOLERANCE, &mut AnomalyMonitor::new())
+            .unwrap();
+        assert_eq!(again.estimate, result.estimate);
+    }
+
+    let out_of_range = ArenaGenerator::new(Some(1))
+        .generate_for_difficulty(&GenerationConfig::normal(), 1.5, DEFAULT_DIFFICULTY_TOLERANCE, &mut AnomalyMonitor::new());
+    assert!(out_of_range.is_err());
+
+    // Retuning towards an easy target never drops below a quota's minimum
+    let quota = GenerationConfig::normal().with_rule_count(3).with_rule_quota("movement", 2, None);
+    let easy = ArenaGenerator::new(Some(1))
+        .generate_for_difficulty(&quota, 0.0, 0.01, &mut AnomalyMonitor::new())
+        .unwrap();
+    assert!(easy.config.rule_count >= 2, "{:?}", easy.config.rule_count);
+    assert!(easy.config.validate().is_ok());
+}
+
+#[test]
+fn rule_selection_honours_required_forbidden_preferred_and_quotas() {
+    let rule_ids = |arena: &Arena| arena.active_rules.iter().map(|r| r.id.clone()).collect::<Vec<_>>();
+
+    for seed in 0..16u64 {
+        let config = GenerationConfig::normal()
+            .with_rule_count(3)
+            .require_rule(RuleId::LavaFloor)
+            .forbid_rule(RuleId::NoAttack)
+            .with_rule_quota("difficulty_easy", 1, None)
+            .with_rule_quota("hazard", 0, Some(1));
+        let mut monitor = AnomalyMonitor::new();
+        let Ok(arena) = ArenaGenerator::new(Some(seed)).generate(&config, &mut monitor) else { continue };
+        let rules = rule_ids(&arena);
+
+        assert_eq!(rules[0], RuleId::LavaFloor, "seed {}", seed);
+        assert!(!rules.contains(&RuleId::NoAttack), "seed {}", seed);
+        assert!(arena.active_rules.iter().any(|r| r.tags.iter().any(|t| t == "difficulty_easy")), "seed {}: {:?}", seed, rules);
+        assert!(arena.active_rules.iter().filter(|r| r.tags.iter().any(|t| t == "hazard")).count() <= 1, "seed {}: {:?}", seed, rules);
+    }
+
+    // A preferred rule shows up far more often than its share
+    let picks = |config: &GenerationConfig| (0..64u64)
+        .filter(|&seed| {
+            let mut generator = ArenaGenerator::new(Some(seed));
+            generator.generate(config, &mut AnomalyMonitor::new())
+                .is_ok_and(|arena| rule_ids(&arena).contains(&RuleId::MoonGravity))
+        })
+        .count();
+    let base = GenerationConfig::normal().with_rule_count(1);
+    assert!(picks(&base.clone().prefer_rule(RuleId::MoonGravity)) > picks(&base));
+
+    // Requiring rules makes room for them in the rule count
+    let required = GenerationConfig::normal().with_rule_count(0).require_rule(RuleId::LavaFloor).require_rule(RuleId::SpeedUp);
+    assert_eq!(required.rule_count, 2);
+    assert!(required.validate().is_ok());
+
+    // Contradictory requirements are configuration errors, not generation failures
+    let incompatible = GenerationConfig::normal().require_rule(RuleId::LowJump).require_rule(RuleId::NoJump);
+    let over_quota = GenerationConfig::normal()
+        .with_fixed_rules(vec![RuleId::LavaFloor, RuleId::ProjectileRain])
+        .with_rule_quota("hazard", 0, Some(1));
+    let over_count = GenerationConfig::normal()
+        .with_fixed_rules(vec![RuleId::LavaFloor, RuleId::SpeedUp])
+        .with_rule_count(1);
+    for config in [incompatible, over_quota, over_count] {
+        let err = ArenaGenerator::new(Some(1)).generate(&config, &mut AnomalyMonitor::new()).unwrap_err();
+        assert!(matches!(err, ArenaGenerationError::InvalidConfiguration { .. }), "{:?}", err);
+    }
+    let too_demanding = GenerationConfig::normal().with_rule_count(1).with_rule_quota("movement", 2, None);
+    assert!(matches!(too_demanding.validate(), Err(ArenaGenerationError::InvalidConfiguration { .. })));
+}
+
+#[test]
+fn rule_graph_reports_inconsistencies_and_drives_selection() {
+    let rule = |id: RuleId, incompatible_with: Vec<RuleId>| Rule {
+        id,
+        name: String::new(),
+        description: String::new(),
+        tags: Vec::new(),
+        parameters: None,
+        incompatible_with,
+    };
+    let rules = [
+        rule(RuleId::NoJump, vec![RuleId::LowJump]),
+        rule(RuleId::LowJump, vec![]),
+        rule(RuleId::MoonGravity, vec![RuleId::HighJump]),
+    ];
+    let refs: Vec<&Rule> = rules.iter().collect();
+    let graph = RuleGraph::build(&refs, &[(RuleId::LowJump, RuleId::MoonGravity, 2.0)], &[(RuleId::NoJump, RuleId::LowJump)]);
+
+    // Declared on one side only, still symmetric in the graph
+    assert!(graph.conflicts(&RuleId::LowJump, &RuleId::NoJump));
+    assert_eq!(graph.synergy(&RuleId::MoonGravity, &RuleId::LowJump), 2.0);
+    assert_eq!(graph.synergy(&RuleId::NoJump, &RuleId::LowJump), 1.0);
+    assert_eq!(graph.issues(), &[
+        RuleGraphIssue::AsymmetricConflict { declared_by: RuleId::NoJump, other: RuleId::LowJump },
+        RuleGraphIssue::UnknownRule { declared_by: RuleId::MoonGravity, target: RuleId::HighJump },
+        RuleGraphIssue::RequiresConflicting { rule: RuleId::NoJump, required: RuleId::LowJump },
+    ]);
+
+    // The built-in declarations are consistent
+    assert!(RulesDatabase::new().relations().issues().is_empty());
+
+    // Implied rules come along with forced and random picks alike
+    let implying = || RulesDatabase::new().with_relations(&[], &[(RuleId::ProjectileRain, RuleId::MoonGravity)]);
+    let config = GenerationConfig::normal().with_rule_count(2).require_rule(RuleId::ProjectileRain);
+    let arena = ArenaGenerator::new(Some(5)).with_rules_db(implying())
+        .generate(&config, &mut AnomalyMonitor::new()).unwrap();
+    let ids: Vec<RuleId> = arena.active_rules.iter().map(|r| r.id.clone()).collect();
+    assert_eq!(ids, vec![RuleId::ProjectileRain, RuleId::MoonGravity]);
+
+    for seed in 0..32u64 {
+        let mut generator = ArenaGenerator::new(Some(seed)).with_rules_db(implying());
+        let Ok(arena) = generator.generate(&GenerationConfig::normal().with_rule_count(3), &mut AnomalyMonitor::new()) else { continue };
+        let has = |id: RuleId| arena.active_rules.iter().any(|r| r.id == id);
+        assert!(!has(RuleId::ProjectileRain) || has(RuleId::MoonGravity), "seed {}", seed);
+    }
+
+    let forbidden = config.forbid_rule(RuleId::MoonGravity);
+    let err = ArenaGenerator::new(Some(5)).with_rules_db(implying())
+        .generate(&forbidden, &mut AnomalyMonitor::new()).unwrap_err();
+    assert!(matches!(err, ArenaGenerationError::InvalidConfiguration { .. }));
+
+    // The built-in declarations are consistent
+    let builtin = RulesDatabase::builtin();
+    assert!(builtin.relations().issues().is_empty(), "{:?}", builtin.relations().issues());
+
+    // Validation reads conflicts and requirements from the graph, not from the rules' own lists
+    let mut arena = Arena::new(6, 6, 1);
+    arena.active_rules = vec![
+        rule(RuleId::ProjectileRain, vec![]),
+        rule(RuleId::MoonGravity, vec![]),
+        rule(RuleId::NoJump, vec![]),
+    ];
+    let mut monitor = AnomalyMonitor::new();
+    monitor.validate_arena(&arena);
+    assert_eq!(monitor.get_anomalies_by_code(AnomalyCode::IncompatibleRules).len(), 1);
+    assert!(monitor.get_anomalies_by_code(AnomalyCode::MissingRuleRequirement).is_empty());
+
+    arena.active_rules = vec![rule(RuleId::ProjectileRain, vec![])];
+    let mut monitor = AnomalyMonitor::new();
+    monitor.validate_arena_with(&arena, implying().relations());
+    let missing = monitor.get_anomalies_by_code(AnomalyCode::MissingRuleRequirement);
+    assert_eq!(missing.len(), 1);
+    assert_eq!(missing[0].severity, AnomalySeverity::Critical);
+    assert!(missing[0].message.contains("MoonGravity"), "{}", missing[0].message);
+}
+
+/// Outlasts a time budget the standard stages fit in
+struct SlowStage;
+
+impl GenerationStage for SlowStage {
+    fn name(&self) -> &str {
+        "slow"
+    }
+
+    fn run(&self, _ctx: &mut StageContext<'_>) -> GenerationResult<()> {
+        std::thread::sleep(std::time::Duration::from_millis(300));
+        Ok(())
+    }
+}
+
+#[test]
+fn generation_errors_are_typed() {
+    // A blown time budget names the stage it ran out in
+    let config = GenerationConfig::normal().with_size(160, 160).with_time_budget(Some(1));
+    let err = ArenaGenerator::new(Some(1)).generate(&config, &mut AnomalyMonitor::new()).unwrap_err();
+    assert!(matches!(&err, ArenaGenerationError::Timeout { max_time_ms: 1, stage } if !stage.is_empty()), "{:?}", err);
+    assert!(err.is_retryable());
+
+    // Critical anomalies travel with the error
+    let mut no_layout = GenerationPipeline::standard();
+    no_layout.remove("layout").unwrap();
+    let config = GenerationConfig { max_repair_attempts: 0, ..GenerationConfig::normal() };
+    let err = ArenaGenerator::new(Some(1)).with_pipeline(no_layout)
+        .generate(&config, &mut AnomalyMonitor::new())
+        .unwrap_err();
+    match &err {
+        ArenaGenerationError::CriticalAnomaly { anomalies } => {
+            assert!(!anomalies.is_empty());
+            assert!(anomalies.iter().all(|a| a.severity == AnomalySeverity::Critical));
+        }
+        other => panic!("expected critical anomalies, got {:?}", other),
+    }
+
+    // The final validation runs even without a repair stage, outside of any stage
+    let mut no_repair = GenerationPipeline::standard();
+    no_repair.remove("layout").unwrap();
+    no_repair.remove("repair").unwrap();
+    let err = ArenaGenerator::new(Some(1)).with_pipeline(no_repair)
+        .generate(&GenerationConfig::normal(), &mut AnomalyMonitor::new())
+        .unwrap_err();
+    assert!(matches!(&err, ArenaGenerationError::CriticalAnomaly { anomalies }
+        if anomalies.iter().all(|a| a.stage.is_none())), "{:?}", err);
+
+    // Running past the budget in the last stage doesn't throw the finished arena away
+    let config = GenerationConfig::normal().with_time_budget(Some(150));
+    let mut slow_last = GenerationPipeline::standard();
+    slow_last.push(SlowStage);
+    ArenaGenerator::new(Some(1)).with_pipeline(slow_last).generate(&config, &mut AnomalyMonitor::new()).unwrap();
+
+    // Shapes without a single playable cell violate a named constraint
+    let empty = GenerationConfig::normal().with_shape(ArenaShape::Mask(ArenaMask::from_bitmap(&["...", "..."])));
+    let err = ArenaGenerator::new(Some(1)).generate(&empty, &mut AnomalyMonitor::new()).unwrap_err();
+    assert!(matches!(&err, ArenaGenerationError::ConstraintViolation { constraint, .. } if constraint == "playable_area"), "{:?}", err);
+
+    // A hand-written mask with too few entries is refused up front, and never indexed past its end
+    let short = ArenaMask { width: 4, height: 4, cells: vec![true; 10] };
+    assert!(!short.is_well_formed() && !short.contains(3, 3) && short.contains(1, 1));
+    let err = GenerationConfig::normal().with_shape(ArenaShape::Mask(short)).validate().unwrap_err();
+    assert!(matches!(&err, ArenaGenerationError::InvalidConfiguration { details } if details.contains("10 entries")), "{:?}", err);
+
+    let err = GenerationPipeline::standard().remove("no_such_stage").unwrap_err();
+    assert!(!err.is_retryable());
+}
+
+#[test]
+fn structural_anomalies_match_the_arena_checks() {
+    let mut arena = Arena::new(4, 4, 1);
+    arena.add_module(0, 0, ModuleId::FloorStd, None);
+    arena.add_module(9, 9, ModuleId::FloorStd, None);
+
+    let mut monitor = AnomalyMonitor::new();
+    monitor.validate_arena(&arena);
+
+    // The monitor reports the arena's own structural issues, in order and word for word
+    let structural: Vec<&Anomaly> = monitor.get_anomalies().iter()
+        .filter(|a| a.category == "STRUCTURAL" && a.code != AnomalyCode::OverlappingModules)
+        .collect();
+    let messages: Vec<&str> = structural.iter().map(|a| a.message.as_str()).collect();
+    assert_eq!(messages, arena.validate_structural_integrity());
+    let codes: Vec<AnomalyCode> = structural.iter().map(|a| a.code).collect();
+    assert_eq!(codes, vec![
+        AnomalyCode::MissingPlayerSpawn,
+        AnomalyCode::InsufficientWalkableSurfaces,
+        AnomalyCode::MissingEnergyOrbs,
+    ]);
+    assert_eq!(structural[1].location.cells, vec![(0, 0), (9, 9)]);
+
+    // Checks the arena doesn't make are still the monitor's own
+    assert_eq!(monitor.get_anomalies_by_code(AnomalyCode::OutOfBounds).len(), 1);
+}
+
+#[test]
+fn monitoring_exports_round_trip_and_escape_messages() {
+    let mut monitor = AnomalyMonitor::new();
+    monitor.start_generation();
+    monitor.start_generation();
+    monitor.begin_stage("repair");
+    monitor.report_anomaly(
+        AnomalyCode::IsolatedElement,
+        "Orb at <3, 4> cut off by \"lava\" & 'walls'".to_string(),
+        AnomalySeverity::Critical,
+        AnomalyLocation::cells(vec![(3, 4), (5, 1)]).with_modules(vec![ModuleId::OrbEnergy]),
+    );
+    monitor.report_anomaly(
+        AnomalyCode::IsolatedElement,
+        "second".to_string(),
+        AnomalySeverity::Warning,
+        AnomalyLocation::none(),
+    );
+    monitor.report_anomaly(
+        AnomalyCode::LowOrbDensity,
+        "Only 1 orb".to_string(),
+        AnomalySeverity::Info,
+        AnomalyLocation::none(),
+    );
+    let summary = monitor.get_summary();
+
+    // JSON survives a trip through its string form, and holds the counts
+    let json = summary.to_json();
+    let parsed: Value = serde_json::from_str(&summary.to_json_string().unwrap()).unwrap();
+    assert_eq!(parsed, json);
+    assert_eq!(json["generations"], 2);
+    assert_eq!(json["total_anomalies"], 3);
+    assert_eq!(json["by_severity"]["CRITICAL"], 1);
+    assert_eq!(json["by_severity"]["WARNING"], 1);
+    assert_eq!(json["by_severity"]["INFO"], 1);
+    assert_eq!(json["by_stage"]["repair"], 3);
+    let isolated = &json["by_code"][AnomalyCode::IsolatedElement.as_str()];
+    assert_eq!(isolated["name"], "IsolatedElement");
+    assert_eq!(isolated["category"], AnomalyCode::IsolatedElement.category());
+    assert_eq!(isolated["total"], 2);
+    assert_eq!(isolated["critical"], 1);
+    assert_eq!(isolated["warning"], 1);
+    assert_eq!(isolated["frequency"], 1.0);
+    assert_eq!(isolated["sample_message"], "Orb at <3, 4> cut off by \"lava\" & 'walls'");
+    let codes: Vec<&String> = json["by_code"].as_object().unwrap().keys().collect();
+    let mut sorted = codes.clone();
+    sorted.sort();
+    assert_eq!(codes, sorted);
+
+    // Codes sort by their stable string, not by where the variant is declared
+    let mut declared = [AnomalyCode::MissingRuleRequirement, AnomalyCode::FallbackArenaUsed, AnomalyCode::IncompatibleRules, AnomalyCode::LowOrbDensity];
+    declared.sort();
+    let order: Vec<&str> = declared.iter().map(AnomalyCode::as_str).collect();
+    assert_eq!(order, vec!["BAL-001", "RET-001", "RUL-001", "RUL-005"]);
+
+    // JUnit: only the code with a critical anomaly fails, and its message is escaped
+    let xml = summary.to_junit_xml("arena <gen>");
+    assert!(xml.contains(r#"<testsuites name="arena &lt;gen&gt;" tests="2" failures="1">"#), "{}", xml);
+    assert!(xml.contains(
+        "Orb at &lt;3, 4&gt; cut off by &quot;lava&quot; &amp; &apos;walls&apos;</failure>"
+    ), "{}", xml);
+    assert!(!xml.contains("<3, 4>"));
+    assert_eq!(xml.matches("<failure ").count(), 1);
+    assert_eq!(xml.matches("<testcase ").count(), 2);
+
+    // Every anomaly keeps its code, stage, cells and modules
+    let anomalies = monitor.anomalies_to_json();
+    let anomalies = anomalies.as_array().unwrap();
+    assert_eq!(anomalies.len(), 3);
+    assert_eq!(anomalies[0]["code"], AnomalyCode::IsolatedElement.as_str());
+    assert_eq!(anomalies[0]["severity"], "CRITICAL");
+    assert_eq!(anomalies[0]["stage"], "repair");
+    assert_eq!(anomalies[0]["message"], "Orb at <3, 4> cut off by \"lava\" & 'walls'");
+    assert_eq!(anomalies[0]["cells"], serde_json::json!([[3, 4], [5, 1]]));
+    assert_eq!(anomalies[0]["region"]["min"], serde_json::json!([3, 1]));
+    assert_eq!(anomalies[0]["region"]["max"], serde_json::json!([5, 4]));
+    assert_eq!(anomalies[0]["modules"], serde_json::json!(["OrbEnergy"]));
+    assert!(anomalies[2]["region"].is_null());
+}
+
+#[test]
+fn retries_derive_seeds_relax_and_fall_back() {
+    assert_eq!(derive_seed(77, 0), 77);
+    let seeds: HashSet<u64> = (0..16).map(|attempt| derive_seed(77, attempt)).collect();
+    assert_eq!(seeds.len(), 16);
+
+    // A first-try success is the plain generation of the master seed
+    let config = GenerationConfig::normal();
+    let policy = RetryPolicy::default().with_time_budget(None);
+    let outcome = ArenaGenerator::new(None)
+        .generate_until_valid(1, &config, &policy, &mut AnomalyMonitor::new())
+        .unwrap();
+    let direct = generate(1, config.width, config.rule_count).unwrap();
+    assert_eq!((outcome.seed, outcome.attempts, outcome.fallback), (1, 1, false));
+    assert_eq!(snapshot(&outcome.arena), snapshot(&direct));
+
+    // Relaxation only loosens the config
+    let strict = GenerationConfig::hard().with_rule_quota("hazard", 1, Some(1)).prefer_rule(RuleId::LavaFloor);
+    let (relaxed, notes) = relax_config(&strict, MAX_RELAXATION_LEVEL);
+    assert!(relaxed.rule_quotas.is_empty() && relaxed.preferred_rules.is_empty());
+    assert!(relaxed.rule_count < strict.rule_count);
+    assert!(relaxed.max_repair_attempts > strict.max_repair_attempts);
+    assert!(!notes.is_empty());
+    assert_eq!(relax_config(&strict, 0).0, strict);
+
+    // Nothing can succeed without a layout or repairs: every attempt fails, then the fallback steps in
+    let no_layout = || {
+        let mut pipeline = GenerationPipeline::standard();
+        pipeline.remove("layout").unwrap();
+        pipeline
+    };
+    let broken = GenerationConfig { max_repair_attempts: 0, ..GenerationConfig::normal() };
+    let policy = RetryPolicy::default().with_max_attempts(4).with_time_budget(None).with_relax_every(0);
+    let mut monitor = AnomalyMonitor::new();
+    let outcome = ArenaGenerator::new(None).with_pipeline(no_layout())
+        .generate_until_valid(9, &broken, &policy, &mut monitor)
+        .unwrap();
+
+    assert!(outcome.fallback);
+    assert_eq!(outcome.attempts, 4);
+    let failed_seeds: Vec<u64> = outcome.failures.iter().map(|f| f.seed).collect();
+    assert_eq!(failed_seeds, (0..4).map(|a| derive_seed(9, a)).collect::<Vec<_>>());
+    assert_eq!(monitor.get_anomalies_by_code(AnomalyCode::FallbackArenaUsed).len(), 1);
+
+    let mut validation = AnomalyMonitor::new();
+    validation.validate_arena(&outcome.arena);
+    assert!(!validation.has_critical_anomalies(), "{:?}", validation.get_anomalies());
+    assert_eq!(outcome.arena.count_modules_by_type(&ModuleId::Player), 1);
+
+    // With relaxation on, raising the repair attempts is what rescues it
+    let relaxing = RetryPolicy::default().with_max_attempts(4).with_time_budget(None);
+    let outcome = ArenaGenerator::new(None).with_pipeline(no_layout())
+        .generate_until_valid(9, &broken, &relaxing, &mut AnomalyMonitor::new())
+        .unwrap();
+    assert!(!outcome.fallback);
+    assert_eq!((outcome.attempts, outcome.relaxation_level), (3, 1));
+    assert!(outcome.arena.generation_metadata.constraints_applied.contains(&"relaxed:1".to_string()));
+
+    let no_fallback = policy.with_fallback(false);
+    let err = ArenaGenerator::new(None).with_pipeline(no_layout())
+        .generate_until_valid(9, &broken, &no_fallback, &mut AnomalyMonitor::new())
+        .unwrap_err();
+    assert!(matches!(err, ArenaGenerationError::CriticalAnomaly { .. }), "{:?}", err);
+}
+
+/// Cancels the generation it runs in, as a player leaving the loading screen would
+struct CancelStage(Arc<GenerationProgress>);
+
+impl GenerationStage for CancelStage {
+    fn name(&self) -> &str {
+        "cancel"
+    }
+
+    fn run(&self, _ctx: &mut StageContext<'_>) -> GenerationResult<()> {
+        self.0.cancel();
+        Ok(())
+    }
+}
+
+#[test]
+fn progress_follows_stages_and_cancellation_stops_generation() {
+    // Every stage is reported, from a generation running on another thread
+    let progress = GenerationProgress::new();
+    let config = GenerationConfig::normal();
+    let stage_count = GenerationPipeline::standard().stage_names().len() as u32;
+    let worker = {
+        let progress = progress.clone();
+        let config = config.clone();
+        std::thread::spawn(move || {
+            ArenaGenerator::new(None).with_progress(progress)
+                .generate_until_valid(1, &config, &RetryPolicy::default(), &mut AnomalyMonitor::new())
+        })
+    };
+    let outcome = worker.join().unwrap().unwrap();
+    let snapshot = progress.snapshot();
+    assert_eq!((snapshot.attempt, snapshot.stages_done, snapshot.stages_total), (outcome.attempts, stage_count, stage_count));
+    assert_eq!(snapshot.stage, "repair");
+    assert_eq!(snapshot.fraction(), 1.0);
+
+    // Cancelled halfway: the next stage never runs
+    let progress = GenerationProgress::new();
+    let mut pipeline = GenerationPipeline::standard();
+    pipeline.insert_after("layout", CancelStage(progress.clone())).unwrap();
+    let mut generator = ArenaGenerator::new(Some(1)).with_pipeline(pipeline).with_progress(progress.clone());
+    let err = generator.generate(&config, &mut AnomalyMonitor::new()).unwrap_err();
+    assert!(matches!(&err, ArenaGenerationError::Cancelled { stage } if stage == "rule_modifications"), "{:?}", err);
+    assert!(!err.is_retryable());
+    assert_eq!(progress.snapshot().stages_done, 5);
+
+    // A cancelled retry loop neither retries nor falls back
+    let mut monitor = AnomalyMonitor::new();
+    let err = generator.generate_until_valid(1, &config, &RetryPolicy::default(), &mut monitor).unwrap_err();
+    assert!(matches!(err, ArenaGenerationError::Cancelled { .. }), "{:?}", err);
+    assert!(monitor.get_anomalies_by_code(AnomalyCode::FallbackArenaUsed).is_empty());
+}
+
+#[test]
+fn fragile_floors_are_generated_and_treated_as_temporary_ground() {
+    let fragile = ModulesDatabase::new().get_module(&ModuleId::FloorFragile).cloned().expect("fragile floor in the database");
+    assert!(fragile.wfc_weight.is_some());
+    let params = fragile.parameters.expect("fragile floor parameters");
+    assert_eq!((params["breakDelay"].as_f64(), params["respawnDelay"].as_f64()), (Some(0.5), Some(5.0)));
+
+    // The rule seeds fragile floors and makes them give way sooner
+    let config = GenerationConfig::normal().with_fixed_rules(vec![RuleId::CrumblingFloor]);
+    for seed in 0..8 {
+        let arena = ArenaGenerator::new(Some(seed)).generate(&config, &mut AnomalyMonitor::new()).unwrap();
+        let cells = arena.get_modules_by_type(&ModuleId::FloorFragile);
+        assert!(cells.len() >= 4, "seed {}: {} fragile floors", seed, cells.len());
+        for cell in cells {
+            let delay = cell.module_params.as_ref().and_then(|p| p["breakDelay"].as_f64());
+            assert!(delay.is_some_and(|d| (d - 0.3).abs() < 1e-9), "seed {}: {:?}", seed, cell.module_params);
+        }
+    }
+
+    // Spawn - floor - fragile - orb: walkable now, lost while the fragile floor is broken
+    let mut arena = Arena::new(4, 1, 0);
+    for (x, module_id) in [ModuleId::Player, ModuleId::FloorStd, ModuleId::FloorFragile, ModuleId::OrbEnergy].into_iter().enumerate() {
+        arena.add_module(x as i32, 0, module_id, None);
+    }
+    assert!(arena.get_reachable_positions((0, 0)).contains(&(3, 0)));
+    assert_eq!(arena.get_path_distances((0, 0))[&(3, 0)], 3);
+    let permanent = arena.get_permanently_reachable_positions((0, 0));
+    assert!(permanent.contains(&(1, 0)) && !permanent.contains(&(2, 0)) && !permanent.contains(&(3, 0)));
+
+    let mut monitor = AnomalyMonitor::new();
+    monitor.validate_arena(&arena);
+    assert!(monitor.get_anomalies_by_code(AnomalyCode::UnreachableOrb).is_empty());
+    let temporary = monitor.get_anomalies_by_code(AnomalyCode::TemporaryPathOnly);
+    assert_eq!(temporary.len(), 1);
+    assert_eq!(temporary[0].location.cells, vec![(3, 0)]);
+    assert!(arena.validate_advanced_integrity().warnings.iter().any(|w| w.contains("fragile")));
+}
+
+#[test]
+fn builtin_themes_are_complete_and_follow_rules() {
+    for theme in ThemeId::ALL {
+        let builtin = ThemePalette::builtin(theme);
+        assert_eq!(builtin.theme, theme);
+        for slot in MaterialSlot::ALL {
+            assert!(builtin.materials.contains_key(&slot), "assets/themes/{}.json lacks {:?}", theme, slot);
+        }
+    }
+
+    // Partial files only override what they list
+    let partial = ThemePalette::from_json(r#"{"theme":"Moon","materials":{"OrbEnergy":{"base_color":[1.0,0.0,0.0,1.0],"roughness":0.5,"metallic":0.0}}}"#).unwrap();
+    let moon = ThemePalette::builtin(ThemeId::Moon);
+    assert_eq!(partial.spec(MaterialSlot::OrbEnergy).base_color, [1.0, 0.0, 0.0, 1.0]);
+    assert_eq!(partial.spec(MaterialSlot::WallHigh), moon.spec(MaterialSlot::WallHigh));
+    assert_eq!(partial.background, moon.background);
+    assert!(ThemePalette::from_json(r#"{"theme":"Sepia"}"#).is_err());
+
+    // Cross-fade endpoints are the two palettes
+    let neon = ThemePalette::builtin(ThemeId::Neon);
+    assert_eq!(moon.lerp(&neon, 0.0), moon);
+    assert_eq!(moon.lerp(&neon, 1.0), neon);
+
+    let rules = RulesDatabase::new();
+    let mut arena = Arena::new(4, 4, 0);
+    assert_eq!(ThemeId::for_arena(&arena), ThemeId::Pastel);
+    arena.active_rules.push(rules.get_rule(&RuleId::LavaFloor).cloned().unwrap());
+    assert_eq!(ThemeId::for_arena(&arena), ThemeId::Volcanic);
+    // Low gravity wins over lava
+    arena.env_variables.insert(EnvVarId::Gravity, 0.3);
+    assert_eq!(ThemeId::for_arena(&arena), ThemeId::Moon);
+
+    // Colour-blind palettes separate hazards from collectibles by luminance, not hue alone
+    let contrast = |a: f32, b: f32| (a.max(b) + 0.05) / (a.min(b) + 0.05);
+    for theme in ThemeId::ALL {
+        let palette = ThemePalette::builtin(theme).colour_blind();
+        let orb = palette.spec(MaterialSlot::OrbEnergy).luminance();
+        for slot in MaterialSlot::ALL.into_iter().filter(MaterialSlot::is_hazard) {
+            let ratio = contrast(orb, palette.spec(slot).luminance());
+            assert!(ratio >= 2.0, "{}: {:?} vs orb contrast {:.2}", theme, slot, ratio);
+        }
+    }
+}
+
+#[test]
+fn projectile_rain_drives_the_laser_turrets_it_drops() {
+    let config = GenerationConfig::normal().with_fixed_rules(vec![RuleId::ProjectileRain]);
+    let mut driven = 0;
+    for seed in 0..8 {
+        let arena = ArenaGenerator::new(Some(seed)).generate(&config, &mut AnomalyMonitor::new()).unwrap();
+        for cell in arena.get_modules_by_type(&ModuleId::HazardLaserTurretRotate) {
+            // Turrets picked by the WFC keep the module defaults
+            let Some(params) = cell.module_params.as_ref() else { continue };
+            driven += 1;
+            assert_eq!(params["damagePerSecond"].as_f64(), Some(40.0), "seed {}", seed);
+            assert_eq!(params["rotationSpeed"].as_f64(), Some(90.0), "seed {}", seed);
+            assert_eq!(params["arc"].as_f64(), Some(180.0));
+            assert!(params["direction"].as_i64().is_some_and(|d| [0, 90, 180, 270].contains(&d)));
+        }
+    }
+    assert!(driven > 0);
+}
+
+#[test]
+fn projectile_schedule_is_seeded_scaled_and_targets_walkable_cells() {
+    let config = GenerationConfig::normal().with_fixed_rules(vec![RuleId::ProjectileRain]);
+    let arena = ArenaGenerator::new(Some(3)).generate(&config, &mut AnomalyMonitor::new()).unwrap();
+    let reachable = arena.get_reachable_positions(arena.get_player_position().unwrap());
+
+    // Same seed, same volleys at the same times
+    let run = |difficulty: f32| {
+        let mut schedule = ProjectileSchedule::for_arena(&arena, difficulty).unwrap();
+        (0..600).flat_map(|_| schedule.tick(0.1)).collect::<Vec<Volley>>()
+    };
+    let volleys = run(1.0);
+    assert!(!volleys.is_empty());
+    assert_eq!(volleys, run(1.0));
+    for volley in &volleys {
+        assert!(volley.targets.iter().all(|cell| reachable.contains(cell)), "{:?}", volley);
+        assert!(VolleyPattern::available(1.0).contains(&volley.pattern));
+    }
+
+    // Harder means faster and bigger volleys, with every pattern unlocked
+    let base = ProjectileSchedule::for_arena(&arena, 1.0).unwrap();
+    let hard = ProjectileSchedule::for_arena(&arena, 2.5).unwrap();
+    assert!(hard.interval < base.interval && hard.volley_size > base.volley_size);
+    assert!(run(2.5).len() > volleys.len());
+    assert_eq!(VolleyPattern::available(2.5).len(), 4);
+    assert!(hard.max_projectiles_in_flight() >= hard.volley_size);
+
+    // GameSpeed shortens the warning, but never below a reaction floor
+    let mut fast = arena.clone();
+    fast.env_variables.insert(EnvVarId::GameSpeed, 2.0);
+    let fast = ProjectileSchedule::for_arena(&fast, 1.0).unwrap();
+    assert!(fast.telegraph < base.telegraph && fast.interval < base.interval);
+    let mut frantic = arena.clone();
+    frantic.env_variables.insert(EnvVarId::GameSpeed, 10.0);
+    assert!(ProjectileSchedule::for_arena(&frantic, 1.0).unwrap().telegraph >= 0.6);
+
+    // Another seed, another timeline; no rule, no schedule
+    let other = ArenaGenerator::new(Some(4)).generate(&config, &mut AnomalyMonitor::new()).unwrap();
+    let mut other = ProjectileSchedule::for_arena(&other, 1.0).unwrap();
+    let other: Vec<Volley> = (0..600).flat_map(|_| other.tick(0.1)).collect();
+    assert_ne!(other, volleys);
+    let mut calm = arena.clone();
+    calm.active_rules.retain(|rule| rule.id != RuleId::ProjectileRain);
+    assert!(ProjectileSchedule::for_arena(&calm, 1.0).is_none());
+}
+
+#[test]
+fn projectile_pool_size_covers_every_volley_shape() {
+    // An open floor: no shape is clipped, so Rings and Crosses keep their full size
+    let mut arena = Arena::new(12, 12, 5);
+    for x in 0..12 {
+        for y in 0..12 {
+            arena.add_module(x, y, ModuleId::FloorStd, None);
+        }
+    }
+    arena.set_module(0, 0, ModuleId::Player, None);
+    arena.active_rules = vec![RulesDatabase::new().get_rule(&RuleId::ProjectileRain).cloned().unwrap()];
+
+    for difficulty in [0.5, 1.0, 1.5, 2.5, 4.0] {
+        let mut schedule = ProjectileSchedule::for_arena(&arena, difficulty).unwrap();
+        let (max_targets, pool) = (schedule.max_volley_targets(), schedule.max_projectiles_in_flight());
+
+        // Projectiles of each volley stay in flight for `telegraph` seconds after it fires
+        let mut in_flight: Vec<(f32, usize)> = Vec::new();
+        let mut largest = 0;
+        for step in 0..2000 {
+            let now = step as f32 * 0.05;
+            in_flight.retain(|&(fired, _)| now - fired < schedule.telegraph);
+            for volley in schedule.tick(0.05) {
+                assert!(volley.targets.len() <= max_targets, "difficulty {}: {:?}", difficulty, volley);
+                largest = largest.max(volley.targets.len());
+                in_flight.push((now, volley.targets.len()));
+            }
+            let count: usize = in_flight.iter().map(|&(_, targets)| targets).sum();
+            assert!(count <= pool, "difficulty {}: {} projectiles in flight, pool of {}", difficulty, count, pool);
+        }
+        if difficulty >= 1.5 {
+            // Rings outgrow the nominal volley size, and the bound is reached
+            assert!(largest > schedule.volley_size, "difficulty {}", difficulty);
+            assert_eq!(largest, max_targets, "difficulty {}", difficulty);
+        }
+    }
+}
+
+#[test]
+fn lava_spreads_over_floor_cools_back_and_keeps_the_arena_in_sync() {
+    let mut arena = Arena::new(5, 5, 11);
+    for x in 0..5 {
+        for y in 0..5 {
+            arena.add_module(x, y, ModuleId::FloorStd, None);
+        }
+    }
+    arena.set_module(2, 2, ModuleId::HazardLavaPit, None);
+    arena.set_module(2, 1, ModuleId::WallHigh, None);
+    arena.set_module(0, 0, ModuleId::Player, None);
+    let mut rule = RulesDatabase::new().get_rule(&RuleId::LavaFloor).unwrap().clone();
+    assert_eq!(LavaFlow::rule_intensity(&Arena { active_rules: vec![rule.clone()], ..arena.clone() }), 1.0);
+    rule.parameters = Some(serde_json::json!({"intensity": 2.5}));
+    arena.active_rules.push(rule);
+    let intensity = LavaFlow::rule_intensity(&arena);
+    assert_eq!(intensity, 2.5);
+
+    // Spreading only takes floor or empty cells, within the cap, and is written into the arena
+    let run = |mut arena: Arena, seconds: u32| {
+        let mut flow = LavaFlow::new(&arena);
+        let changes: Vec<LavaChange> = (0..seconds * 10).flat_map(|_| flow.tick(&mut arena, intensity, 0.1)).collect();
+        (arena, flow, changes)
+    };
+    let (spread, flow, changes) = run(arena.clone(), 10);
+    assert_eq!(flow.sources(), &[(2, 2)]);
+    assert!(changes.iter().all(|c| matches!(c, LavaChange::Spread(..))));
+    assert!(!changes.is_empty() && changes.len() <= flow.max_flows(intensity));
+    assert_eq!(spread.count_modules_by_type(&ModuleId::HazardLavaPit), 1 + flow.flows().count());
+    for (x, y) in flow.flows() {
+        assert!(arena.get_cell(x, y).is_some_and(|cell| cell.module_id == ModuleId::FloorStd));
+        assert_eq!(spread.get_cell(x, y).unwrap().module_id, ModuleId::HazardLavaPit);
+    }
+    assert_eq!(spread.count_modules_by_type(&ModuleId::WallHigh), 1);
+    assert_eq!(spread.count_modules_by_type(&ModuleId::Player), 1);
+    assert_eq!(spread.modules.len(), arena.modules.len());
+
+    // Same seed, same flow
+    assert_eq!(run(arena.clone(), 10).2, changes);
+
+    // Without the rule nothing spreads; what already flowed cools back to what it covered
+    let (mut cooling, mut flow, _) = run(arena.clone(), 10);
+    cooling.active_rules.clear();
+    let idle = LavaFlow::rule_intensity(&cooling);
+    assert_eq!(idle, 0.0);
+    let cooled: Vec<LavaChange> = (0..(LAVA_COOL_TIME * 10.0) as u32 + 1).flat_map(|_| flow.tick(&mut cooling, idle, 0.1)).collect();
+    assert!(!cooled.is_empty() && cooled.iter().all(|c| matches!(c, LavaChange::Cooled(..))));
+    assert_eq!(flow.flows().count(), 0);
+    assert_eq!(cooling.count_modules_by_type(&ModuleId::HazardLavaPit), 1);
+    assert!(cooling.validate_structural_integrity().iter().all(|issue| !issue.contains("walkable")));
+}
+
+#[test]
+fn cooled_lava_restores_the_covered_cells() {
+    // Large floors with their parameters, and empty cells, all around a pit
+    let mut arena = Arena::new(3, 3, 12);
+    for (x, y) in [(0, 0), (1, 0), (2, 0), (0, 1), (2, 1)] {
+        arena.add_module(x, y, ModuleId::FloorLarge, Some(serde_json::json!({"size": [2, 2]})));
+    }
+    arena.add_module(1, 1, ModuleId::HazardLavaPit, None);
+    let layout = |arena: &Arena| {
+        let mut cells: Vec<(i32, i32, ModuleId, String)> = arena.modules.iter()
+            .map(|cell| (cell.x, cell.y, cell.module_id.clone(), format!("{:?}", cell.module_params)))
+            .collect();
+        cells.sort_by_key(|(x, y, ..)| (*x, *y));
+        cells
+    };
+    let original = layout(&arena);
+
+    let mut flow = LavaFlow::new(&arena);
+    let mut spread = arena.clone();
+    for _ in 0..100 {
+        flow.tick(&mut spread, 4.0, 0.1);
+    }
+    assert!(flow.flows().any(|(x, y)| arena.get_cell(x, y).is_none()), "never spread into an empty cell");
+    assert!(flow.flows().any(|(x, y)| arena.get_cell(x, y).is_some()), "never spread over floor");
+
+    let cooled: Vec<LavaChange> = (0..(LAVA_COOL_TIME * 10.0) as u32 + 1).flat_map(|_| flow.tick(&mut spread, 0.0, 0.1)).collect();
+    assert!(!cooled.is_empty());
+    assert_eq!(layout(&spread), original);
+}
+
+#[test]
+fn lava_never_cuts_the_spawn_off_from_an_orb() {
+    // The only way to the orb runs along the pit
+    let mut arena = Arena::new(5, 3, 13);
+    arena.add_module(0, 1, ModuleId::Player, None);
+    for x in 1..4 {
+        arena.add_module(x, 1, ModuleId::FloorStd, None);
+    }
+    arena.add_module(4, 1, ModuleId::OrbEnergy, None);
+    arena.add_module(2, 0, ModuleId::HazardLavaPit, None);
+
+    let mut flow = LavaFlow::new(&arena);
+    let mut spread = arena.clone();
+    for _ in 0..300 {
+        flow.tick(&mut spread, 10.0, 0.1);
+        assert!(spread.get_reachable_positions((0, 1)).contains(&(4, 1)), "{:?}", flow.flows().collect::<Vec<_>>());
+    }
+    // It still overflows where nothing is at stake
+    assert!(flow.flows().count() > 0);
+    assert!(flow.flows().all(|(_, y)| y != 1));
+}
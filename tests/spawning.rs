@@ -0,0 +1,792 @@
+//tests/spawning.rs
+//
+// Headless spawning of generated arenas: no window, no renderer, only the ECS.
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy::render::mesh::{MeshVertexAttributeId, VertexAttributeValues};
+use bevy_rapier3d::prelude::*;
+use shiftropolis::app::core::*;
+use shiftropolis::app::data::*;
+use shiftropolis::app::game::*;
+use shiftropolis::app::generation::*;
+use shiftropolis::app::mesh_generation::*;
+use shiftropolis::app::monitoring::*;
+
+fn headless_app(arena: Arena, settings: ArenaVisualSettings) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<StandardMaterial>>()
+        .insert_resource(settings)
+        .init_resource::<ModuleMaterials>()
+        .init_resource::<MeshCache>()
+        .insert_resource(ArenaManager {
+            current_arena: Some(arena),
+            ..default()
+        });
+    app
+}
+
+fn advance(world: &mut World, seconds: f32) {
+    world.resource_mut::<Time>().advance_by(std::time::Duration::from_secs_f32(seconds));
+}
+
+fn spawn_player(world: &mut World, health: f32) -> Entity {
+    world.spawn(Player { health, max_health: 100.0, speed: 5.0, jump_force: 8.0, is_grounded: true, last_position: Vec3::ZERO }).id()
+}
+
+#[test]
+fn spawned_modules_carry_colliders_markers_and_gameplay_components() {
+    let mut arena = ArenaGenerator::new(Some(3))
+        .generate(&GenerationConfig::normal(), &mut AnomalyMonitor::new())
+        .unwrap();
+    // At least one of each module with gameplay components
+    arena.set_module(0, 0, ModuleId::FloorFragile, None);
+    arena.set_module(1, 0, ModuleId::OrbEnergy, None);
+    arena.set_module(2, 0, ModuleId::HazardLavaPit, None);
+    arena.set_module(3, 0, ModuleId::HazardLaserEmitterStatic, None);
+
+    let settings = ArenaVisualSettings { merge_static_geometry: false, ..default() };
+    let mut app = headless_app(arena.clone(), settings);
+    app.world_mut().run_system_once(spawn_arena_visuals).unwrap();
+    let world = app.world_mut();
+
+    let spawned = world.resource::<ArenaManager>().spawned_modules.clone();
+    assert!(!spawned.is_empty());
+
+    let mut modules = world.query::<(&ArenaModule, Option<&Collider>, Option<&Sensor>)>();
+    assert_eq!(modules.iter(world).count(), spawned.len());
+    for (module, collider, sensor) in modules.iter(world) {
+        assert!(collider.is_some(), "{:?} has no collider", module.module_id);
+        let cell = ArenaManager::world_to_cell(module.original_position);
+        assert!(arena.modules.iter().any(|c| (c.x, c.y) == cell && c.module_id == module.module_id), "{:?} at {:?}", module.module_id, cell);
+        assert_eq!(sensor.is_some(), module.module_id == ModuleId::OrbEnergy, "{:?}", module.module_id);
+    }
+
+    let count = |world: &mut World, id: ModuleId| {
+        world.query::<&ArenaModule>().iter(world).filter(|m| m.module_id == id).count()
+    };
+    let orbs = count(world, ModuleId::OrbEnergy);
+    let fragile = count(world, ModuleId::FloorFragile);
+    let hazards = count(world, ModuleId::HazardLavaPit)
+        + count(world, ModuleId::HazardLaserEmitterStatic)
+        + count(world, ModuleId::HazardLaserTurretRotate);
+
+    assert_eq!(world.query::<(&ArenaModule, &EnergyOrb, &Sensor)>().iter(world).count(), orbs);
+    assert_eq!(world.query::<(&ArenaModule, &FragileSurface)>().iter(world).count(), fragile);
+    assert_eq!(world.query::<(&ArenaModule, &DynamicHazard)>().iter(world).count(), hazards);
+    assert!(orbs >= 1 && fragile >= 1 && hazards >= 2);
+
+    // Plain floors stay plain
+    let floors: Vec<Entity> = world.query::<(Entity, &ArenaModule)>().iter(world)
+        .filter(|(_, m)| m.module_id == ModuleId::FloorStd)
+        .map(|(e, _)| e)
+        .collect();
+    for entity in floors {
+        let entity = world.entity(entity);
+        assert!(!entity.contains::<EnergyOrb>() && !entity.contains::<DynamicHazard>() && !entity.contains::<FragileSurface>());
+    }
+}
+
+#[test]
+fn modules_share_cached_meshes_and_static_geometry_merges_into_chunks() {
+    let arena = ArenaGenerator::new(Some(5))
+        .generate(&GenerationConfig::normal().with_size(20, 20), &mut AnomalyMonitor::new())
+        .unwrap();
+    let is_static = |id: &ModuleId| static_module_size(id).is_some();
+    let static_cells = arena.modules.iter().filter(|c| is_static(&c.module_id)).count();
+
+    // One mesh asset per shape, not per cell
+    let mut app = headless_app(arena.clone(), ArenaVisualSettings { merge_static_geometry: false, ..default() });
+    app.world_mut().run_system_once(spawn_arena_visuals).unwrap();
+    let world = app.world_mut();
+    let cached = world.resource::<MeshCache>().len();
+    assert_eq!(world.resource::<Assets<Mesh>>().len(), cached);
+    assert!(cached < 24 && cached * 10 < arena.modules.len(), "{} meshes for {} modules", cached, arena.modules.len());
+
+    let floor_meshes: std::collections::HashSet<AssetId<Mesh>> = world.query::<(&ArenaModule, &Mesh3d)>().iter(world)
+        .filter(|(m, _)| m.module_id == ModuleId::FloorStd)
+        .map(|(_, mesh)| mesh.id())
+        .collect();
+    assert_eq!(floor_meshes.len(), 1);
+    let unmerged = world.resource::<ArenaManager>().spawned_modules.len();
+
+    // Merged: static cells live in a few chunks whose compound colliders cover every cell
+    let chunk_size = 8;
+    let mut app = headless_app(arena.clone(), ArenaVisualSettings { merge_static_geometry: true, merge_chunk_size: chunk_size, ..default() });
+    app.world_mut().run_system_once(spawn_arena_visuals).unwrap();
+    let world = app.world_mut();
+
+    assert!(world.query::<&ArenaModule>().iter(world).all(|m| !is_static(&m.module_id)));
+    let (mut covered, mut chunks) = (0, 0);
+    for (chunk, collider) in world.query::<(&MergedStaticGeometry, &Collider)>().iter(world) {
+        let compound = collider.as_compound().expect("merged chunks have compound colliders");
+        assert_eq!(compound.shapes().count(), chunk.cells.len());
+        let (cx, cy) = (chunk.cells[0].0.div_euclid(chunk_size as i32), chunk.cells[0].1.div_euclid(chunk_size as i32));
+        assert!(chunk.cells.iter().all(|&(x, y)| (x.div_euclid(chunk_size as i32), y.div_euclid(chunk_size as i32)) == (cx, cy)));
+        covered += chunk.cells.len();
+        chunks += 1;
+    }
+    assert_eq!(covered, static_cells);
+
+    assert!(chunks * 4 < static_cells, "{} chunks for {} static cells", chunks, static_cells);
+    assert_eq!(world.resource::<ArenaManager>().spawned_modules.len(), unmerged - static_cells + chunks);
+}
+
+#[test]
+fn every_database_module_spawns_with_collider_and_state_components() {
+    let database = ModulesDatabase::new();
+    let definitions = database.get_all_modules();
+    // One module of each kind with its database parameters, two cells apart
+    let mut arena = Arena::new(12, 12, 11);
+    for (i, definition) in definitions.iter().enumerate() {
+        arena.set_module(i as i32 % 6 * 2, i as i32 / 6 * 2, definition.id.clone(), definition.parameters.clone());
+    }
+    // A barrier switched off from the start
+    arena.set_module(11, 11, ModuleId::InteractBarrierEnergy, Some(serde_json::json!({"startActive": false})));
+
+    let mut app = headless_app(arena, ArenaVisualSettings { merge_static_geometry: false, ..default() });
+    app.world_mut().run_system_once(spawn_arena_visuals).unwrap();
+    let world = app.world_mut();
+    assert_eq!(world.resource::<ArenaManager>().spawned_modules.len(), definitions.len() + 1);
+
+    for definition in &definitions {
+        let entities: Vec<Entity> = world.query::<(Entity, &ArenaModule)>().iter(world)
+            .filter(|(_, m)| m.module_id == definition.id)
+            .map(|(e, _)| e)
+            .collect();
+        assert!(!entities.is_empty(), "{:?} was not spawned", definition.id);
+
+        for entity in entities {
+            let entity = world.entity(entity);
+            assert!(entity.contains::<Collider>() && entity.contains::<Mesh3d>(), "{:?}", definition.id);
+            let has_state = match definition.id {
+                ModuleId::Player => entity.contains::<PlayerSpawnPad>(),
+                ModuleId::MoveTeleporterIn | ModuleId::MoveTeleporterOut => entity.get::<Teleporter>()
+                    .is_some_and(|t| t.is_entry == (definition.id == ModuleId::MoveTeleporterIn)),
+                ModuleId::MoveClimbSurface => entity.get::<ClimbSurface>().is_some_and(|c| c.climb_speed == 3.0),
+                ModuleId::InteractButtonFloor => entity.get::<TriggerButton>().is_some_and(|b| !b.wall_mounted && b.reset_delay == 0.5),
+                ModuleId::InteractButtonWall => entity.get::<TriggerButton>().is_some_and(|b| b.wall_mounted),
+                ModuleId::InteractLever => entity.get::<Lever>().is_some_and(|l| !l.is_on) && entity.contains::<Children>(),
+                ModuleId::InteractEnemySpawner => entity.get::<EnemySpawner>().is_some_and(|s| s.spawn_limit == 3),
+                ModuleId::InteractBarrierEnergy => entity.contains::<EnergyBarrier>(),
+                _ => true,
+            };
+            assert!(has_state, "{:?} lacks its state component", definition.id);
+        }
+    }
+}
+
+#[test]
+fn barriers_and_levers_spawn_in_their_starting_state() {
+    let database = ModulesDatabase::new();
+    let mut arena = Arena::new(3, 1, 11);
+    arena.set_module(0, 0, ModuleId::InteractBarrierEnergy, Some(serde_json::json!({"startActive": false})));
+    for (x, module_id) in [(1, ModuleId::InteractBarrierEnergy), (2, ModuleId::InteractLever)] {
+        let parameters = database.get_module(&module_id).unwrap().parameters.clone();
+        arena.set_module(x, 0, module_id, parameters);
+    }
+
+    let mut app = headless_app(arena, ArenaVisualSettings { merge_static_geometry: false, ..default() });
+    app.world_mut().run_system_once(spawn_arena_visuals).unwrap();
+    let world = app.world_mut();
+
+    // Only the barrier that starts off is hidden and non-blocking
+    let mut barriers = world.query::<(&EnergyBarrier, &Visibility, Has<ColliderDisabled>)>();
+    let barriers: Vec<_> = barriers.iter(world).map(|(b, v, disabled)| (b.is_active, *v, disabled)).collect();
+    assert!(barriers.contains(&(false, Visibility::Hidden, true)));
+    assert!(barriers.iter().all(|&(active, _, disabled)| active != disabled));
+
+    // The lever handle is a separate, tilted child
+    let mut handles = world.query_filtered::<&Transform, With<LeverHandle>>();
+    let handle = handles.single(world).unwrap();
+    assert!(handle.rotation.angle_between(Quat::IDENTITY) > 0.1);
+}
+
+#[test]
+fn fragile_floors_break_and_respawn_with_their_collider() {
+    let mut arena = Arena::new(3, 1, 0);
+    arena.add_module(0, 0, ModuleId::Player, None);
+    // Zero delays: each system run advances one stage of the cycle
+    arena.add_module(1, 0, ModuleId::FloorFragile, Some(serde_json::json!({"breakDelay": 0.0, "respawnDelay": 0.0})));
+
+    let mut app = headless_app(arena, ArenaVisualSettings { merge_static_geometry: false, ..default() });
+    app.world_mut().run_system_once(spawn_arena_visuals).unwrap();
+    let world = app.world_mut();
+    let entity = world.query_filtered::<Entity, With<FragileSurface>>().single(world).unwrap();
+    let intact_material = world.get::<MeshMaterial3d<StandardMaterial>>(entity).unwrap().0.clone();
+    assert!(!world.entity(entity).contains::<ColliderDisabled>());
+
+    // Stepped on: breaks, hides and stops blocking
+    world.get_mut::<FragileSurface>(entity).unwrap().is_breaking = true;
+    world.run_system_once(fragile_surface_system).unwrap();
+    let broken = world.entity(entity);
+    assert!(broken.get::<FragileSurface>().unwrap().is_broken);
+    assert_eq!(broken.get::<Visibility>(), Some(&Visibility::Hidden));
+    assert!(broken.contains::<ColliderDisabled>() && broken.contains::<Collider>());
+
+    // Reforms solid, with its intact material
+    world.run_system_once(fragile_surface_system).unwrap();
+    let restored = world.entity(entity);
+    let fragile = restored.get::<FragileSurface>().unwrap();
+    assert!(!fragile.is_broken && !fragile.is_breaking);
+    assert_ne!(restored.get::<Visibility>(), Some(&Visibility::Hidden));
+    assert!(!restored.contains::<ColliderDisabled>() && restored.contains::<Collider>());
+    assert_eq!(restored.get::<MeshMaterial3d<StandardMaterial>>().unwrap().0, intact_material);
+}
+
+fn colour_blind_app(colour_blind: bool) -> App {
+    let mut arena = Arena::new(4, 1, 0);
+    for (x, module_id) in [ModuleId::OrbEnergy, ModuleId::HazardLavaPit, ModuleId::HazardLaserEmitterStatic, ModuleId::FloorStd].into_iter().enumerate() {
+        arena.add_module(x as i32, 0, module_id, None);
+    }
+    let mut app = headless_app(arena, ArenaVisualSettings { merge_static_geometry: false, colour_blind, ..default() });
+    app.world_mut().run_system_once(spawn_arena_visuals).unwrap();
+    app
+}
+
+fn patterned_modules(world: &mut World) -> Vec<ModuleId> {
+    let parents: Vec<Entity> = world.query_filtered::<&ChildOf, With<HazardPattern>>().iter(world)
+        .map(|child_of| child_of.parent())
+        .collect();
+    parents.into_iter()
+        .map(|parent| world.get::<ArenaModule>(parent).unwrap().module_id.clone())
+        .collect()
+}
+
+fn orb_vertex_count(world: &mut World) -> usize {
+    let orb = world.query_filtered::<&Mesh3d, With<EnergyOrb>>().single(world).unwrap().0.clone();
+    world.resource::<Assets<Mesh>>().get(&orb).unwrap().count_vertices()
+}
+
+#[test]
+fn colour_blind_mode_stripes_hazards_and_only_hazards() {
+    let mut app = colour_blind_app(true);
+    let patterned = patterned_modules(app.world_mut());
+    assert_eq!(patterned.len(), 2);
+    assert!(patterned.iter().all(|id| matches!(id, ModuleId::HazardLavaPit | ModuleId::HazardLaserEmitterStatic)));
+
+    let mut app = colour_blind_app(false);
+    assert!(patterned_modules(app.world_mut()).is_empty());
+}
+
+#[test]
+fn colour_blind_mode_turns_orbs_into_diamonds() {
+    let diamond = orb_vertex_count(colour_blind_app(true).world_mut());
+    assert_eq!(diamond, 8 * 3);
+    assert_ne!(orb_vertex_count(colour_blind_app(false).world_mut()), diamond);
+}
+
+#[test]
+fn theme_fades_through_a_mix_of_both_palettes_to_the_target() {
+    let moon = ThemePalette::builtin(ThemeId::Moon);
+    let volcanic = ThemePalette::builtin(ThemeId::Volcanic);
+    let mut theme = ActiveTheme::default();
+    theme.set(moon.clone());
+    theme.start_transition(volcanic.clone(), 1.0);
+    assert_eq!(theme.target(), &volcanic);
+    assert!(theme.advance(0.5));
+    assert_ne!(theme.current, moon);
+    assert_ne!(theme.current, volcanic);
+    assert!(theme.advance(0.6));
+    assert_eq!(theme.current, volcanic);
+    assert!(!theme.is_transitioning() && !theme.advance(0.1));
+}
+
+#[test]
+fn procedural_textures_are_deterministic_per_seed() {
+    // Same seed, same pixels; another seed changes only the noise-based textures
+    for kind in TextureKind::ALL {
+        let pixels = kind.generate(32, 7);
+        assert_eq!(pixels.data.len(), 32 * 32 * 4);
+        assert_eq!(pixels, kind.generate(32, 7), "{:?} is not deterministic", kind);
+    }
+    assert_ne!(TextureKind::LavaNoise.generate(32, 7), TextureKind::LavaNoise.generate(32, 8));
+    assert_eq!(TextureKind::Grid.generate(32, 7), TextureKind::Grid.generate(32, 8));
+
+    // Decals fade out before their border
+    let scorch = TextureKind::Scorch.generate(32, 7);
+    assert!(scorch.data.chunks(4).take(32).all(|pixel| pixel[3] == 0));
+}
+
+#[test]
+fn texture_size_stays_within_the_budget() {
+    assert!(texture_set_bytes(budgeted_texture_size(4096)) <= TEXTURE_BUDGET_BYTES);
+    assert_eq!(budgeted_texture_size(100), 128);
+}
+
+fn textured_app() -> App {
+    let mut arena = Arena::new(3, 1, 11);
+    arena.add_module(0, 0, ModuleId::FloorStd, None);
+    arena.add_module(1, 0, ModuleId::HazardLaserEmitterStatic, None);
+    arena.add_module(2, 0, ModuleId::HazardLavaPit, None);
+    let mut app = headless_app(arena, ArenaVisualSettings { merge_static_geometry: false, texture_size: 64, ..default() });
+    app.init_resource::<Assets<Image>>().init_resource::<ProceduralTextures>();
+    app.world_mut().run_system_once(prepare_arena_textures).unwrap();
+    app.world_mut().run_system_once(spawn_arena_visuals).unwrap();
+    app
+}
+
+#[test]
+fn materials_share_textures_prepared_once_per_seed() {
+    let mut app = textured_app();
+    let world = app.world_mut();
+
+    let textures = world.resource::<ProceduralTextures>();
+    let grid = textures.get(TextureKind::Grid).cloned().unwrap();
+    let lava = textures.get(TextureKind::LavaNoise).cloned().unwrap();
+    assert_eq!(world.resource::<Assets<Image>>().len(), TextureKind::ALL.len());
+    let materials = world.resource::<ModuleMaterials>();
+    let assets = world.resource::<Assets<StandardMaterial>>();
+    assert_eq!(assets.get(&materials.floor_std).unwrap().base_color_texture, Some(grid));
+    assert_eq!(assets.get(&materials.hazard_lava).unwrap().base_color_texture, Some(lava));
+
+    // Same seed again: nothing regenerated; another seed replaces the set in place
+    let mut textures = world.remove_resource::<ProceduralTextures>().unwrap();
+    assert!(!textures.prepare(11, 64, &mut world.resource_mut::<Assets<Image>>()));
+    assert!(textures.prepare(12, 64, &mut world.resource_mut::<Assets<Image>>()));
+    assert_eq!(world.resource::<Assets<Image>>().len(), TextureKind::ALL.len());
+}
+
+#[test]
+fn static_emitters_carry_a_scorch_decal() {
+    let mut app = textured_app();
+    let world = app.world_mut();
+    let scorch_material = world.resource::<ModuleMaterials>().scorch_decal.clone();
+    let decals: Vec<Entity> = world.query::<(&ChildOf, &MeshMaterial3d<StandardMaterial>)>().iter(world)
+        .filter(|(_, material)| material.0 == scorch_material)
+        .map(|(child_of, _)| child_of.parent())
+        .collect();
+    assert_eq!(decals.len(), 1);
+    assert_eq!(world.get::<ArenaModule>(decals[0]).unwrap().module_id, ModuleId::HazardLaserEmitterStatic);
+}
+
+#[test]
+fn laser_sweep_bounces_inside_its_arc() {
+    // A 90° arc swept at 90°/s bounces on its edges and never leaves the arc
+    let mut turret = laser_emitter(20.0, 40.0, 90.0, 90.0, 180.0);
+    let mut headings = Vec::new();
+    for _ in 0..40 {
+        headings.push(advance_sweep(&mut turret, 0.1).to_degrees());
+    }
+    assert!(headings.iter().all(|h| (135.0 - 1e-3..=225.0 + 1e-3).contains(h)), "{:?}", headings);
+    assert!(headings.iter().any(|h| *h > 220.0) && headings.iter().any(|h| *h < 140.0));
+
+    let mut fixed = laser_emitter(20.0, 30.0, 0.0, 0.0, 90.0);
+    assert_eq!(advance_sweep(&mut fixed, 1.0), 90f32.to_radians());
+}
+
+/// A static emitter (50/s, 8 long), a default turret and a glass panel; emitters sorted static first
+fn laser_app() -> (App, Vec<(Entity, f32, f32, f32)>) {
+    let mut arena = Arena::new(3, 1, 0);
+    arena.add_module(0, 0, ModuleId::HazardLaserEmitterStatic, Some(serde_json::json!({"damagePerSecond": 50, "beamLength": 8})));
+    arena.add_module(1, 0, ModuleId::HazardLaserTurretRotate, None);
+    arena.add_module(2, 0, ModuleId::PanelGlass, None);
+    let mut app = headless_app(arena, ArenaVisualSettings { merge_static_geometry: false, ..default() });
+    app.world_mut().run_system_once(spawn_arena_visuals).unwrap();
+    let world = app.world_mut();
+    let mut emitters: Vec<(Entity, f32, f32, f32)> = world.query::<(Entity, &LaserEmitter, &DynamicHazard)>().iter(world)
+        .map(|(entity, emitter, _)| (entity, emitter.beam_length, emitter.damage_per_second, emitter.rotation_speed))
+        .collect();
+    emitters.sort_by(|a, b| a.3.total_cmp(&b.3));
+    (app, emitters)
+}
+
+fn beam_of(world: &World, emitter: Entity) -> Vec<Entity> {
+    world.get::<Children>(emitter).unwrap().iter().copied()
+        .filter(|&child| world.entity(child).contains::<LaserBeam>())
+        .collect()
+}
+
+#[test]
+fn laser_emitters_spawn_with_their_parameters_and_one_beam() {
+    let (mut app, emitters) = laser_app();
+    let world = app.world_mut();
+    assert_eq!(emitters.len(), 2);
+    assert_eq!((emitters[0].1, emitters[0].2, emitters[0].3), (8.0, 50.0, 0.0));
+    assert_eq!((emitters[1].1, emitters[1].2), (20.0, 40.0));
+    assert!(emitters[1].3 > 0.0);
+    for &(entity, ..) in &emitters {
+        assert_eq!(beam_of(world, entity).len(), 1);
+    }
+    // Glass lets beams through
+    assert_eq!(world.query_filtered::<&ArenaModule, With<BeamPassThrough>>().iter(world).count(), 1);
+}
+
+#[test]
+fn laser_beam_drains_health_and_reports_a_single_death() {
+    let (mut app, emitters) = laser_app();
+    let world = app.world_mut();
+    let player = spawn_player(world, 30.0);
+    for &(entity, ..) in &emitters {
+        world.get_mut::<LaserEmitter>(entity).unwrap().hitting_player = entity == emitters[0].0;
+    }
+    world.init_resource::<Events<PlayerDeathEvent>>();
+    advance(world, 0.5);
+    world.run_system_once(laser_damage_system).unwrap();
+    let expected = 30.0 - emitters[0].2 * 0.5;
+    assert!((world.get::<Player>(player).unwrap().health - expected).abs() < 1e-4);
+    world.run_system_once(laser_damage_system).unwrap();
+    world.run_system_once(laser_damage_system).unwrap();
+    assert_eq!(world.resource::<Events<PlayerDeathEvent>>().len(), 1);
+}
+
+#[test]
+fn touching_a_laser_emitter_does_no_contact_damage() {
+    let (mut app, emitters) = laser_app();
+    app.add_event::<CollisionEvent>()
+        .add_event::<OrbCollectedEvent>()
+        .add_event::<PlayerDeathEvent>();
+    let world = app.world_mut();
+    let player = spawn_player(world, 30.0);
+    for &(entity, ..) in &emitters {
+        world.send_event(CollisionEvent::Started(player, entity, CollisionEventFlags::empty()));
+    }
+    world.run_system_once(player_collision_system).unwrap();
+    assert_eq!(world.get::<Player>(player).unwrap().health, 30.0);
+    assert!(world.resource::<Events<PlayerDeathEvent>>().is_empty());
+}
+
+#[test]
+fn laser_beam_visual_follows_the_clipped_length() {
+    let (mut app, emitters) = laser_app();
+    let world = app.world_mut();
+    world.get_mut::<LaserEmitter>(emitters[0].0).unwrap().current_length = 3.0;
+    world.run_system_once(laser_beam_visual_system).unwrap();
+    let beam = beam_of(world, emitters[0].0)[0];
+    let transform = world.get::<Transform>(beam).unwrap();
+    assert_eq!((transform.scale.x, transform.translation.x), (3.0, 1.5));
+}
+
+/// A projectile rain arena with its schedule set and its pool filled
+fn projectile_app() -> App {
+    let config = GenerationConfig::normal().with_fixed_rules(vec![RuleId::ProjectileRain]);
+    let arena = ArenaGenerator::new(Some(3)).generate(&config, &mut AnomalyMonitor::new()).unwrap();
+    let mut app = headless_app(arena, ArenaVisualSettings { merge_static_geometry: false, ..default() });
+    app.insert_resource(gameplay::DifficultySystem::new())
+        .init_resource::<ProjectileRain>()
+        .add_event::<CollisionEvent>()
+        .add_event::<OrbCollectedEvent>()
+        .add_event::<PlayerDeathEvent>();
+    app.world_mut().run_system_once(projectile_schedule_system).unwrap();
+    app
+}
+
+fn schedule_of(world: &World) -> ProjectileSchedule {
+    world.resource::<ProjectileRain>().schedule.clone().unwrap()
+}
+
+/// Launches the first volley: the projectiles in flight, with their position and marker
+fn launch_first_volley(world: &mut World) -> Vec<(Entity, Vec3, Entity)> {
+    advance(world, schedule_of(world).interval + 0.01);
+    world.run_system_once(projectile_spawn_system).unwrap();
+    world.query::<(Entity, &Projectile, &Transform)>().iter(world)
+        .filter(|(_, projectile, _)| projectile.active)
+        .map(|(entity, projectile, transform)| (entity, transform.translation, projectile.indicator))
+        .collect()
+}
+
+#[test]
+fn projectile_pool_is_filled_up_front_parked_and_without_collisions() {
+    let mut app = projectile_app();
+    let world = app.world_mut();
+    // A second pass on the same arena changes nothing
+    world.run_system_once(projectile_schedule_system).unwrap();
+    let pool_size = world.resource::<ProjectileRain>().pool_size;
+    assert_eq!(pool_size, schedule_of(world).max_projectiles_in_flight());
+    assert_eq!(world.resource::<ProjectileRain>().free_count(), pool_size);
+    assert_eq!(world.query_filtered::<(), (With<Projectile>, With<ColliderDisabled>, With<Sensor>)>().iter(world).count(), pool_size);
+    assert_eq!(world.query::<&ImpactIndicator>().iter(world).count(), pool_size);
+}
+
+#[test]
+fn projectile_volleys_fall_onto_their_marked_cells() {
+    let mut app = projectile_app();
+    let world = app.world_mut();
+    let pool_size = world.resource::<ProjectileRain>().pool_size;
+
+    // A due volley takes projectiles out of the pool, high above their marked cells
+    let in_flight = launch_first_volley(world);
+    assert!(!in_flight.is_empty());
+    assert_eq!(world.resource::<ProjectileRain>().free_count(), pool_size - in_flight.len());
+    for &(entity, position, indicator) in &in_flight {
+        assert!(position.y >= DROP_HEIGHT);
+        assert!(!world.entity(entity).contains::<ColliderDisabled>());
+        assert_eq!(world.get::<Visibility>(indicator), Some(&Visibility::Inherited));
+        let marker = world.get::<Transform>(indicator).unwrap().translation;
+        assert_eq!((marker.x, marker.z), (position.x, position.z));
+    }
+
+    // Halfway through the warning they are lower and their markers larger
+    advance(world, schedule_of(world).telegraph * 0.5);
+    world.run_system_once(projectile_fall_system).unwrap();
+    let (first, _, first_indicator) = in_flight[0];
+    let height = world.get::<Transform>(first).unwrap().translation.y;
+    assert!(height > 0.0 && height < DROP_HEIGHT);
+    assert!(world.get::<Transform>(first_indicator).unwrap().scale.x > 0.5);
+}
+
+#[test]
+fn projectile_hit_damages_once_and_returns_to_the_pool() {
+    let mut app = projectile_app();
+    let world = app.world_mut();
+    let damage = schedule_of(world).damage;
+    let (first, _, first_indicator) = launch_first_volley(world)[0];
+
+    let player = spawn_player(world, damage * 0.5);
+    for _ in 0..2 {
+        world.send_event(CollisionEvent::Started(player, first, CollisionEventFlags::SENSOR));
+        world.run_system_once(player_collision_system).unwrap();
+    }
+    assert!((world.get::<Player>(player).unwrap().health + damage * 0.5).abs() < 1e-4);
+    assert_eq!(world.resource::<Events<PlayerDeathEvent>>().len(), 1);
+    world.run_system_once(projectile_fall_system).unwrap();
+    assert!(world.entity(first).contains::<ColliderDisabled>());
+    assert_eq!(world.get::<Visibility>(first_indicator), Some(&Visibility::Hidden));
+}
+
+#[test]
+fn long_projectile_rain_never_grows_the_pool() {
+    let mut app = projectile_app();
+    let world = app.world_mut();
+    let pool_size = world.resource::<ProjectileRain>().pool_size;
+    for _ in 0..200 {
+        advance(world, 0.1);
+        world.run_system_once(projectile_spawn_system).unwrap();
+        world.run_system_once(projectile_fall_system).unwrap();
+    }
+    assert_eq!(world.resource::<ProjectileRain>().pool_size, pool_size);
+    assert_eq!(world.query::<&Projectile>().iter(world).count(), pool_size);
+}
+
+/// A merged 5×3 floor around `pits`, with the `LavaFloor` rule at `intensity` if given
+fn lava_app(pits: &[(i32, i32)], intensity: Option<f32>) -> App {
+    let mut arena = Arena::new(5, 3, 7);
+    for x in 0..5 {
+        for y in 0..3 {
+            arena.add_module(x, y, ModuleId::FloorStd, None);
+        }
+    }
+    for &(x, y) in pits {
+        arena.set_module(x, y, ModuleId::HazardLavaPit, None);
+    }
+    if let Some(intensity) = intensity {
+        let mut rule = RulesDatabase::new().get_rule(&RuleId::LavaFloor).unwrap().clone();
+        rule.parameters = Some(serde_json::json!({"intensity": intensity}));
+        arena.active_rules.push(rule);
+    }
+
+    let mut app = headless_app(arena, ArenaVisualSettings { merge_static_geometry: true, ..default() });
+    app.insert_resource(gameplay::DangerPressureSystem::new())
+        .init_resource::<LavaDynamics>()
+        .add_event::<CollisionEvent>()
+        .add_event::<OrbCollectedEvent>()
+        .add_event::<PlayerDeathEvent>();
+    app.world_mut().run_system_once(spawn_arena_visuals).unwrap();
+    app
+}
+
+fn lava_count(world: &World) -> usize {
+    world.resource::<ArenaManager>().current_arena.as_ref().unwrap().count_modules_by_type(&ModuleId::HazardLavaPit)
+}
+
+/// Runs the flow until the lava has overflowed onto at least two cells
+fn spread_lava(world: &mut World) {
+    for _ in 0..20 {
+        advance(world, 0.25);
+        world.run_system_once(lava_flow_system).unwrap();
+        if lava_count(world) > 2 {
+            return;
+        }
+    }
+    panic!("the lava never spread");
+}
+
+#[test]
+fn lava_spread_rebuilds_merged_floors_around_it() {
+    let mut app = lava_app(&[(2, 1)], Some(20.0));
+    let world = app.world_mut();
+    spread_lava(world);
+
+    // The arena data and the entities move together; merged floors are rebuilt around the lava
+    let arena = world.resource::<ArenaManager>().current_arena.clone().unwrap();
+    let lava_entities: Vec<(i32, i32)> = world.query_filtered::<&ArenaModule, With<LavaPit>>().iter(world)
+        .map(|module| ArenaManager::world_to_cell(module.original_position))
+        .collect();
+    assert_eq!(lava_entities.len(), arena.count_modules_by_type(&ModuleId::HazardLavaPit));
+    assert!(lava_entities.iter().all(|&(x, y)| arena.get_cell(x, y).unwrap().module_id == ModuleId::HazardLavaPit));
+    let merged_cells: Vec<(i32, i32)> = world.query::<&MergedStaticGeometry>().iter(world).flat_map(|chunk| chunk.cells.clone()).collect();
+    assert_eq!(merged_cells.len(), arena.count_modules_by_type(&ModuleId::FloorStd));
+    assert!(merged_cells.iter().all(|cell| !lava_entities.contains(cell)));
+    let spawned = world.resource::<ArenaManager>().spawned_modules.clone();
+    assert!(spawned.iter().all(|&entity| world.get_entity(entity).is_ok()));
+}
+
+#[test]
+fn lava_burns_while_touched_and_two_tiles_burn_no_faster_than_one() {
+    let mut app = lava_app(&[(1, 1), (2, 1)], None);
+    let world = app.world_mut();
+    let player = spawn_player(world, 100.0);
+    let tiles: Vec<Entity> = world.query_filtered::<Entity, With<LavaPit>>().iter(world).collect();
+    assert_eq!(tiles.len(), 2);
+    for &tile in &tiles {
+        world.send_event(CollisionEvent::Started(player, tile, CollisionEventFlags::empty()));
+    }
+    world.run_system_once(player_collision_system).unwrap();
+    assert!(tiles.iter().all(|&tile| world.get::<LavaPit>(tile).unwrap().touching_player));
+
+    // Contact burns every frame until it stops
+    advance(world, 0.5);
+    world.run_system_once(lava_damage_system).unwrap();
+    world.run_system_once(lava_damage_system).unwrap();
+    assert!((world.get::<Player>(player).unwrap().health - 75.0).abs() < 1e-3);
+    world.resource_mut::<Events<CollisionEvent>>().clear();
+    for &tile in &tiles {
+        world.send_event(CollisionEvent::Stopped(player, tile, CollisionEventFlags::empty()));
+    }
+    world.run_system_once(player_collision_system).unwrap();
+    world.run_system_once(lava_damage_system).unwrap();
+    assert!((world.get::<Player>(player).unwrap().health - 75.0).abs() < 1e-3);
+}
+
+#[test]
+fn lava_overflow_cools_back_to_floor_without_the_rule() {
+    let mut app = lava_app(&[(2, 1)], Some(20.0));
+    let world = app.world_mut();
+    spread_lava(world);
+
+    // Only the original pit is left, and the floor merges back around it
+    world.resource_mut::<ArenaManager>().current_arena.as_mut().unwrap().active_rules.clear();
+    advance(world, LAVA_COOL_TIME + 0.1);
+    world.run_system_once(lava_flow_system).unwrap();
+    assert_eq!(lava_count(world), 1);
+    assert_eq!(world.query::<&LavaPit>().iter(world).count(), 1);
+    assert_eq!(world.query::<&MergedStaticGeometry>().iter(world).map(|chunk| chunk.cells.len()).sum::<usize>(), 14);
+}
+
+#[test]
+fn pregenerated_arena_replaces_the_current_one_at_shift_end_unless_stale() {
+    let generate = |seed: u64| ArenaGenerator::new(Some(seed))
+        .generate_until_valid(seed, &GenerationConfig::normal(), &RetryPolicy::default(), &mut AnomalyMonitor::new())
+        .unwrap();
+    let first = generate(1).arena;
+    let next = generate(2);
+    let next_id = next.arena.id;
+    let next_spawn = next.arena.get_player_position().unwrap();
+
+    let mut app = headless_app(first.clone(), ArenaVisualSettings { merge_static_geometry: false, texture_size: 32, ..default() });
+    app.init_resource::<Assets<Image>>()
+        .init_resource::<ProceduralTextures>()
+        .init_resource::<ActiveTheme>()
+        .insert_resource(gameplay::DifficultySystem::new())
+        .init_resource::<ArenaGenerationTasks>()
+        .add_event::<ShiftEndEvent>();
+    app.world_mut().run_system_once(spawn_arena_visuals).unwrap();
+    let world = app.world_mut();
+    let old_modules = world.resource::<ArenaManager>().spawned_modules.clone();
+    let player = spawn_player(world, 100.0);
+    world.entity_mut(player).insert(Transform::default());
+
+    let target = world.resource::<gameplay::DifficultySystem>().get_arena_difficulty_target();
+    let end_shift = |world: &mut World| {
+        world.send_event(ShiftEndEvent { shift_number: 1, orbs_collected: 0, orbs_uncollected: 0 });
+        world.run_system_once(swap_arena_on_shift_end).unwrap();
+    };
+
+    // Still on target: swapped in, old modules gone, player on the new spawn, next one already started
+    world.resource_mut::<ArenaGenerationTasks>().pregenerated = Some(PregeneratedArena { outcome: next, difficulty_target: target });
+    end_shift(world);
+    assert_eq!(world.resource::<ArenaManager>().current_arena.as_ref().unwrap().id, next_id);
+    assert!(old_modules.iter().all(|&entity| world.get_entity(entity).is_err()));
+    let spawned = world.resource::<ArenaManager>().spawned_modules.clone();
+    assert!(!spawned.is_empty());
+    assert_eq!(world.query::<&ArenaModule>().iter(world).count(), spawned.len());
+    let position = world.get::<Transform>(player).unwrap().translation;
+    assert_eq!((position.x, position.z), (next_spawn.0 as f32, next_spawn.1 as f32));
+    let tasks = world.resource::<ArenaGenerationTasks>();
+    assert!(tasks.pregenerated.is_none());
+    assert!(tasks.next_shift.as_ref().is_some_and(|task| task.difficulty_target == target));
+
+    // Generated for a difficulty since left behind: thrown away, and the generation restarted on target
+    let stale = generate(3);
+    {
+        let mut tasks = world.resource_mut::<ArenaGenerationTasks>();
+        tasks.pregenerated = Some(PregeneratedArena { outcome: stale, difficulty_target: target + 0.5 });
+        tasks.next_shift = Some(ArenaGenerationTask::spawn(target + 0.5));
+    }
+    end_shift(world);
+    assert_eq!(world.resource::<ArenaManager>().current_arena.as_ref().unwrap().id, next_id);
+    assert_eq!(world.resource::<ArenaManager>().spawned_modules, spawned);
+    let tasks = world.resource::<ArenaGenerationTasks>();
+    assert!(tasks.pregenerated.is_none());
+    assert!(tasks.next_shift.as_ref().is_some_and(|task| task.difficulty_target == target));
+}
+
+fn float3(mesh: &Mesh, attribute: impl Into<MeshVertexAttributeId>) -> Vec<Vec3> {
+    match mesh.attribute(attribute) {
+        Some(VertexAttributeValues::Float32x3(values)) => values.iter().map(|&v| Vec3::from_array(v)).collect(),
+        other => panic!("unexpected attribute {:?}", other.map(|v| v.len())),
+    }
+}
+
+/// Every triangle agrees with its vertex normals; for convex shapes it also faces away from the middle
+fn assert_faces_outward(name: &str, mesh: &Mesh, convex: bool) {
+    let positions = float3(mesh, Mesh::ATTRIBUTE_POSITION);
+    let normals = float3(mesh, Mesh::ATTRIBUTE_NORMAL);
+    assert!(mesh.attribute(Mesh::ATTRIBUTE_UV_0).is_some(), "{} has no UVs", name);
+    assert!(mesh.attribute(Mesh::ATTRIBUTE_TANGENT).is_some(), "{} has no tangents", name);
+    assert!(normals.iter().all(|n| (n.length() - 1.0).abs() < 1e-4), "{} has non-unit normals", name);
+
+    let center = positions.iter().copied().sum::<Vec3>() / positions.len() as f32;
+    let indices: Vec<usize> = mesh.indices().expect("indexed").iter().collect();
+    for triangle in indices.chunks(3) {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|i| positions[i]);
+        let face = (b - a).cross(c - a);
+        if face.length() < 1e-6 {
+            continue;
+        }
+        for &i in triangle {
+            assert!(face.dot(normals[i]) > 0.0, "{}: triangle {:?} disagrees with its normals", name, triangle);
+        }
+        if convex {
+            assert!(face.dot((a + b + c) / 3.0 - center) > 0.0, "{}: triangle {:?} faces inward", name, triangle);
+        }
+    }
+}
+
+#[test]
+fn procedural_meshes_face_outward_with_matching_colliders() {
+    assert_faces_outward("cube", &cube_mesh(1.0, 0.1, 1.0), true);
+    assert_faces_outward("beveled floor", &box_mesh(Vec3::new(1.0, 0.1, 1.0), 0.02), true);
+    assert_faces_outward("sphere", &sphere_mesh(0.3, 16), true);
+    assert_faces_outward("cylinder", &cylinder_mesh(0.4, 1.0, 12), true);
+    assert_faces_outward("arch", &arch_mesh(2.0, 3.0, 0.3), false);
+    assert_faces_outward("octahedron", &octahedron_mesh(0.35), true);
+    assert_faces_outward("hazard stripes", &hazard_stripes_mesh(1.0, 1.0), false);
+    assert_faces_outward("decal", &decal_mesh(1.8), false);
+
+    // Bevels add the four chamfers to the six faces of a cube
+    assert_eq!(cube_mesh(1.0, 0.1, 1.0).indices().unwrap().len(), 6 * 6);
+    assert_eq!(box_mesh(Vec3::new(1.0, 0.1, 1.0), 0.02).indices().unwrap().len(), 10 * 6);
+
+    for degrees in [30.0_f32, 45.0] {
+        let angle = degrees.to_radians();
+        let mesh = ramp_mesh(1.0, angle);
+        assert_faces_outward("ramp", &mesh, true);
+
+        // The hull spans exactly the wedge
+        let collider = ramp_collider(1.0, angle);
+        assert!(collider.as_convex_polyhedron().is_some());
+        let aabb = collider.raw.compute_local_aabb();
+        assert!((aabb.maxs.y - aabb.mins.y - ramp_height(1.0, angle)).abs() < 1e-4);
+        let top = float3(&mesh, Mesh::ATTRIBUTE_POSITION).iter().map(|p| p.y).fold(f32::MIN, f32::max);
+        assert!((top - aabb.maxs.y).abs() < 1e-4);
+    }
+    assert!(ramp_height(1.0, 45f32.to_radians()) > ramp_height(1.0, 30f32.to_radians()));
+
+    // Pillars plus one hull per vault segment
+    let arch = arch_collider(2.0, 3.0, 0.3);
+    let compound = arch.as_compound().expect("arch collider is compound");
+    assert!(compound.shapes().count() > 2);
+}